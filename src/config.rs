@@ -1,8 +1,9 @@
 use serde::{Deserialize, Serialize};
 use std::fs;
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, USER_AGENT};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ServiceConfig {
     pub server: ServerConfig,
     pub hyperbeam: HyperbeamConfig,
@@ -11,60 +12,389 @@ pub struct ServiceConfig {
     pub limits: LimitsConfig,
     pub ui: UiConfig,
     pub logging: LoggingConfig,
+    #[serde(default)]
+    pub reconcile: ReconcileConfig,
+    #[serde(default)]
+    pub reserves: ReservesConfig,
+    /// Also accepted as `[persistence]`
+    #[serde(default, alias = "persistence")]
+    pub state: StateConfig,
+    #[serde(default)]
+    pub notifications: NotificationsConfig,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ServerConfig {
     pub port: u16,
     pub host: String,
+    /// Serve on this Unix domain socket instead of `host`:`port` (Unix only)
+    #[serde(default)]
+    pub unix_socket: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct HyperbeamConfig {
     pub base_url: String,
+    /// Additional nodes; processes without their own `base_url` are spread across
+    /// `base_url` and these by process ID
+    #[serde(default)]
+    pub base_urls: Vec<String>,
+    /// Upper bound on in-flight requests to HyperBEAM and the AO CU
+    #[serde(default = "default_max_concurrent_requests")]
+    pub max_concurrent_requests: usize,
+    /// Total time allowed for a single request
+    #[serde(default = "default_timeout_secs")]
+    pub request_timeout_secs: u64,
+    /// Time allowed to establish a connection
+    #[serde(default = "default_timeout_secs")]
+    pub connect_timeout_secs: u64,
+    /// Total time allowed for a reserves request (HyperBEAM or AO dry-run), replacing
+    /// `request_timeout_secs` so slot reads can use a tighter limit
+    #[serde(default = "default_timeout_secs")]
+    pub reserves_timeout_secs: u64,
+    /// User-Agent sent to HyperBEAM and the AO CU instead of reqwest's default
+    #[serde(default)]
+    pub user_agent: Option<String>,
+    /// Extra header sent with every request to HyperBEAM and the AO CU, for gateways
+    /// that require a token
+    #[serde(default)]
+    pub auth_header: Option<AuthHeader>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuthHeader {
+    pub name: String,
+    pub value: String,
+}
+
+impl std::fmt::Debug for AuthHeader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AuthHeader")
+            .field("name", &self.name)
+            .field("value", &"<redacted>")
+            .finish()
+    }
+}
+
+fn default_max_concurrent_requests() -> usize {
+    10
+}
+
+fn default_timeout_secs() -> u64 {
+    30
+}
+
+impl HyperbeamConfig {
+    /// All configured nodes, `base_url` first, without duplicates.
+    pub fn nodes(&self) -> Vec<String> {
+        let mut nodes = vec![self.base_url.clone()];
+        for url in &self.base_urls {
+            if !nodes.contains(url) {
+                nodes.push(url.clone());
+            }
+        }
+        nodes
+    }
+
+    /// Headers added to every request to HyperBEAM and the AO CU.
+    pub fn default_headers(&self) -> Result<HeaderMap> {
+        let mut headers = HeaderMap::new();
+        if let Some(user_agent) = &self.user_agent {
+            let value = HeaderValue::from_str(user_agent)
+                .map_err(|e| anyhow!("hyperbeam.user_agent is not a valid header value: {}", e))?;
+            headers.insert(USER_AGENT, value);
+        }
+        if let Some(auth) = &self.auth_header {
+            let name = HeaderName::from_bytes(auth.name.as_bytes())
+                .map_err(|e| anyhow!("hyperbeam.auth_header.name is not a valid header name ({}): {}", auth.name, e))?;
+            let mut value = HeaderValue::from_str(&auth.value)
+                .map_err(|e| anyhow!("hyperbeam.auth_header.value is not a valid header value: {}", e))?;
+            value.set_sensitive(true);
+            headers.insert(name, value);
+        }
+        Ok(headers)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct AoConfig {
     pub cu_url: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MonitoringConfig {
     pub cron_list_interval: u64,
     pub queue_slots_interval: u64,
     pub synced_pools_interval: u64,
     pub monitor_loop_interval: u64,
     pub queue_slots_delay: u64,
+    /// Slot readings implying a faster advancement than this are discarded
+    #[serde(default = "default_max_slots_per_minute")]
+    pub max_slots_per_minute: f64,
+    /// Re-read computed then current slot sequentially before marking a process synced
+    #[serde(default)]
+    pub confirm_synced_read: bool,
+    /// Requeue synced pools whose deficit grows beyond `desync_threshold`
+    #[serde(default)]
+    pub requeue_on_desync: bool,
+    #[serde(default = "default_desync_threshold")]
+    pub desync_threshold: u64,
+    /// Weight of the newest sample in the moving average sync rate, from 0 to 1
+    #[serde(default = "default_rate_smoothing")]
+    pub rate_smoothing: f64,
+    /// A process counts as synced when its computed slot is at most this many slots behind
+    #[serde(default)]
+    pub sync_tolerance: u64,
+    /// A computed slot falling back by more than this many slots resets the sync rate baseline
+    #[serde(default = "default_slot_regression_threshold")]
+    pub slot_regression_threshold: u64,
+    /// Consecutive checks without progress, despite a deficit, before a process is flagged
+    /// as stalled; 0 disables the check
+    #[serde(default = "default_stall_threshold")]
+    pub stall_threshold: u64,
+    /// Number of recent API response times kept per process for latency percentiles
+    #[serde(default = "default_response_time_window")]
+    pub response_time_window: usize,
+    /// Fetch and compare HB and AO reserves for synced pools
+    #[serde(default = "default_true")]
+    pub fetch_reserves: bool,
+}
+
+fn default_response_time_window() -> usize {
+    20
+}
+
+fn default_stall_threshold() -> u64 {
+    10
+}
+
+fn default_rate_smoothing() -> f64 {
+    0.3
+}
+
+fn default_slot_regression_threshold() -> u64 {
+    10
+}
+
+fn default_desync_threshold() -> u64 {
+    100
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+fn default_max_slots_per_minute() -> f64 {
+    100_000.0
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct LimitsConfig {
     pub max_active_processes: usize,
     pub queue_preview_limit: usize,
     pub queue_check_limit: usize,
+    /// Maximum number of queued processes; 0 means unlimited
+    #[serde(default)]
+    pub max_queue_size: usize,
+    /// Consecutive failed cron initializations before a process is given up on; 0 means unlimited
+    #[serde(default)]
+    pub max_init_attempts: u64,
+    /// When non-empty, only these process IDs may be tracked
+    #[serde(default)]
+    pub process_allowlist: Vec<String>,
+    /// Process IDs that are never tracked
+    #[serde(default)]
+    pub process_denylist: Vec<String>,
+    /// Order in which queued processes are activated
+    #[serde(default)]
+    pub queue_strategy: QueueStrategy,
+    /// Reject `/api/queue/add` requests whose name is already used by a tracked process
+    #[serde(default)]
+    pub enforce_unique_names: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QueueStrategy {
+    /// Activate in queue order
+    #[default]
+    Fifo,
+    /// Activate the queued process with the smallest known deficit first, so quick wins finish early
+    SmallestDeficitFirst,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct UiConfig {
     pub refresh_interval: u64,
+    /// Reserve keys hidden from the web UI on top of `reserves.filter.ignored_keys`
+    #[serde(default)]
+    pub ignored_reserve_keys: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct LoggingConfig {
     pub level: String,
     pub format: String,
 }
 
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReconcileConfig {
+    /// Move already-synced processes that reappear in the pool config back to the queue
+    #[serde(default)]
+    pub requeue_synced_on_config: bool,
+    /// What to do on startup with active processes whose cron was never initialized
+    #[serde(default)]
+    pub uninitialized_active: UninitializedActivePolicy,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UninitializedActivePolicy {
+    #[default]
+    Reinitialize,
+    Requeue,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReservesConfig {
+    /// Fetch HB and AO reserves concurrently; when false they run one after the other
+    #[serde(default = "default_true")]
+    pub concurrent_fetch: bool,
+    /// When fetching sequentially, skip the AO fetch if the HB fetch failed
+    #[serde(default)]
+    pub skip_ao_on_hb_failure: bool,
+    #[serde(default)]
+    pub filter: ReserveFilter,
+}
+
+impl Default for ReservesConfig {
+    fn default() -> Self {
+        Self {
+            concurrent_fetch: true,
+            skip_ao_on_hb_failure: false,
+            filter: ReserveFilter::default(),
+        }
+    }
+}
+
+/// Decides which reserve keys are token IDs, as opposed to pool metadata.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReserveFilter {
+    /// Length of a token process ID
+    #[serde(default = "default_token_id_length")]
+    pub token_id_length: usize,
+    /// Keys that are never treated as tokens
+    #[serde(default = "default_ignored_reserve_keys")]
+    pub ignored_keys: Vec<String>,
+}
+
+impl ReserveFilter {
+    pub fn is_token_key(&self, key: &str) -> bool {
+        key.len() == self.token_id_length && !self.ignored_keys.iter().any(|ignored| ignored == key)
+    }
+
+    /// This filter with `extra` keys ignored as well.
+    pub fn ignoring(&self, extra: &[String]) -> ReserveFilter {
+        let mut filter = self.clone();
+        filter.ignored_keys.extend(extra.iter().cloned());
+        filter
+    }
+}
+
+impl Default for ReserveFilter {
+    fn default() -> Self {
+        Self {
+            token_id_length: default_token_id_length(),
+            ignored_keys: default_ignored_reserve_keys(),
+        }
+    }
+}
+
+fn default_token_id_length() -> usize {
+    43
+}
+
+fn default_ignored_reserve_keys() -> Vec<String> {
+    ["TokenA", "TokenB", "K"].into_iter().map(String::from).collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StateConfig {
+    #[serde(default)]
+    pub backend: StateBackend,
+    /// JSON state file, also the migration source for the SQLite backend
+    #[serde(default = "default_state_path")]
+    pub path: String,
+    /// Database file used by the SQLite backend
+    #[serde(default = "default_sqlite_path")]
+    pub sqlite_path: String,
+    /// Save recent API response times so latency percentiles survive a restart
+    #[serde(default)]
+    pub persist_response_times: bool,
+    /// Write the JSON state without indentation
+    #[serde(default)]
+    pub compact: bool,
+    /// Gzip the JSON state, written to `path` with a `.gz` suffix
+    #[serde(default)]
+    pub gzip: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StateBackend {
+    #[default]
+    Json,
+    Sqlite,
+}
+
+impl Default for StateConfig {
+    fn default() -> Self {
+        Self {
+            backend: StateBackend::default(),
+            path: default_state_path(),
+            sqlite_path: default_sqlite_path(),
+            persist_response_times: false,
+            compact: false,
+            gzip: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct NotificationsConfig {
+    /// Receives a JSON POST whenever a process finishes syncing
+    #[serde(default)]
+    pub webhook_url: Option<String>,
+    /// Also POST to `webhook_url` when a process is flagged as stalled
+    #[serde(default)]
+    pub notify_on_stall: bool,
+}
+
+fn default_state_path() -> String {
+    "hydration-state.json".to_string()
+}
+
+fn default_sqlite_path() -> String {
+    "hydration-state.db".to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
 impl Default for ServiceConfig {
     fn default() -> Self {
         Self {
             server: ServerConfig {
                 port: 8080,
                 host: "0.0.0.0".to_string(),
+                unix_socket: None,
             },
             hyperbeam: HyperbeamConfig {
                 base_url: "http://65.108.7.125:8734".to_string(),
+                base_urls: Vec::new(),
+                max_concurrent_requests: default_max_concurrent_requests(),
+                request_timeout_secs: default_timeout_secs(),
+                connect_timeout_secs: default_timeout_secs(),
+                reserves_timeout_secs: default_timeout_secs(),
+                user_agent: None,
+                auth_header: None,
             },
             ao: AoConfig {
                 cu_url: "https://cu.ao-testnet.xyz".to_string(),
@@ -75,37 +405,359 @@ impl Default for ServiceConfig {
                 synced_pools_interval: 60,
                 monitor_loop_interval: 15,
                 queue_slots_delay: 10,
+                max_slots_per_minute: default_max_slots_per_minute(),
+                confirm_synced_read: false,
+                requeue_on_desync: false,
+                desync_threshold: default_desync_threshold(),
+                rate_smoothing: default_rate_smoothing(),
+                sync_tolerance: 0,
+                slot_regression_threshold: default_slot_regression_threshold(),
+                stall_threshold: default_stall_threshold(),
+                response_time_window: default_response_time_window(),
+                fetch_reserves: true,
             },
             limits: LimitsConfig {
                 max_active_processes: 5,
                 queue_preview_limit: 10,
                 queue_check_limit: 20,
+                max_queue_size: 0,
+                max_init_attempts: 0,
+                process_allowlist: Vec::new(),
+                process_denylist: Vec::new(),
+                queue_strategy: QueueStrategy::default(),
+                enforce_unique_names: false,
             },
             ui: UiConfig {
                 refresh_interval: 5,
+                ignored_reserve_keys: Vec::new(),
             },
             logging: LoggingConfig {
                 level: "info".to_string(),
                 format: "full".to_string(),
             },
+            reconcile: ReconcileConfig::default(),
+            reserves: ReservesConfig::default(),
+            state: StateConfig::default(),
+            notifications: NotificationsConfig::default(),
         }
     }
 }
 
 impl ServiceConfig {
+    /// Load the configuration. Precedence: defaults < config.toml < environment.
     pub fn load() -> Result<Self> {
         // Try to load from config.toml, fall back to defaults if not found
-        if let Ok(contents) = fs::read_to_string("config.toml") {
-            let config: ServiceConfig = toml::from_str(&contents)?;
-            Ok(config)
+        let mut config = if let Ok(contents) = fs::read_to_string("config.toml") {
+            toml::from_str(&contents)?
+        } else {
+            Self::default()
+        };
+        
+        config.apply_env_overrides()?;
+        config.validate()?;
+        Ok(config)
+    }
+    
+    /// Check for values that would leave the service unable to do anything useful,
+    /// reporting every problem found rather than just the first.
+    pub fn validate(&self) -> Result<()> {
+        let mut problems = Vec::new();
+        
+        if self.server.port == 0 {
+            problems.push("server.port must be greater than 0".to_string());
+        }
+        if self.server.host.trim().is_empty() {
+            problems.push("server.host must not be empty".to_string());
+        }
+        if cfg!(not(unix)) && self.server.unix_socket.is_some() {
+            problems.push("server.unix_socket is only supported on Unix".to_string());
+        }
+        let urls = [("hyperbeam.base_url", &self.hyperbeam.base_url), ("ao.cu_url", &self.ao.cu_url)]
+            .into_iter()
+            .chain(self.hyperbeam.base_urls.iter().map(|url| ("hyperbeam.base_urls", url)));
+        for (field, url) in urls {
+            if url.trim().is_empty() {
+                problems.push(format!("{} must not be empty", field));
+            } else if let Err(e) = reqwest::Url::parse(url) {
+                problems.push(format!("{} is not a valid URL ({}): {}", field, url, e));
+            }
+        }
+        if let Some(url) = &self.notifications.webhook_url {
+            if let Err(e) = reqwest::Url::parse(url) {
+                problems.push(format!("notifications.webhook_url is not a valid URL ({}): {}", url, e));
+            }
+        }
+        if self.hyperbeam.max_concurrent_requests == 0 {
+            problems.push("hyperbeam.max_concurrent_requests must be at least 1".to_string());
+        }
+        if self.hyperbeam.request_timeout_secs == 0 {
+            problems.push("hyperbeam.request_timeout_secs must be at least 1".to_string());
+        }
+        if self.hyperbeam.connect_timeout_secs == 0 {
+            problems.push("hyperbeam.connect_timeout_secs must be at least 1".to_string());
+        }
+        if self.hyperbeam.reserves_timeout_secs == 0 {
+            problems.push("hyperbeam.reserves_timeout_secs must be at least 1".to_string());
+        }
+        if let Err(e) = self.hyperbeam.default_headers() {
+            problems.push(e.to_string());
+        }
+        if self.limits.max_active_processes == 0 {
+            problems.push("limits.max_active_processes must be at least 1".to_string());
+        }
+        for (field, interval) in [
+            ("monitoring.cron_list_interval", self.monitoring.cron_list_interval),
+            ("monitoring.queue_slots_interval", self.monitoring.queue_slots_interval),
+            ("monitoring.synced_pools_interval", self.monitoring.synced_pools_interval),
+            ("monitoring.monitor_loop_interval", self.monitoring.monitor_loop_interval),
+        ] {
+            if interval == 0 {
+                problems.push(format!("{} must be at least 1 second", field));
+            }
+        }
+        if self.monitoring.rate_smoothing <= 0.0 || self.monitoring.rate_smoothing > 1.0 {
+            problems.push("monitoring.rate_smoothing must be greater than 0 and at most 1".to_string());
+        }
+        if self.monitoring.response_time_window == 0 {
+            problems.push("monitoring.response_time_window must be at least 1".to_string());
+        }
+        if self.monitoring.max_slots_per_minute <= 0.0 {
+            problems.push("monitoring.max_slots_per_minute must be greater than 0".to_string());
+        }
+        
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow!("Invalid configuration:\n  - {}", problems.join("\n  - ")))
+        }
+    }
+    
+    /// Check that `new` only differs from this config in settings that can be applied
+    /// without a restart. Anything baked into the server, HTTP client, state store or
+    /// logging at startup is fixed.
+    pub fn check_reloadable(&self, new: &ServiceConfig) -> Result<()> {
+        let fixed = [
+            ("server", self.server != new.server),
+            ("hyperbeam", self.hyperbeam != new.hyperbeam),
+            ("ao", self.ao != new.ao),
+            ("logging", self.logging != new.logging),
+            ("reserves", self.reserves != new.reserves),
+            ("state", self.state != new.state),
+            ("limits.process_allowlist", self.limits.process_allowlist != new.limits.process_allowlist),
+            ("limits.process_denylist", self.limits.process_denylist != new.limits.process_denylist),
+        ];
+        let changed: Vec<&str> = fixed.iter()
+            .filter(|(_, changed)| *changed)
+            .map(|(field, _)| *field)
+            .collect();
+        
+        if changed.is_empty() {
+            Ok(())
         } else {
-            Ok(Self::default())
+            Err(anyhow!("Cannot reload without a restart, changed: {}", changed.join(", ")))
+        }
+    }
+    
+    /// Override settings from `HYDRATION_*` environment variables.
+    fn apply_env_overrides(&mut self) -> Result<()> {
+        fn var(name: &str) -> Option<String> {
+            std::env::var(name).ok().filter(|v| !v.is_empty())
+        }
+        
+        fn parse<T: std::str::FromStr>(name: &str) -> Result<Option<T>>
+        where
+            T::Err: std::fmt::Display,
+        {
+            var(name)
+                .map(|v| v.parse().map_err(|e| anyhow!("Invalid {}={}: {}", name, v, e)))
+                .transpose()
+        }
+        
+        if let Some(host) = var("HYDRATION_SERVER_HOST") {
+            self.server.host = host;
+        }
+        if let Some(port) = parse("HYDRATION_SERVER_PORT")? {
+            self.server.port = port;
+        }
+        if let Some(url) = var("HYDRATION_HYPERBEAM_BASE_URL") {
+            self.hyperbeam.base_url = url;
+        }
+        if let Some(url) = var("HYDRATION_AO_CU_URL") {
+            self.ao.cu_url = url;
         }
+        if let Some(max) = parse("HYDRATION_MAX_ACTIVE_PROCESSES")? {
+            self.limits.max_active_processes = max;
+        }
+        if let Some(level) = var("HYDRATION_LOG_LEVEL") {
+            self.logging.level = level;
+        }
+        if let Some(path) = var("HYDRATION_STATE_PATH") {
+            self.state.path = path;
+        }
+        
+        Ok(())
     }
     
+    /// A copy safe to return from the API, with secrets masked.
+    pub fn redacted(&self) -> ServiceConfig {
+        let mut config = self.clone();
+        if let Some(auth) = &mut config.hyperbeam.auth_header {
+            auth.value = "<redacted>".to_string();
+        }
+        config
+    }
+    
+    #[allow(dead_code)]
     pub fn save(&self) -> Result<()> {
         let toml_string = toml::to_string_pretty(&self)?;
         fs::write("config.toml", toml_string)?;
         Ok(())
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Change = fn(&mut ServiceConfig);
+
+    fn invalid(change: impl FnOnce(&mut ServiceConfig)) -> String {
+        let mut config = ServiceConfig::default();
+        change(&mut config);
+        config.validate().unwrap_err().to_string()
+    }
+
+    #[test]
+    fn the_default_config_is_valid() {
+        ServiceConfig::default().validate().unwrap();
+    }
+
+    #[test]
+    fn each_invalid_value_is_reported() {
+        let cases: [(&str, Change); 22] = [
+            ("server.port must be greater than 0", |c| c.server.port = 0),
+            ("server.host must not be empty", |c| c.server.host = " ".to_string()),
+            ("hyperbeam.base_url must not be empty", |c| c.hyperbeam.base_url = String::new()),
+            ("hyperbeam.base_url is not a valid URL", |c| c.hyperbeam.base_url = "not a url".to_string()),
+            ("ao.cu_url is not a valid URL", |c| c.ao.cu_url = "cu".to_string()),
+            ("hyperbeam.base_urls is not a valid URL", |c| c.hyperbeam.base_urls = vec!["::".to_string()]),
+            ("notifications.webhook_url is not a valid URL", |c| c.notifications.webhook_url = Some("hook".to_string())),
+            ("hyperbeam.max_concurrent_requests must be at least 1", |c| c.hyperbeam.max_concurrent_requests = 0),
+            ("hyperbeam.request_timeout_secs must be at least 1", |c| c.hyperbeam.request_timeout_secs = 0),
+            ("hyperbeam.connect_timeout_secs must be at least 1", |c| c.hyperbeam.connect_timeout_secs = 0),
+            ("hyperbeam.reserves_timeout_secs must be at least 1", |c| c.hyperbeam.reserves_timeout_secs = 0),
+            ("hyperbeam.user_agent is not a valid header value", |c| c.hyperbeam.user_agent = Some("bad\nagent".to_string())),
+            ("hyperbeam.auth_header.name is not a valid header name", |c| c.hyperbeam.auth_header = Some(AuthHeader {
+                name: "bad name".to_string(),
+                value: "secret".to_string(),
+            })),
+            ("limits.max_active_processes must be at least 1", |c| c.limits.max_active_processes = 0),
+            ("monitoring.cron_list_interval must be at least 1 second", |c| c.monitoring.cron_list_interval = 0),
+            ("monitoring.queue_slots_interval must be at least 1 second", |c| c.monitoring.queue_slots_interval = 0),
+            ("monitoring.synced_pools_interval must be at least 1 second", |c| c.monitoring.synced_pools_interval = 0),
+            ("monitoring.monitor_loop_interval must be at least 1 second", |c| c.monitoring.monitor_loop_interval = 0),
+            ("monitoring.rate_smoothing must be greater than 0", |c| c.monitoring.rate_smoothing = 0.0),
+            ("monitoring.rate_smoothing must be greater than 0", |c| c.monitoring.rate_smoothing = 1.5),
+            ("monitoring.response_time_window must be at least 1", |c| c.monitoring.response_time_window = 0),
+            ("monitoring.max_slots_per_minute must be greater than 0", |c| c.monitoring.max_slots_per_minute = -1.0),
+        ];
+        for (expected, change) in cases {
+            let error = invalid(change);
+            assert!(error.contains(expected), "expected {:?} in {:?}", expected, error);
+        }
+    }
+
+    #[test]
+    fn every_problem_is_reported_at_once() {
+        let error = invalid(|c| {
+            c.server.port = 0;
+            c.limits.max_active_processes = 0;
+        });
+        assert_eq!(
+            error,
+            "Invalid configuration:\n  - server.port must be greater than 0\n  - limits.max_active_processes must be at least 1",
+        );
+    }
+
+    #[test]
+    fn reserve_filter_matches_token_ids_by_length_and_ignored_keys() {
+        let filter = ReserveFilter::default();
+        assert!(filter.is_token_key(&"a".repeat(43)));
+        assert!(!filter.is_token_key(&"a".repeat(42)));
+        assert!(!filter.is_token_key(&"a".repeat(44)));
+        for key in ["TokenA", "TokenB", "K"] {
+            assert!(!filter.is_token_key(key));
+        }
+
+        let custom = ReserveFilter {
+            token_id_length: 6,
+            ignored_keys: vec!["TokenA".to_string()],
+        };
+        assert!(!custom.is_token_key("TokenA"));
+        assert!(custom.is_token_key("TokenB"));
+        assert!(!custom.is_token_key(&"a".repeat(43)));
+
+        let extended = custom.ignoring(&["TokenB".to_string()]);
+        assert!(!extended.is_token_key("TokenB"));
+        assert!(extended.is_token_key("abcdef"));
+        // The original filter is unchanged
+        assert!(custom.is_token_key("TokenB"));
+    }
+
+    #[test]
+    fn reserve_filter_defaults_fill_in_missing_fields() {
+        let filter: ReserveFilter = toml::from_str("token_id_length = 10").unwrap();
+        assert_eq!(filter.token_id_length, 10);
+        assert_eq!(filter.ignored_keys, ReserveFilter::default().ignored_keys);
+    }
+
+    const ENV_VARS: [&str; 7] = [
+        "HYDRATION_SERVER_HOST",
+        "HYDRATION_SERVER_PORT",
+        "HYDRATION_HYPERBEAM_BASE_URL",
+        "HYDRATION_AO_CU_URL",
+        "HYDRATION_MAX_ACTIVE_PROCESSES",
+        "HYDRATION_LOG_LEVEL",
+        "HYDRATION_STATE_PATH",
+    ];
+
+    // Environment variables are process-wide, so every override case runs in this one test
+    #[test]
+    fn environment_overrides_win_over_file_values() {
+        let mut config = ServiceConfig::default();
+        config.server.port = 1234;
+        config.logging.level = "warn".to_string();
+
+        std::env::set_var("HYDRATION_SERVER_HOST", "127.0.0.1");
+        std::env::set_var("HYDRATION_SERVER_PORT", "9999");
+        std::env::set_var("HYDRATION_HYPERBEAM_BASE_URL", "http://hb.example");
+        std::env::set_var("HYDRATION_AO_CU_URL", "http://cu.example");
+        std::env::set_var("HYDRATION_MAX_ACTIVE_PROCESSES", "7");
+        std::env::set_var("HYDRATION_LOG_LEVEL", "debug");
+        std::env::set_var("HYDRATION_STATE_PATH", "/tmp/hydration.json");
+        config.apply_env_overrides().unwrap();
+
+        assert_eq!(config.server.host, "127.0.0.1");
+        assert_eq!(config.server.port, 9999);
+        assert_eq!(config.hyperbeam.base_url, "http://hb.example");
+        assert_eq!(config.ao.cu_url, "http://cu.example");
+        assert_eq!(config.limits.max_active_processes, 7);
+        assert_eq!(config.logging.level, "debug");
+        assert_eq!(config.state.path, "/tmp/hydration.json");
+
+        // Empty values are ignored
+        for name in ENV_VARS {
+            std::env::set_var(name, "");
+        }
+        let mut unchanged = ServiceConfig::default();
+        unchanged.apply_env_overrides().unwrap();
+        assert_eq!(unchanged, ServiceConfig::default());
+
+        std::env::set_var("HYDRATION_SERVER_PORT", "not-a-port");
+        let error = ServiceConfig::default().apply_env_overrides().unwrap_err().to_string();
+        assert!(error.starts_with("Invalid HYDRATION_SERVER_PORT=not-a-port"), "{}", error);
+
+        for name in ENV_VARS {
+            std::env::remove_var(name);
+        }
+    }
+}
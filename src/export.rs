@@ -0,0 +1,84 @@
+use crate::models::ProcessStatus;
+use chrono::{DateTime, Utc};
+
+const SYNCED_CSV_HEADER: &str = "process_id,name,initial_slot_deficit,total_slots_advanced,avg_sync_rate,activated_at,synced_at,time_to_sync_minutes,queued_secs,active_secs,synced_secs";
+
+/// Quote a CSV field if it contains a delimiter, quote or newline.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn optional<T: ToString>(value: Option<T>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
+
+fn timestamp(value: Option<DateTime<Utc>>) -> String {
+    optional(value.map(|dt| dt.to_rfc3339()))
+}
+
+/// Render synced processes as CSV, one row per process.
+pub fn render_synced_csv(processes: &[ProcessStatus]) -> String {
+    let mut out = String::from(SYNCED_CSV_HEADER);
+    out.push('\n');
+
+    for p in processes {
+        let time_to_sync = p.metrics.time_to_sync()
+            .map(|d| format!("{:.2}", d.num_seconds() as f64 / 60.0));
+        let lifecycle = p.lifecycle_timings();
+
+        let row = [
+            csv_field(&p.process_id),
+            csv_field(&p.name),
+            optional(p.metrics.initial_slot_deficit),
+            p.metrics.total_slots_advanced.to_string(),
+            format!("{:.2}", p.metrics.avg_sync_rate),
+            timestamp(p.activated_at),
+            timestamp(p.synced_at),
+            optional(time_to_sync),
+            optional(lifecycle.queued_secs),
+            optional(lifecycle.active_secs),
+            optional(lifecycle.synced_secs),
+        ];
+        out.push_str(&row.join(","));
+        out.push('\n');
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn renders_a_header_and_one_row_per_process() {
+        let at = |minute| Utc.with_ymd_and_hms(2024, 1, 1, 12, minute, 0).unwrap();
+        let mut process = ProcessStatus::new("pool, \"main\"".to_string(), "pid".to_string(), None);
+        process.added_at = Some(at(0));
+        process.activated_at = Some(at(10));
+        process.synced_at = Some(at(40));
+        process.metrics.initial_slot_deficit = Some(1200);
+        process.metrics.total_slots_advanced = 1200;
+        process.metrics.avg_sync_rate = 40.0;
+        process.metrics.sync_start_time = Some(at(10));
+        process.metrics.sync_end_time = Some(at(40));
+
+        let csv = render_synced_csv(&[process]);
+        let lines: Vec<_> = csv.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], SYNCED_CSV_HEADER);
+
+        // The time spent synced so far depends on the current time
+        let (row, synced_secs) = lines[1].rsplit_once(',').unwrap();
+        assert_eq!(
+            row,
+            "pid,\"pool, \"\"main\"\"\",1200,1200,40.00,2024-01-01T12:10:00+00:00,2024-01-01T12:40:00+00:00,30.00,600,1800",
+        );
+        assert!(synced_secs.parse::<u64>().unwrap() > 0);
+    }
+}
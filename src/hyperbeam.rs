@@ -1,40 +1,152 @@
 use anyhow::{anyhow, Result};
-use reqwest::Client;
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::time::{Duration, Instant};
 use std::collections::HashMap;
-use crate::models::{AODryRunRequest, AODryRunResponse, AOTag};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use tokio::sync::{Semaphore, SemaphorePermit};
+use crate::config::{HyperbeamConfig, ReservesConfig};
+use crate::models::{AODryRunRequest, AODryRunResponse, AOTag, StallNotification, SyncNotification};
 
-const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
 
 pub struct HyperBeamClient {
     client: Client,
-    default_base_url: String,
+    /// Plain client for notification webhooks, which must not receive the configured
+    /// auth header
+    webhook_client: Client,
+    base_urls: Vec<String>,
     ao_cu_url: String,
+    reserves_config: ReservesConfig,
+    reserves_timeout: Duration,
+    request_permits: Semaphore,
+    /// Outcome of the most recent AO dry-run; assumed true until one fails
+    ao_cu_reachable: AtomicBool,
+    pub stats: ClientStats,
+}
+
+#[derive(Debug, Default)]
+pub struct ClientStats {
+    pub hb_requests: AtomicU64,
+    pub ao_requests: AtomicU64,
+    pub errors: AtomicU64,
+}
+
+impl ClientStats {
+    pub fn hb_requests(&self) -> u64 {
+        self.hb_requests.load(Ordering::Relaxed)
+    }
+
+    pub fn ao_requests(&self) -> u64 {
+        self.ao_requests.load(Ordering::Relaxed)
+    }
+
+    pub fn errors(&self) -> u64 {
+        self.errors.load(Ordering::Relaxed)
+    }
+
+    /// All outbound requests, to HyperBEAM and the AO CU combined.
+    pub fn total_requests(&self) -> u64 {
+        self.hb_requests() + self.ao_requests()
+    }
 }
 
 impl HyperBeamClient {
-    pub fn new(default_base_url: String, ao_cu_url: String) -> Self {
+    /// HyperBEAM requests go to the per-call `base_url` when given, otherwise to one of
+    /// the configured nodes chosen by process ID; `ao_cu_url` is used for all AO dry-runs.
+    pub fn new(
+        hyperbeam: &HyperbeamConfig,
+        ao_cu_url: String,
+        reserves_config: ReservesConfig,
+    ) -> Self {
         let client = Client::builder()
-            .timeout(REQUEST_TIMEOUT)
+            .timeout(Duration::from_secs(hyperbeam.request_timeout_secs))
+            .connect_timeout(Duration::from_secs(hyperbeam.connect_timeout_secs))
+            .default_headers(hyperbeam.default_headers().expect("hyperbeam headers are checked by validate()"))
             .build()
             .expect("Failed to create HTTP client");
         
         Self { 
             client,
-            default_base_url,
+            webhook_client: Client::new(),
+            base_urls: hyperbeam.nodes(),
             ao_cu_url,
+            reserves_config,
+            reserves_timeout: Duration::from_secs(hyperbeam.reserves_timeout_secs),
+            request_permits: Semaphore::new(hyperbeam.max_concurrent_requests),
+            ao_cu_reachable: AtomicBool::new(true),
+            stats: ClientStats::default(),
         }
     }
 
+    /// The node a process without its own `base_url` is assigned to. The assignment is
+    /// a stable hash of the process ID, so it survives restarts.
+    pub fn node_for(&self, process_id: &str) -> &str {
+        // FNV-1a, which unlike `DefaultHasher` is guaranteed not to change between releases
+        let hash = process_id.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        });
+        &self.base_urls[(hash % self.base_urls.len() as u64) as usize]
+    }
+
+    fn resolve_base<'a>(&'a self, base_url: Option<&'a str>, process_id: &str) -> &'a str {
+        base_url.unwrap_or_else(|| self.node_for(process_id))
+    }
+
+    /// Send a request, counting it against `counter` and recording failures.
+    /// Every outbound request waits for a permit, held until its body has been read,
+    /// so the total in flight is bounded.
+    async fn send(&self, request: RequestBuilder, counter: &AtomicU64) -> Result<PermittedResponse<'_>> {
+        let permit = self.request_permits.acquire().await?;
+        counter.fetch_add(1, Ordering::Relaxed);
+        match request.send().await {
+            Ok(response) => {
+                if !response.status().is_success() {
+                    self.stats.errors.fetch_add(1, Ordering::Relaxed);
+                }
+                Ok(PermittedResponse { response, _permit: permit })
+            }
+            Err(e) => {
+                self.stats.errors.fetch_add(1, Ordering::Relaxed);
+                Err(e.into())
+            }
+        }
+    }
+
+    /// Post a sync notification in the background; failures are only logged.
+    pub fn notify_synced(&self, webhook_url: &str, notification: SyncNotification) {
+        self.post_webhook(webhook_url, "Sync", notification.process_id.clone(), &notification);
+    }
+
+    /// Post a stall notification in the background; failures are only logged.
+    pub fn notify_stalled(&self, webhook_url: &str, notification: StallNotification) {
+        self.post_webhook(webhook_url, "Stall", notification.process_id.clone(), &notification);
+    }
+
+    fn post_webhook(&self, webhook_url: &str, kind: &'static str, process_id: String, payload: &impl Serialize) {
+        let request = self.webhook_client.post(webhook_url)
+            .timeout(WEBHOOK_TIMEOUT)
+            .json(payload);
+        
+        tokio::spawn(async move {
+            match request.send().await {
+                Ok(response) if !response.status().is_success() => {
+                    tracing::warn!("{} webhook for {} returned HTTP {}", kind, process_id, response.status());
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::warn!("{} webhook for {} failed: {}", kind, process_id, e);
+                }
+            }
+        });
+    }
+
     pub async fn initialize_cron(&self, base_url: Option<&str>, process_id: &str) -> Result<()> {
-        let base = base_url.unwrap_or(&self.default_base_url);
+        let base = self.resolve_base(base_url, process_id);
         let url = format!("{}/~cron@1.0/once?cron-path=/{process_id}~process@1.0/now", base);
         
-        let response = self.client
-            .get(&url)
-            .send()
-            .await?;
+        let response = self.send(self.client.get(&url), &self.stats.hb_requests).await?;
         
         if !response.status().is_success() {
             return Err(anyhow!(
@@ -47,20 +159,49 @@ impl HyperBeamClient {
         Ok(())
     }
 
+    /// Stop every cron task on the process's node that targets the process. Returns the
+    /// number of tasks stopped; a process without a cron, or whose task is already gone,
+    /// is not an error.
+    pub async fn cancel_cron(&self, base_url: Option<&str>, process_id: &str) -> Result<usize> {
+        let base = self.resolve_base(base_url, process_id);
+        let tasks: Vec<CronItem> = self.fetch_cron_list(base).await?
+            .into_iter()
+            .filter(|item| parse_process_id_from_cron_path(&item.path) == Some(process_id))
+            .collect();
+        
+        let mut stopped = 0;
+        for task in tasks {
+            let url = format!("{}/~cron@1.0/stop?task={}", base, task.task_id);
+            let response = self.send(self.client.get(&url), &self.stats.hb_requests).await?;
+            
+            if response.status() == reqwest::StatusCode::NOT_FOUND {
+                continue;
+            }
+            if !response.status().is_success() {
+                return Err(anyhow!(
+                    "Failed to stop cron task {}: HTTP {} - {}",
+                    task.task_id,
+                    response.status(),
+                    response.text().await.unwrap_or_default()
+                ));
+            }
+            stopped += 1;
+        }
+        
+        Ok(stopped)
+    }
+
     pub async fn get_slot_value(
         &self,
         base_url: Option<&str>,
         process_id: &str,
         endpoint: &str,
     ) -> Result<(u64, f64)> {
-        let base = base_url.unwrap_or(&self.default_base_url);
+        let base = self.resolve_base(base_url, process_id);
         let url = format!("{}/{process_id}~process@1.0/{endpoint}", base);
         
         let start = Instant::now();
-        let response = self.client
-            .get(&url)
-            .send()
-            .await?;
+        let response = self.send(self.client.get(&url), &self.stats.hb_requests).await?;
         
         let response_time = start.elapsed().as_millis() as f64;
         
@@ -73,8 +214,7 @@ impl HyperBeamClient {
         }
         
         let text = response.text().await?;
-        let value = text.trim().parse::<u64>()
-            .map_err(|e| anyhow!("Failed to parse slot value '{}': {}", text, e))?;
+        let value = parse_slot_value(&text)?;
         
         Ok((value, response_time))
     }
@@ -116,12 +256,91 @@ impl HyperBeamClient {
         })
     }
     
+    /// Read the computed slot and then the current slot, one after the other, so a
+    /// slot landing between the two reads cannot make the process look synced.
+    pub async fn check_slots_sequential(
+        &self,
+        base_url: Option<&str>,
+        process_id: &str,
+    ) -> Result<SlotCheckResult> {
+        let (computed_slot, computed_time) = self.get_computed_slot(base_url, process_id).await?;
+        let (current_slot, current_time) = self.get_current_slot(base_url, process_id).await?;
+        
+        Ok(SlotCheckResult {
+            computed_slot,
+            current_slot,
+            computed_response_time: computed_time,
+            current_response_time: current_time,
+        })
+    }
+    
+    /// Confirm a synced reading with a fresh sequential read.
+    pub async fn confirm_synced(&self, base_url: Option<&str>, process_id: &str, tolerance: u64) -> bool {
+        match self.check_slots_sequential(base_url, process_id).await {
+            Ok(result) => result.is_synced(tolerance),
+            Err(_) => false,
+        }
+    }
+    
     pub async fn check_current_slot(&self, base_url: Option<&str>, process_id: &str) -> Result<u64> {
         let (current_slot, _) = self.get_current_slot(base_url, process_id).await?;
         Ok(current_slot)
     }
 }
 
+/// Returned when a node answers a slot request with an empty body, which happens
+/// while it restarts and is not worth logging as an error.
+#[derive(Debug)]
+pub struct EmptySlotResponse;
+
+impl std::fmt::Display for EmptySlotResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Empty slot response")
+    }
+}
+
+impl std::error::Error for EmptySlotResponse {}
+
+pub fn is_empty_slot_response(error: &anyhow::Error) -> bool {
+    error.downcast_ref::<EmptySlotResponse>().is_some()
+}
+
+/// Parse a slot from a response body: a plain or quoted number, or a JSON object whose
+/// only key is `slot`. Anything else, such as `{"error": 5}`, is rejected.
+pub fn parse_slot_value(text: &str) -> Result<u64> {
+    let trimmed = text.trim().trim_matches('"').trim();
+    if trimmed.is_empty() {
+        return Err(EmptySlotResponse.into());
+    }
+    
+    if let Ok(value) = trimmed.parse::<u64>() {
+        return Ok(value);
+    }
+    
+    // JSON-wrapped number, e.g. {"slot": 123} or {"slot": "123"}
+    if let Ok(serde_json::Value::Object(map)) = serde_json::from_str::<serde_json::Value>(trimmed) {
+        let value = match (map.len(), map.get("slot")) {
+            (1, Some(slot)) => slot.as_u64().or_else(|| slot.as_str()?.trim().parse().ok()),
+            _ => None,
+        };
+        if let Some(value) = value {
+            return Ok(value);
+        }
+    }
+    
+    let preview: String = trimmed.chars().take(100).collect();
+    Err(anyhow!("Failed to parse slot value from response: '{}'", preview))
+}
+
+/// Extract the process ID from a cron path such as `/<process-id>~process@1.0/now`.
+/// The leading slash is optional and anything after the first segment is ignored;
+/// paths whose first segment has no ID before the device separator give `None`.
+pub fn parse_process_id_from_cron_path(path: &str) -> Option<&str> {
+    let segment = path.trim_start_matches('/').split('/').next()?;
+    let (process_id, _device) = segment.split_once('~')?;
+    (!process_id.is_empty() && !process_id.contains(char::is_whitespace)).then_some(process_id)
+}
+
 #[derive(Debug, Clone)]
 pub struct SlotCheckResult {
     pub computed_slot: u64,
@@ -131,16 +350,13 @@ pub struct SlotCheckResult {
 }
 
 impl SlotCheckResult {
-    pub fn is_synced(&self) -> bool {
-        self.computed_slot == self.current_slot
+    /// Synced when the computed slot is at most `tolerance` slots behind the current slot.
+    pub fn is_synced(&self, tolerance: u64) -> bool {
+        self.computed_slot <= self.current_slot && self.deficit() <= tolerance
     }
     
     pub fn deficit(&self) -> u64 {
-        if self.current_slot > self.computed_slot {
-            self.current_slot - self.computed_slot
-        } else {
-            0
-        }
+        self.current_slot.saturating_sub(self.computed_slot)
     }
 }
 
@@ -150,13 +366,10 @@ impl HyperBeamClient {
         base_url: Option<&str>,
         process_id: &str,
     ) -> Result<HashMap<String, String>> {
-        let base = base_url.unwrap_or(&self.default_base_url);
+        let base = self.resolve_base(base_url, process_id);
         let url = format!("{}/{process_id}~process@1.0/now/reserves", base);
         
-        let response = self.client
-            .get(&url)
-            .send()
-            .await?;
+        let response = self.send(self.client.get(&url).timeout(self.reserves_timeout), &self.stats.hb_requests).await?;
         
         if !response.status().is_success() {
             return Err(anyhow!(
@@ -170,7 +383,26 @@ impl HyperBeamClient {
         Ok(reserves)
     }
     
+    /// Whether the last AO dry-run succeeded. Reserve comparisons are unavailable while
+    /// this is false, but HyperBEAM monitoring carries on unaffected.
+    pub fn ao_cu_reachable(&self) -> bool {
+        self.ao_cu_reachable.load(Ordering::Relaxed)
+    }
+
     pub async fn fetch_ao_reserves(&self, process_id: &str) -> Result<HashMap<String, String>> {
+        let result = self.dry_run_reserves(process_id).await;
+        let reachable = result.is_ok();
+        if self.ao_cu_reachable.swap(reachable, Ordering::Relaxed) != reachable {
+            if reachable {
+                tracing::info!("AO CU at {} is reachable again", self.ao_cu_url);
+            } else if let Err(e) = &result {
+                tracing::warn!("AO CU at {} is unreachable: {}", self.ao_cu_url, e);
+            }
+        }
+        result
+    }
+
+    async fn dry_run_reserves(&self, process_id: &str) -> Result<HashMap<String, String>> {
         let payload = AODryRunRequest {
             id: "1234".to_string(),
             target: process_id.to_string(),
@@ -186,11 +418,7 @@ impl HyperBeamClient {
         };
         
         let url = format!("{}/dry-run?process-id={}", &self.ao_cu_url, process_id);
-        let response = self.client
-            .post(&url)
-            .json(&payload)
-            .send()
-            .await?;
+        let response = self.send(self.client.post(&url).json(&payload).timeout(self.reserves_timeout), &self.stats.ao_requests).await?;
         
         if !response.status().is_success() {
             return Err(anyhow!(
@@ -208,11 +436,10 @@ impl HyperBeamClient {
             if let Some(message) = messages.first() {
                 for tag in &message.tags {
                     // Skip non-token tags
-                    if !["Action", "Data-Protocol", "Type", "Variant", "Reference"].contains(&tag.name.as_str()) {
-                        // Token addresses are 43 characters long
-                        if tag.name.len() == 43 {
-                            reserves.insert(tag.name.clone(), tag.value.clone());
-                        }
+                    if !["Action", "Data-Protocol", "Type", "Variant", "Reference"].contains(&tag.name.as_str())
+                        && self.reserves_config.filter.is_token_key(&tag.name)
+                    {
+                        reserves.insert(tag.name.clone(), tag.value.clone());
                     }
                 }
             }
@@ -226,13 +453,21 @@ impl HyperBeamClient {
         base_url: Option<&str>,
         process_id: &str,
     ) -> Result<ReservesResult> {
-        let (hb_future, ao_future) = tokio::join!(
-            self.fetch_hb_reserves(base_url, process_id),
-            self.fetch_ao_reserves(process_id)
-        );
-        
-        let hb_reserves = hb_future.ok();
-        let ao_reserves = ao_future.ok();
+        let (hb_reserves, ao_reserves) = if self.reserves_config.concurrent_fetch {
+            let (hb_future, ao_future) = tokio::join!(
+                self.fetch_hb_reserves(base_url, process_id),
+                self.fetch_ao_reserves(process_id)
+            );
+            (hb_future.ok(), ao_future.ok())
+        } else {
+            let hb_reserves = self.fetch_hb_reserves(base_url, process_id).await.ok();
+            let ao_reserves = if hb_reserves.is_none() && self.reserves_config.skip_ao_on_hb_failure {
+                None
+            } else {
+                self.fetch_ao_reserves(process_id).await.ok()
+            };
+            (hb_reserves, ao_reserves)
+        };
         
         Ok(ReservesResult {
             hb_reserves,
@@ -240,14 +475,37 @@ impl HyperBeamClient {
         })
     }
     
-    pub async fn fetch_cron_list(&self, base_url: Option<&str>) -> Result<Vec<CronItem>> {
-        let base = base_url.unwrap_or(&self.default_base_url);
+    /// Fetch and merge the cron lists of every configured node. Fails only if
+    /// no node could be reached.
+    pub async fn fetch_all_cron_lists(&self) -> Result<Vec<CronItem>> {
+        let results = futures::future::join_all(
+            self.base_urls.iter().map(|base| self.fetch_cron_list(base))
+        ).await;
+        
+        let mut items = Vec::new();
+        let mut failures = 0;
+        let mut last_error = None;
+        for (base, result) in self.base_urls.iter().zip(results) {
+            match result {
+                Ok(node_items) => items.extend(node_items),
+                Err(e) => {
+                    tracing::warn!("Failed to fetch cron list from {}: {}", base, e);
+                    failures += 1;
+                    last_error = Some(e);
+                }
+            }
+        }
+        
+        match last_error {
+            Some(e) if failures == self.base_urls.len() => Err(e),
+            _ => Ok(items),
+        }
+    }
+    
+    pub async fn fetch_cron_list(&self, base: &str) -> Result<Vec<CronItem>> {
         let url = format!("{}/~cron@1.0/list/serialize~json@1.0", base);
         
-        let response = self.client
-            .get(&url)
-            .send()
-            .await?;
+        let response = self.send(self.client.get(&url), &self.stats.hb_requests).await?;
         
         if !response.status().is_success() {
             return Err(anyhow!("Failed to fetch cron list: {}", response.status()));
@@ -263,7 +521,27 @@ impl HyperBeamClient {
     }
 }
 
-#[derive(Debug, Clone)]
+/// A response holding its request permit until the body is read or it is dropped.
+struct PermittedResponse<'a> {
+    response: Response,
+    _permit: SemaphorePermit<'a>,
+}
+
+impl PermittedResponse<'_> {
+    fn status(&self) -> StatusCode {
+        self.response.status()
+    }
+
+    async fn text(self) -> reqwest::Result<String> {
+        self.response.text().await
+    }
+
+    async fn json<T: DeserializeOwned>(self) -> reqwest::Result<T> {
+        self.response.json().await
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
 pub struct ReservesResult {
     pub hb_reserves: Option<HashMap<String, String>>,
     pub ao_reserves: Option<HashMap<String, String>>,
@@ -284,4 +562,348 @@ pub struct CronListResponse {
     pub body: Vec<CronItem>,
     pub device: String,
     pub status: u16,
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_plain_and_quoted_numbers() {
+        assert_eq!(parse_slot_value("12345").unwrap(), 12345);
+        assert_eq!(parse_slot_value("  12345\n").unwrap(), 12345);
+        assert_eq!(parse_slot_value("\"12345\"").unwrap(), 12345);
+        assert_eq!(parse_slot_value(" \" 12345 \" ").unwrap(), 12345);
+    }
+
+    #[test]
+    fn parses_the_slot_key() {
+        assert_eq!(parse_slot_value(r#"{"slot": 42}"#).unwrap(), 42);
+        assert_eq!(parse_slot_value(r#"{"slot": "42"}"#).unwrap(), 42);
+    }
+
+    #[test]
+    fn empty_bodies_are_a_soft_error() {
+        for body in ["", "   ", "\n", "\"\""] {
+            let error = parse_slot_value(body).unwrap_err();
+            assert!(is_empty_slot_response(&error), "{:?}", body);
+        }
+    }
+
+    #[test]
+    fn html_is_rejected_with_a_preview() {
+        let body = format!("<html><body>502 Bad Gateway{}</body></html>", "x".repeat(200));
+        let error = parse_slot_value(&body).unwrap_err();
+        assert!(!is_empty_slot_response(&error));
+        let message = error.to_string();
+        assert!(message.contains("<html><body>502 Bad Gateway"));
+        assert!(!message.contains(&"x".repeat(100)));
+    }
+
+    #[test]
+    fn error_objects_and_other_json_are_rejected() {
+        for body in [
+            r#"{"error": 5}"#,
+            r#"{"status": 500}"#,
+            r#"{"slot": 5, "error": "stale"}"#,
+            r#"{"slot": -1}"#,
+            r#"{"slot": "abc"}"#,
+            "[5]",
+            "-5",
+            "1.5",
+        ] {
+            assert!(parse_slot_value(body).is_err(), "{}", body);
+        }
+    }
+
+    /// Order in which a reserves mock saw the HB request start and finish and the AO
+    /// request arrive. The HB answer is delayed so overlap shows up.
+    async fn reserve_fetch_order(concurrent_fetch: bool, skip_ao_on_hb_failure: bool, hb_ok: bool) -> Vec<&'static str> {
+        use crate::test_support::{client_for, config_for, serve};
+        use axum::http::StatusCode;
+        use axum::response::IntoResponse;
+        use std::sync::{Arc, Mutex};
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let (hb_events, ao_events) = (events.clone(), events.clone());
+        let router = axum::Router::new()
+            .route("/:process/now/reserves", axum::routing::get(move || async move {
+                hb_events.lock().unwrap().push("hb start");
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                hb_events.lock().unwrap().push("hb end");
+                if hb_ok { "{}".into_response() } else { StatusCode::BAD_GATEWAY.into_response() }
+            }))
+            .route("/dry-run", axum::routing::post(move || async move {
+                ao_events.lock().unwrap().push("ao");
+                r#"{"Messages": []}"#
+            }));
+
+        let mut config = config_for(&serve(router).await);
+        config.reserves.concurrent_fetch = concurrent_fetch;
+        config.reserves.skip_ao_on_hb_failure = skip_ao_on_hb_failure;
+        let reserves = client_for(&config).fetch_reserves(None, "pool").await.unwrap();
+        assert_eq!(reserves.hb_reserves.is_some(), hb_ok);
+
+        let events = events.lock().unwrap().clone();
+        events
+    }
+
+    #[tokio::test]
+    async fn reserve_fetches_run_in_the_configured_order() {
+        assert_eq!(reserve_fetch_order(false, false, true).await, ["hb start", "hb end", "ao"]);
+        assert_eq!(reserve_fetch_order(true, false, true).await, ["hb start", "ao", "hb end"]);
+    }
+
+    #[tokio::test]
+    async fn sequential_fetch_skips_ao_only_when_configured() {
+        assert_eq!(reserve_fetch_order(false, true, false).await, ["hb start", "hb end"]);
+        assert_eq!(reserve_fetch_order(false, false, false).await, ["hb start", "hb end", "ao"]);
+        assert_eq!(reserve_fetch_order(false, true, true).await, ["hb start", "hb end", "ao"]);
+    }
+
+    #[tokio::test]
+    async fn requests_go_to_the_configured_hosts() {
+        use crate::test_support::{client_for, config_for, record_requests, serve, slot_node, MockSlots};
+
+        let (node, node_log) = record_requests(slot_node(MockSlots::new(5, 5)));
+        let (cu, cu_log) = record_requests(axum::Router::new()
+            .route("/dry-run", axum::routing::post(|| async { r#"{"Messages": []}"# })));
+        let (other, other_log) = record_requests(slot_node(MockSlots::new(7, 7)));
+        let other_url = serve(other).await;
+
+        let mut config = config_for(&serve(node).await);
+        config.ao.cu_url = serve(cu).await;
+        let client = client_for(&config);
+
+        assert_eq!(client.check_current_slot(None, "pool").await.unwrap(), 5);
+        client.fetch_ao_reserves("pool").await.unwrap();
+        // A per-process base URL takes priority over the configured node
+        assert_eq!(client.check_current_slot(Some(&other_url), "pool").await.unwrap(), 7);
+
+        assert_eq!(node_log.requests(), ["GET /pool~process@1.0/slot/current"]);
+        assert_eq!(cu_log.requests(), ["POST /dry-run?process-id=pool"]);
+        assert_eq!(other_log.requests(), ["GET /pool~process@1.0/slot/current"]);
+    }
+
+    #[tokio::test]
+    async fn in_flight_requests_stay_within_the_limit_until_bodies_are_read() {
+        use crate::test_support::{client_for, config_for, serve};
+        use axum::body::Body;
+        use std::sync::Arc;
+
+        #[derive(Clone, Default)]
+        struct InFlight {
+            current: Arc<AtomicU64>,
+            max: Arc<AtomicU64>,
+        }
+
+        // Headers go out at once but the body only after a delay, so a request counts
+        // as in flight until its body has been sent
+        let in_flight = InFlight::default();
+        let router = axum::Router::new()
+            .route("/:process/slot/current", axum::routing::get(|axum::extract::State(in_flight): axum::extract::State<InFlight>| async move {
+                let now = in_flight.current.fetch_add(1, Ordering::SeqCst) + 1;
+                in_flight.max.fetch_max(now, Ordering::SeqCst);
+                Body::from_stream(futures::stream::once(async move {
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                    in_flight.current.fetch_sub(1, Ordering::SeqCst);
+                    Ok::<_, std::convert::Infallible>("7")
+                }))
+            }))
+            .with_state(in_flight.clone());
+
+        let mut config = config_for(&serve(router).await);
+        config.hyperbeam.max_concurrent_requests = 2;
+        let client = client_for(&config);
+
+        let results = futures::future::join_all(
+            (0..8).map(|i| client.check_current_slot(None, if i % 2 == 0 { "a" } else { "b" }))
+        ).await;
+
+        assert!(results.iter().all(|r| matches!(r, Ok(7))));
+        assert_eq!(in_flight.max.load(Ordering::SeqCst), 2);
+        assert_eq!(client.stats.hb_requests(), 8);
+    }
+
+    #[test]
+    fn processes_are_assigned_to_nodes_deterministically() {
+        use crate::test_support::{client_for, config_for};
+
+        let mut config = config_for("http://a");
+        config.hyperbeam.base_urls = vec!["http://b".to_string(), "http://c".to_string(), "http://a".to_string()];
+        let client = client_for(&config);
+        let restarted = client_for(&config);
+
+        let ids: Vec<String> = (0..30).map(|i| format!("process-{}", i)).collect();
+        let mut used = std::collections::HashSet::new();
+        for id in &ids {
+            let node = client.node_for(id);
+            assert_eq!(client.node_for(id), node);
+            assert_eq!(restarted.node_for(id), node);
+            used.insert(node.to_string());
+        }
+        assert_eq!(used.len(), 3);
+
+        // A single configured node takes every process
+        let single = client_for(&config_for("http://a"));
+        assert!(ids.iter().all(|id| single.node_for(id) == "http://a"));
+        assert_eq!(single.resolve_base(Some("http://own"), "process-0"), "http://own");
+    }
+
+    #[tokio::test]
+    async fn a_slow_node_trips_the_request_timeout() {
+        use crate::test_support::{client_for, config_for, serve};
+
+        let slow = axum::Router::new().route("/:process/slot/current", axum::routing::get(|| async {
+            tokio::time::sleep(Duration::from_secs(10)).await;
+            "5"
+        }));
+        let mut config = config_for(&serve(slow).await);
+        config.hyperbeam.request_timeout_secs = 1;
+        let client = client_for(&config);
+
+        let started = std::time::Instant::now();
+        let error = client.check_current_slot(None, "pool").await.unwrap_err();
+        assert!(started.elapsed() < Duration::from_secs(5), "took {:?}", started.elapsed());
+        let timed_out = error.chain()
+            .filter_map(|cause| cause.downcast_ref::<reqwest::Error>())
+            .any(|e| e.is_timeout());
+        assert!(timed_out, "{:#}", error);
+        assert_eq!(client.stats.errors(), 1);
+    }
+
+    #[test]
+    fn slot_check_is_synced_within_tolerance() {
+        let result = |computed, current| SlotCheckResult {
+            computed_slot: computed,
+            current_slot: current,
+            computed_response_time: 0.0,
+            current_response_time: 0.0,
+        };
+        assert!(result(100, 100).is_synced(0));
+        assert!(!result(99, 100).is_synced(0));
+        assert!(result(97, 100).is_synced(3));
+        assert!(!result(96, 100).is_synced(3));
+        assert!(!result(101, 100).is_synced(3));
+        assert_eq!(result(101, 100).deficit(), 0);
+    }
+
+    #[tokio::test]
+    async fn every_outbound_call_is_counted() {
+        use crate::test_support::{client_for, config_for, serve, slot_node, MockSlots};
+
+        let node = slot_node(MockSlots::new(5, 5))
+            .route("/dry-run", axum::routing::post(|| async { r#"{"Messages": []}"# }));
+        let mut config = config_for(&serve(node).await);
+        config.ao.cu_url = config.hyperbeam.base_url.clone();
+        let client = client_for(&config);
+
+        for expected in 1..=3 {
+            client.check_current_slot(None, "pool").await.unwrap();
+            assert_eq!(client.stats.hb_requests(), expected);
+        }
+        client.fetch_ao_reserves("pool").await.unwrap();
+        assert_eq!((client.stats.hb_requests(), client.stats.ao_requests()), (3, 1));
+
+        // Failed calls count as requests as well as errors
+        client.fetch_cron_list(&config.hyperbeam.base_url).await.unwrap_err();
+        assert_eq!(client.stats.hb_requests(), 4);
+        assert_eq!(client.stats.errors(), 1);
+        assert_eq!(client.stats.total_requests(), 5);
+    }
+
+    #[test]
+    fn cron_paths_yield_the_process_id_or_none() {
+        for (path, expected) in [
+            ("/abc~process@1.0/now", Some("abc")),
+            ("abc~process@1.0/now", Some("abc")),
+            ("//abc~process@1.0/now", Some("abc")),
+            ("/abc~process@1.0", Some("abc")),
+            ("/abc~process@1.0/now/deeper~other@1.0", Some("abc")),
+            ("", None),
+            ("/", None),
+            ("/~process@1.0/now", None),
+            ("/abc/now", None),
+            ("/abc", None),
+            ("/nested/abc~process@1.0/now", None),
+            ("/a b~process@1.0/now", None),
+        ] {
+            assert_eq!(parse_process_id_from_cron_path(path), expected, "{:?}", path);
+        }
+    }
+
+    #[tokio::test]
+    async fn configured_headers_are_sent_to_hyperbeam_and_the_cu() {
+        use crate::config::AuthHeader;
+        use crate::test_support::{client_for, config_for, serve};
+        use axum::http::HeaderMap;
+        use std::sync::{Arc, Mutex};
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let record = |seen: Arc<Mutex<Vec<(String, String)>>>, body: &'static str| {
+            move |headers: HeaderMap| async move {
+                let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok()).unwrap_or_default().to_string();
+                seen.lock().unwrap().push((header("user-agent"), header("x-gateway-token")));
+                body
+            }
+        };
+        let node = axum::Router::new()
+            .route("/:process/slot/current", axum::routing::get(record(seen.clone(), "5")))
+            .route("/dry-run", axum::routing::post(record(seen.clone(), r#"{"Messages": []}"#)));
+        let mut config = config_for(&serve(node).await);
+        config.hyperbeam.user_agent = Some("hydration-test/1.0".to_string());
+        config.hyperbeam.auth_header = Some(AuthHeader {
+            name: "X-Gateway-Token".to_string(),
+            value: "secret".to_string(),
+        });
+        let client = client_for(&config);
+
+        client.check_current_slot(None, "pool").await.unwrap();
+        client.fetch_ao_reserves("pool").await.unwrap();
+
+        let expected = ("hydration-test/1.0".to_string(), "secret".to_string());
+        assert_eq!(*seen.lock().unwrap(), [expected.clone(), expected]);
+    }
+
+    #[tokio::test]
+    async fn reserve_fetches_use_their_own_timeout() {
+        use crate::test_support::{client_for, config_for, serve};
+
+        let delay = Duration::from_millis(1500);
+        let node = axum::Router::new()
+            .route("/:process/slot/current", axum::routing::get(|| async { "5" }))
+            .route("/:process/now/reserves", axum::routing::get(move || async move {
+                tokio::time::sleep(delay).await;
+                axum::Json(HashMap::from([("token".to_string(), "1".to_string())]))
+            }))
+            .route("/dry-run", axum::routing::post(move || async move {
+                tokio::time::sleep(delay).await;
+                r#"{"Messages": []}"#
+            }))
+            .route("/:process/compute/at-slot", axum::routing::get(move || async move {
+                tokio::time::sleep(delay).await;
+                "5"
+            }));
+        let url = serve(node).await;
+        let is_timeout = |error: &anyhow::Error| error.chain()
+            .filter_map(|cause| cause.downcast_ref::<reqwest::Error>())
+            .any(|e| e.is_timeout());
+
+        // A tight reserves timeout trips on slow reserves without affecting fast slot reads
+        let mut config = config_for(&url);
+        config.hyperbeam.reserves_timeout_secs = 1;
+        let client = client_for(&config);
+        assert_eq!(client.check_current_slot(None, "pool").await.unwrap(), 5);
+        assert!(is_timeout(&client.fetch_hb_reserves(None, "pool").await.unwrap_err()));
+        assert!(is_timeout(&client.fetch_ao_reserves("pool").await.unwrap_err()));
+
+        // A looser reserves timeout lets slow reserves finish while slow slot reads time out
+        let mut config = config_for(&url);
+        config.hyperbeam.request_timeout_secs = 1;
+        config.hyperbeam.reserves_timeout_secs = 5;
+        let client = client_for(&config);
+        assert!(is_timeout(&client.get_computed_slot(None, "pool").await.unwrap_err()));
+        assert_eq!(client.fetch_hb_reserves(None, "pool").await.unwrap()["token"], "1");
+        assert!(client.fetch_ao_reserves("pool").await.is_ok());
+    }
+}
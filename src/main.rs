@@ -3,39 +3,90 @@ mod hyperbeam;
 mod queue;
 mod state;
 mod config;
+mod export;
+mod metrics;
+mod validate;
+mod once;
+#[cfg(test)]
+mod test_support;
+#[cfg(unix)]
+mod unix_socket;
 
 use anyhow::{anyhow, Result};
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
-    response::Html,
-    routing::{get, post},
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        Html, IntoResponse, Response,
+    },
+    routing::{get, post, put},
     Json, Router,
 };
 use chrono::Utc;
-use models::{AddProcessRequest, ApiResponse, ApiStatus, Config, ProcessConfig, ProcessState};
-use queue::QueueManager;
-use hyperbeam::{HyperBeamClient, CronItem};
-use config::ServiceConfig;
+use models::{
+    AddProcessPayload, AddProcessRequest, AddProcessResult, ApiResponse, ApiStatus, Config, ErrorCode, HealthStatus,
+    HistoryQuery, Laggard, LaggardsQuery, LogLevel, ProcessConfig, ProcessState, QueuePage, QueueQuery, SetPriorityRequest,
+    SlotSample, SortQuery, StatusQuery, SyncedQuery, TrendSample, MAX_SLOT_HISTORY,
+};
+use queue::{QueueError, QueueManager, UpsertOutcome};
+use hyperbeam::{is_empty_slot_response, parse_process_id_from_cron_path, HyperBeamClient, CronItem, ReservesResult};
+use config::{ReconcileConfig, ReserveFilter, ServiceConfig, UninitializedActivePolicy};
+use futures::{Stream, StreamExt};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::collections::VecDeque;
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::RwLock;
+use tokio::sync::{watch, RwLock};
 use tokio::time::sleep;
 use tower_http::cors::CorsLayer;
-use tracing::{debug, error, info, warn};
+use tracing::{debug, error, info, info_span, warn, Instrument};
 
 struct AppState {
     queue: Arc<QueueManager>,
     client: Arc<HyperBeamClient>,
     start_time: chrono::DateTime<Utc>,
     cron_list: Arc<RwLock<Vec<CronItem>>>,
-    config: Arc<ServiceConfig>,
+    /// Tracked processes that have a cron, rebuilt after every cron list fetch
+    active_from_crons: Arc<RwLock<Vec<models::ProcessStatus>>>,
+    /// Swapped by `/api/config/reload`; loops read it afresh on every iteration
+    config: std::sync::RwLock<Arc<ServiceConfig>>,
+    store: Arc<dyn state::StateStore>,
+    shutdown: watch::Receiver<bool>,
+    /// Whether the last cron list fetch succeeded
+    hyperbeam_reachable: AtomicBool,
+    /// Set once state is loaded and the initial cron list fetch has completed
+    ready: AtomicBool,
+    /// One sample per minute, oldest first, capped at `MAX_TREND_SAMPLES`
+    trends: RwLock<VecDeque<TrendSample>>,
+}
+
+impl AppState {
+    fn config(&self) -> Arc<ServiceConfig> {
+        self.config.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     // Load configuration
     let service_config = ServiceConfig::load()?;
+    
+    // `--validate <pools.json>` checks a pool config and exits without starting the service
+    if std::env::args().nth(1).as_deref() == Some("--validate") {
+        let Some(path) = std::env::args().nth(2) else {
+            eprintln!("Usage: hydration-service --validate <pools.json>");
+            std::process::exit(2);
+        };
+        std::process::exit(validate::run(&path, &service_config));
+    }
+    
+    // `--once [--json]` reads every tracked process's slots once, prints them and exits
+    if std::env::args().nth(1).as_deref() == Some("--once") {
+        let json = std::env::args().nth(2).as_deref() == Some("--json");
+        std::process::exit(once::run(&service_config, json).await);
+    }
+    
     let service_config = Arc::new(service_config);
     
     // Initialize tracing based on config
@@ -45,18 +96,27 @@ async fn main() -> Result<()> {
         .init();
 
     info!("Starting Hydration Service");
-    info!("Using HyperBEAM URL: {}", service_config.hyperbeam.base_url);
+    info!("Using HyperBEAM nodes: {}", service_config.hyperbeam.nodes().join(", "));
     info!("Using AO CU URL: {}", service_config.ao.cu_url);
 
     // Initialize components
-    let queue = Arc::new(QueueManager::new(service_config.limits.max_active_processes));
+    let queue = Arc::new(QueueManager::new(
+        service_config.limits.max_active_processes,
+        service_config.limits.max_queue_size,
+        service_config.limits.max_init_attempts,
+        service_config.limits.process_allowlist.clone(),
+        service_config.limits.process_denylist.clone(),
+        service_config.limits.queue_strategy,
+    ));
     let client = Arc::new(HyperBeamClient::new(
-        service_config.hyperbeam.base_url.clone(),
+        &service_config.hyperbeam,
         service_config.ao.cu_url.clone(),
+        service_config.reserves.clone(),
     ));
     
     // Load previous state
-    let state_loaded = state::load_state(&queue).await?;
+    let store = state::open_store(&service_config.state)?;
+    let state_loaded = state::load_state(&store, &queue).await?;
     if state_loaded {
         info!("Loaded previous state from disk");
     }
@@ -67,43 +127,22 @@ async fn main() -> Result<()> {
         let config_str = tokio::fs::read_to_string(config_path).await?;
         let config: Config = serde_json::from_str(&config_str)?;
         
-        // Reconcile config with existing state
-        let existing_processes = queue.all_processes.read().await;
-        let existing_ids: std::collections::HashSet<String> = existing_processes.keys().cloned().collect();
-        drop(existing_processes);
-        
-        let mut new_processes = 0;
-        let mut existing_in_config = 0;
-        
-        for process_config in config.processes {
-            if existing_ids.contains(&process_config.process_id) {
-                // Process already exists in state, update name and base_url from config
-                existing_in_config += 1;
-                info!("Process {} already in state, updating name and keeping existing state", process_config.name);
-                
-                // Update name and base_url from config
-                queue.update_process_config(&process_config.process_id, process_config.name.clone(), process_config.base_url).await;
-            } else {
-                // New process not in state, add to queue
-                if let Err(e) = queue.add_to_queue(process_config.clone()).await {
-                    warn!("Failed to add process {} to queue: {}", process_config.name, e);
-                } else {
-                    new_processes += 1;
-                    info!("Added new process {} to queue", process_config.name);
-                }
-            }
-        }
-        
-        info!("Config reconciliation: {} new processes added, {} already existed", 
-              new_processes, existing_in_config);
+        reconcile_config(&queue, config.processes, &service_config.reconcile).await;
     }
 
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
     let app_state = Arc::new(AppState {
         queue: queue.clone(),
         client: client.clone(),
         start_time: Utc::now(),
         cron_list: Arc::new(RwLock::new(Vec::new())),
-        config: service_config.clone(),
+        active_from_crons: Arc::new(RwLock::new(Vec::new())),
+        config: std::sync::RwLock::new(service_config.clone()),
+        store: store.clone(),
+        shutdown: shutdown_rx,
+        hyperbeam_reachable: AtomicBool::new(false),
+        ready: AtomicBool::new(false),
+        trends: RwLock::new(VecDeque::new()),
     });
 
     // Recovery: Check active processes that are initialized but have no slot values
@@ -114,9 +153,11 @@ async fn main() -> Result<()> {
             let client_clone = client.clone();
             let queue_clone = queue.clone();
             let process_id = process.process_id.clone();
+            let base_url = process.base_url.clone();
             
+            let span = info_span!("recover", process_id = %process_id);
             tokio::spawn(async move {
-                match client_clone.check_slots(None, &process_id).await {
+                match client_clone.check_slots(base_url.as_deref(), &process_id).await {
                     Ok(result) => {
                         let _ = queue_clone.update_process_status(&process_id, |status| {
                             status.computed_slot = Some(result.computed_slot);
@@ -137,10 +178,14 @@ async fn main() -> Result<()> {
                         info!("Could not recover slots for process {} (might not be deployed yet): {}", process_id, e);
                     }
                 }
-            });
+            }.instrument(span));
         }
     }
 
+    // Recovery: Active processes whose cron was never initialized are skipped by the
+    // monitor loop and are not in the queue, so they need to be picked up again
+    recover_uninitialized_active(&client, &queue, service_config.reconcile.uninitialized_active).await;
+
     // Start monitoring task
     let monitor_state = app_state.clone();
     tokio::spawn(async move {
@@ -158,11 +203,14 @@ async fn main() -> Result<()> {
     tokio::spawn(async move {
         // Fetch cron list immediately on startup
         info!("Fetching initial cron list from HyperBEAM");
-        if let Ok(cron_items) = cron_monitor_state.client.fetch_cron_list(None).await {
-            let mut cron_list = cron_monitor_state.cron_list.write().await;
-            *cron_list = cron_items;
-            info!("Initial cron list loaded with {} items", cron_list.len());
+        if let Ok(cron_items) = cron_monitor_state.client.fetch_all_cron_lists().await {
+            let count = cron_items.len();
+            *cron_monitor_state.cron_list.write().await = cron_items;
+            refresh_active_from_crons(&cron_monitor_state).await;
+            cron_monitor_state.hyperbeam_reachable.store(true, Ordering::Relaxed);
+            info!("Initial cron list loaded with {} items", count);
         }
+        cron_monitor_state.ready.store(true, Ordering::Relaxed);
         
         // Then continue monitoring
         monitor_cron_list(cron_monitor_state).await;
@@ -173,31 +221,266 @@ async fn main() -> Result<()> {
     tokio::spawn(async move {
         monitor_queue_slots(queue_monitor_state).await;
     });
+    
+    // Start service-wide trend sampling
+    let trends_state = app_state.clone();
+    tokio::spawn(async move {
+        record_trends(trends_state).await;
+    });
+
+    let shutdown = async move {
+        shutdown_signal().await;
+        // Let long-lived streams end so in-flight connections can drain
+        let _ = shutdown_tx.send(true);
+    };
+    serve_until_shutdown(app_state, shutdown).await
+}
+
+/// Serve the API until `shutdown` resolves, then save state one final time.
+async fn serve_until_shutdown(
+    app_state: Arc<AppState>,
+    shutdown: impl std::future::Future<Output = ()> + Send + 'static,
+) -> Result<()> {
+    let service_config = app_state.config();
+    let app = router(app_state.clone());
+    
+    match &service_config.server.unix_socket {
+        #[cfg(unix)]
+        Some(path) => {
+            info!("Server running on unix:{}", path);
+            unix_socket::serve(path, app, shutdown).await?;
+        }
+        #[cfg(not(unix))]
+        Some(_) => return Err(anyhow!("server.unix_socket is only supported on Unix")),
+        None => {
+            let bind_addr = format!("{}:{}", service_config.server.host, service_config.server.port);
+            let listener = tokio::net::TcpListener::bind(&bind_addr)
+                .await?;
+            
+            info!("Server running on http://{}", bind_addr);
+            
+            axum::serve(listener, app)
+                .with_graceful_shutdown(shutdown)
+                .await?;
+        }
+    }
+    
+    info!("Shutting down, saving state");
+    let saved = match state::save_state(&app_state.store, &app_state.queue, &service_config.state).await {
+        Ok(_) => true,
+        Err(e) => {
+            error!("Failed to save state on shutdown: {}", e);
+            false
+        }
+    };
+    log_shutdown_report(&app_state, saved).await;
+    
+    Ok(())
+}
+
+/// Re-initialize or requeue, per `policy`, active processes restored without a cron.
+async fn recover_uninitialized_active(client: &Arc<HyperBeamClient>, queue: &Arc<QueueManager>, policy: UninitializedActivePolicy) {
+    let active_processes = queue.get_active_processes().await;
+    // Paused processes are left alone, including those whose cron an operator stopped
+    for process in active_processes.into_iter().filter(|p| !p.cron_initialized && !p.paused) {
+        let config = ProcessConfig {
+            name: process.name.clone(),
+            process_id: process.process_id.clone(),
+            base_url: process.base_url.clone(),
+        };
+        
+        match policy {
+            UninitializedActivePolicy::Reinitialize => {
+                info!("Re-initializing active process {} with no cron", process.process_id);
+                let client_clone = client.clone();
+                let queue_clone = queue.clone();
+                
+                let span = info_span!("init", process_id = %config.process_id);
+                tokio::spawn(async move {
+                    if let Err(e) = initialize_process(&client_clone, &queue_clone, &config).await {
+                        error!("Failed to initialize {}: {}", config.process_id, e);
+                        let _ = queue_clone.mark_init_failed(&config.process_id, e.to_string()).await;
+                    }
+                }.instrument(span));
+            }
+            UninitializedActivePolicy::Requeue => {
+                info!("Requeuing active process {} with no cron", process.process_id);
+                if let Err(e) = queue.requeue(config).await {
+                    warn!("Failed to requeue process {}: {}", process.process_id, e);
+                }
+            }
+        }
+    }
+}
+
+/// Merge the pool config into the state loaded from disk: new processes are queued,
+/// known ones keep their state but take the configured name and base_url.
+async fn reconcile_config(queue: &QueueManager, processes: Vec<ProcessConfig>, reconcile: &ReconcileConfig) {
+    let existing_processes = queue.all_processes.read().await;
+    let existing_ids: std::collections::HashSet<String> = existing_processes.keys().cloned().collect();
+    drop(existing_processes);
+    
+    let mut new_processes = 0;
+    let mut existing_in_config = 0;
+    let mut requeued = 0;
+    
+    for process_config in processes {
+        let is_synced = queue.synced.read().await.contains_key(&process_config.process_id);
+        if is_synced && reconcile.requeue_synced_on_config {
+            // Synced process listed again in config, requeue it for re-verification
+            existing_in_config += 1;
+            match queue.requeue(process_config.clone()).await {
+                Ok(_) => {
+                    requeued += 1;
+                    info!("Process {} already synced, requeued for re-verification", process_config.name);
+                }
+                Err(e) => warn!("Failed to requeue synced process {}: {}", process_config.name, e),
+            }
+        } else {
+            match queue.upsert_to_queue(process_config.clone()).await {
+                Ok(UpsertOutcome::Added) => {
+                    new_processes += 1;
+                    info!("Added new process {} to queue", process_config.name);
+                }
+                Ok(UpsertOutcome::Updated) => {
+                    existing_in_config += 1;
+                    info!("Process {} already queued, updated name and base_url", process_config.name);
+                }
+                Err(_) if existing_ids.contains(&process_config.process_id) => {
+                    // Active, synced or errored: keep its state but take the name and base_url from config
+                    existing_in_config += 1;
+                    info!("Process {} already in state, updating name and keeping existing state", process_config.name);
+                    queue.update_process_config(&process_config.process_id, process_config.name.clone(), process_config.base_url).await;
+                }
+                Err(e) => warn!("Failed to add process {} to queue: {}", process_config.name, e),
+            }
+        }
+    }
+    
+    info!("Config reconciliation: {} new processes added, {} already existed, {} synced requeued", 
+          new_processes, existing_in_config, requeued);
+}
 
-    // Build router
-    let app = Router::new()
+fn router(app_state: Arc<AppState>) -> Router {
+    Router::new()
         .route("/", get(render_tui))
+        .route("/api/health", get(get_health))
+        .route("/api/health/ready", get(get_readiness))
+        .route("/api/version", get(get_version))
         .route("/api/status", get(get_status))
+        .route("/api/stream", get(stream_status))
         .route("/api/state", get(get_state))
+        .route("/api/laggards", get(get_laggards))
+        .route("/api/metrics", get(get_metrics))
+        .route("/api/trends", get(get_trends))
+        .route("/api/cron", get(get_cron_list))
+        .route("/api/config/reload", post(reload_config))
+        .route("/api/export/synced.csv", get(export_synced_csv))
+        .route("/api/active", get(get_active_from_crons))
+        .route("/api/initializing", get(get_initializing))
+        .route("/api/queue", get(get_queue))
+        .route("/api/synced", get(get_synced))
         .route("/api/queue/add", post(add_to_queue))
+        .route("/api/queue/clear", post(clear_queue))
+        .route("/api/process/:id", get(get_process).delete(remove_process))
+        .route("/api/process/by-name/:name", get(get_processes_by_name))
+        .route("/api/process/:id/history", get(get_process_history))
         .route("/api/process/:id/restart", post(restart_process))
+        .route("/api/process/:id/priority", put(set_priority))
+        .route("/api/process/:id/reset-metrics", post(reset_metrics))
+        .route("/api/process/:id/force-resync", post(force_resync))
+        .route("/api/process/:id/stop-cron", post(stop_cron))
+        .route("/api/process/:id/reserves/refresh", post(refresh_reserves))
+        .route("/api/process/:id/pause", post(pause_process))
+        .route("/api/process/:id/unpause", post(unpause_process))
         .layer(CorsLayer::permissive())
-        .with_state(app_state);
+        .with_state(app_state)
+}
 
-    let bind_addr = format!("{}:{}", service_config.server.host, service_config.server.port);
-    let listener = tokio::net::TcpListener::bind(&bind_addr)
-        .await?;
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            error!("Failed to listen for SIGINT: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+    
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                error!("Failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
     
-    info!("Server running on http://{}", bind_addr);
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
     
-    axum::serve(listener, app).await?;
+    tokio::select! {
+        _ = ctrl_c => info!("Received SIGINT"),
+        _ = terminate => info!("Received SIGTERM"),
+    }
+}
+
+async fn log_shutdown_report(state: &AppState, saved: bool) {
+    let counts = state.queue.count_by_state().await;
+    let count = |s: ProcessState| counts.get(&s).copied().unwrap_or(0);
+    let persisted = if saved { counts.values().sum() } else { 0 };
+    let uptime = (Utc::now() - state.start_time).num_seconds();
     
-    Ok(())
+    info!(
+        persisted,
+        queued = count(ProcessState::Queued),
+        active = count(ProcessState::Active),
+        synced = count(ProcessState::Synced),
+        errored = count(ProcessState::Error),
+        uptime_seconds = uptime,
+        hb_requests = state.client.stats.hb_requests(),
+        ao_requests = state.client.stats.ao_requests(),
+        request_errors = state.client.stats.errors(),
+        "Shutdown report"
+    );
+}
+
+const TREND_INTERVAL: Duration = Duration::from_secs(60);
+
+/// A day of samples at `TREND_INTERVAL`
+const MAX_TREND_SAMPLES: usize = 1440;
+
+async fn record_trends(state: Arc<AppState>) {
+    let mut ticker = tokio::time::interval(TREND_INTERVAL);
+    loop {
+        ticker.tick().await;
+        record_trend_sample(&state).await;
+    }
+}
+
+/// Append a sample of the current counts, dropping the oldest once the history is full.
+async fn record_trend_sample(state: &AppState) {
+    let (active, queued, synced) = state.queue.get_status().await;
+    let sample = TrendSample {
+        timestamp: Utc::now(),
+        active,
+        queued,
+        synced,
+        aggregate_rate: state.queue.aggregate_sync_rate().await,
+    };
+    
+    let mut trends = state.trends.write().await;
+    if trends.len() >= MAX_TREND_SAMPLES {
+        trends.pop_front();
+    }
+    trends.push_back(sample);
 }
 
 async fn monitor_queue_slots(state: Arc<AppState>) {
     // Initial delay to let things settle
-    sleep(Duration::from_secs(10)).await;
+    sleep(Duration::from_secs(state.config().monitoring.queue_slots_delay)).await;
     
     loop {
         // Get queued processes
@@ -207,40 +490,40 @@ async fn monitor_queue_slots(state: Arc<AppState>) {
         if queue_count > 0 {
             debug!("Checking current slots for {} queued processes", queue_count);
             
-            for process in queue_preview {
-                let client = state.client.clone();
-                let queue = state.queue.clone();
+            // The client's request semaphore keeps this from overwhelming the API. The
+            // batch is awaited so a slow one cannot overlap the next.
+            let checks = queue_preview.into_iter().filter(|p| !p.paused).map(|process| {
                 let pid = process.process_id.clone();
-                
-                // Don't spawn, do it sequentially to avoid overwhelming the API
-                match client.check_current_slot(None, &pid).await {
-                    Ok(current_slot) => {
-                        debug!("Got current slot {} for queued process {}", current_slot, &pid[..8]);
-                        let _ = queue.update_process_status(&pid, |status| {
-                            status.current_slot = Some(current_slot);
-                            status.last_checked = Some(Utc::now());
-                        }).await;
-                    },
-                    Err(_) => {
-                        // Process might not exist yet, which is normal for queued items
+                let span = info_span!("queue_slot", process_id = %pid);
+                let state = &state;
+                async move {
+                    match state.client.check_current_slot(process.base_url.as_deref(), &pid).await {
+                        Ok(current_slot) => {
+                            debug!("Got current slot {} for queued process {}", current_slot, shorten_id(&pid, 8));
+                            let _ = state.queue.update_process_status(&pid, |status| {
+                                status.current_slot = Some(current_slot);
+                                status.last_checked = Some(Utc::now());
+                            }).await;
+                        },
+                        Err(_) => {
+                            // Process might not exist yet, which is normal for queued items
+                        }
                     }
-                }
-                
-                // Small delay between checks
-                sleep(Duration::from_millis(100)).await;
-            }
+                }.instrument(span)
+            });
+            futures::future::join_all(checks).await;
         }
         
-        // Update every 30 seconds
-        sleep(Duration::from_secs(30)).await;
+        sleep(Duration::from_secs(state.config().monitoring.queue_slots_interval)).await;
     }
 }
 
 async fn monitor_cron_list(state: Arc<AppState>) {
     loop {
         // Fetch cron list from HyperBEAM
-        match state.client.fetch_cron_list(None).await {
+        match state.client.fetch_all_cron_lists().await {
             Ok(cron_items) => {
+                state.hyperbeam_reachable.store(true, Ordering::Relaxed);
                 let count = cron_items.len();
                 info!("Fetched {} cron items from HyperBEAM", count);
                 
@@ -248,13 +531,12 @@ async fn monitor_cron_list(state: Arc<AppState>) {
                 let mut cron_list = state.cron_list.write().await;
                 *cron_list = cron_items.clone();
                 drop(cron_list);
+                refresh_active_from_crons(&state).await;
                 
                 // Build set of process IDs that have active crons
                 let mut cron_process_ids = std::collections::HashSet::new();
                 for cron_item in &cron_items {
-                    if let Some(process_id) = cron_item.path
-                        .strip_prefix("/")
-                        .and_then(|p| p.split("~").next()) {
+                    if let Some(process_id) = parse_process_id_from_cron_path(&cron_item.path) {
                         cron_process_ids.insert(process_id.to_string());
                     }
                 }
@@ -276,25 +558,26 @@ async fn monitor_cron_list(state: Arc<AppState>) {
                         if let Some(status) = all.get_mut(&process_id) {
                             // Don't change to error, just mark as queued again if it was active
                             if status.state == ProcessState::Active {
+                                status.close_active_period();
                                 status.state = ProcessState::Queued;
                                 info!("Process {} moved back to queued state", process_id);
                             }
                         }
+                        state.queue.mark_changed();
                     }
                 }
                 
                 // Check slots for each active cron process
                 for cron_item in &cron_items {
                     // Extract process ID from path (format: /processId~process@1.0/now)
-                    if let Some(process_id) = cron_item.path
-                        .strip_prefix("/")
-                        .and_then(|p| p.split("~").next()) {
+                    if let Some(process_id) = parse_process_id_from_cron_path(&cron_item.path) {
                         
                         let created_at = chrono::DateTime::from_timestamp_millis(cron_item.created_at as i64);
                         
                         // Check if we're tracking this process and fetch its slots
                         let all_processes = state.queue.all_processes.read().await;
-                        if all_processes.contains_key(process_id) {
+                        if let Some(tracked) = all_processes.get(process_id).filter(|p| !p.paused) {
+                            let base_url = tracked.base_url.clone();
                             drop(all_processes);
                             
                             // Fetch current slot values for this active process
@@ -302,9 +585,25 @@ async fn monitor_cron_list(state: Arc<AppState>) {
                             let queue = state.queue.clone();
                             let pid = process_id.to_string();
                             
+                            let max_rate = state.config().monitoring.max_slots_per_minute;
+                            let confirm_synced = state.config().monitoring.confirm_synced_read;
+                            let sync_tolerance = state.config().monitoring.sync_tolerance;
+                            let webhook_url = state.config().notifications.webhook_url.clone();
+                            
+                            let span = info_span!("check", process_id = %pid);
                             tokio::spawn(async move {
-                                match client.check_slots(None, &pid).await {
+                                match client.check_slots(base_url.as_deref(), &pid).await {
                                     Ok(result) => {
+                                        let plausible = queue.all_processes.read().await
+                                            .get(&pid)
+                                            .map(|s| s.is_plausible_reading(result.computed_slot, result.current_slot, max_rate))
+                                            .unwrap_or(true);
+                                        if !plausible {
+                                            warn!("Discarding implausible slot reading for {} - Computed: {}, Current: {}",
+                                                pid, result.computed_slot, result.current_slot);
+                                            return;
+                                        }
+                                        
                                         // First update the status
                                         let _ = queue.update_process_status(&pid, |status| {
                                             let old_computed = status.computed_slot;
@@ -317,6 +616,7 @@ async fn monitor_cron_list(state: Arc<AppState>) {
                                             if let Some(prev) = old_computed {
                                                 if result.computed_slot > prev {
                                                     status.metrics.total_slots_advanced += result.computed_slot - prev;
+                                                    status.last_advanced_at = Some(Utc::now());
                                                 }
                                             }
                                             
@@ -330,32 +630,77 @@ async fn monitor_cron_list(state: Arc<AppState>) {
                                         }).await;
                                         
                                         // Check if synced and use the proper queue method
-                                        if result.is_synced() {
-                                            if let Err(e) = queue.mark_synced(&pid).await {
-                                                // Process might already be marked as synced
-                                                debug!("Failed to mark {} as synced: {}", pid, e);
-                                            } else {
-                                                info!("Process {} is now synced via cron check!", pid);
+                                        if result.is_synced(sync_tolerance) {
+                                            if confirm_synced && !client.confirm_synced(base_url.as_deref(), &pid, sync_tolerance).await {
+                                                debug!("Sync of {} not confirmed by sequential read", pid);
+                                                return;
+                                            }
+                                            match queue.mark_synced(&pid).await {
+                                                Ok(true) => {
+                                                    info!("Process {} is now synced via cron check!", pid);
+                                                    notify_synced(&client, &queue, webhook_url.as_deref(), &pid).await;
+                                                }
+                                                Ok(false) => {}
+                                                Err(e) => debug!("Failed to mark {} as synced: {}", pid, e),
                                             }
                                         }
                                     },
                                     Err(e) => {
-                                        error!("Failed to check slots for active process {}: {}", pid, e);
+                                        if is_empty_slot_response(&e) {
+                                            debug!("Empty slot response for active process {}", pid);
+                                        } else {
+                                            error!("Failed to check slots for active process {}: {}", pid, e);
+                                        }
                                     }
                                 }
-                            });
+                            }.instrument(span));
                         }
                     }
                 }
             },
             Err(e) => {
+                state.hyperbeam_reachable.store(false, Ordering::Relaxed);
                 error!("Failed to fetch cron list: {}", e);
             }
         }
         
-        // Update every 15 seconds for faster refresh
-        sleep(Duration::from_secs(15)).await;
+        sleep(Duration::from_secs(state.config().monitoring.cron_list_interval)).await;
+    }
+}
+
+/// Rebuild the list of tracked processes that have a cron on HyperBEAM, with each
+/// process's sync rate measured from its cron creation time.
+async fn refresh_active_from_crons(state: &AppState) {
+    let cron_list = state.cron_list.read().await.clone();
+    let all_processes = state.queue.all_processes.read().await;
+    let mut active_from_crons = Vec::new();
+    
+    for cron_item in &cron_list {
+        // Extract process ID from path
+        if let Some(process_id) = parse_process_id_from_cron_path(&cron_item.path) {
+            
+            // Check if we're tracking this process
+            if let Some(process) = all_processes.get(process_id) {
+                let mut process_with_cron = process.clone();
+                // Update with cron created time
+                let created_at = chrono::DateTime::from_timestamp_millis(cron_item.created_at as i64);
+                process_with_cron.cron_created_at = created_at;
+                
+                // Calculate real-time sync rate based on cron creation
+                if let Some(created) = created_at {
+                    let minutes_elapsed = (Utc::now() - created).num_seconds() as f64 / 60.0;
+                    if minutes_elapsed > 0.0 && process_with_cron.metrics.total_slots_advanced > 0 {
+                        process_with_cron.metrics.avg_sync_rate = process_with_cron.metrics.total_slots_advanced as f64 / minutes_elapsed;
+                    }
+                }
+                
+                active_from_crons.push(process_with_cron);
+            }
+        }
     }
+    drop(all_processes);
+    
+    *state.active_from_crons.write().await = active_from_crons;
 }
 
 async fn monitor_synced_pools(state: Arc<AppState>) {
@@ -373,17 +718,24 @@ async fn monitor_synced_pools(state: Arc<AppState>) {
             info!("Checking {} synced pools for updates", synced_count);
         }
         
-        for (process_id, _) in synced {
+        for (process_id, status) in synced.into_iter().filter(|(_, status)| !status.paused) {
             let client = state.client.clone();
             let queue = state.queue.clone();
             let pid = process_id.clone();
+            let base_url = status.base_url;
+            let name = status.name;
+            let requeue_threshold = state.config().monitoring.requeue_on_desync
+                .then_some(state.config().monitoring.desync_threshold);
+            let sync_tolerance = state.config().monitoring.sync_tolerance;
+            let fetch_reserves = state.config().monitoring.fetch_reserves;
             
+            let span = info_span!("synced_check", process_id = %pid);
             tokio::spawn(async move {
                 // Check both computed and current slots
-                match client.check_slots(None, &process_id).await {
+                match client.check_slots(base_url.as_deref(), &process_id).await {
                     Ok(result) => {
                         let was_synced = queue.synced.read().await.contains_key(&process_id);
-                        let still_synced = result.is_synced();
+                        let still_synced = result.is_synced(sync_tolerance);
                         
                         // Update slot values
                         let update_result = queue.update_process_status(&process_id, |status| {
@@ -397,7 +749,7 @@ async fn monitor_synced_pools(state: Arc<AppState>) {
                             // Log if values changed
                             if old_computed != Some(result.computed_slot) || old_current != Some(result.current_slot) {
                                 info!("Pool {} slots updated: computed {} -> {}, current {} -> {}", 
-                                    &pid[..8],
+                                    shorten_id(&pid, 8),
                                     old_computed.unwrap_or(0), result.computed_slot,
                                     old_current.unwrap_or(0), result.current_slot
                                 );
@@ -406,21 +758,47 @@ async fn monitor_synced_pools(state: Arc<AppState>) {
                             // If no longer synced, mark it but keep in synced list for monitoring
                             if was_synced && !still_synced {
                                 warn!("Pool {} is no longer synced! Computed: {}, Current: {}", 
-                                    &pid[..8], result.computed_slot, result.current_slot);
+                                    shorten_id(&pid, 8), result.computed_slot, result.current_slot);
                             }
                         }).await;
                         
                         if let Err(e) = update_result {
-                            error!("Failed to update slots for {}: {}", &pid[..8], e);
+                            error!("Failed to update slots for {}: {}", shorten_id(&pid, 8), e);
+                        }
+                        
+                        // Send pools that fell too far behind back through the queue
+                        if let Some(threshold) = requeue_threshold {
+                            if result.deficit() > threshold {
+                                let config = ProcessConfig {
+                                    name,
+                                    process_id: process_id.clone(),
+                                    base_url: base_url.clone(),
+                                };
+                                match queue.requeue(config).await {
+                                    Ok(_) => {
+                                        warn!("Pool {} drifted {} slots behind, requeued", shorten_id(&pid, 8), result.deficit());
+                                        return;
+                                    }
+                                    Err(e) => error!("Failed to requeue desynced pool {}: {}", shorten_id(&pid, 8), e),
+                                }
+                            }
                         }
                     },
                     Err(e) => {
-                        error!("Failed to check slots for {}: {}", &pid[..8], e);
+                        if is_empty_slot_response(&e) {
+                            debug!("Empty slot response for {}", shorten_id(&pid, 8));
+                        } else {
+                            error!("Failed to check slots for {}: {}", shorten_id(&pid, 8), e);
+                        }
                     }
                 }
                 
+                if !fetch_reserves {
+                    return;
+                }
+                
                 // Fetch reserves
-                match client.fetch_reserves(None, &process_id).await {
+                match client.fetch_reserves(base_url.as_deref(), &process_id).await {
                     Ok(reserves) => {
                         let hb_count = reserves.hb_reserves.as_ref().map(|r| r.len()).unwrap_or(0);
                         let ao_count = reserves.ao_reserves.as_ref().map(|r| r.len()).unwrap_or(0);
@@ -436,23 +814,22 @@ async fn monitor_synced_pools(state: Arc<AppState>) {
                             // Log if reserve counts changed
                             if old_hb_count != hb_count || old_ao_count != ao_count {
                                 info!("Pool {} reserves updated: HB {} -> {}, AO {} -> {}", 
-                                    &pid[..8], old_hb_count, hb_count, old_ao_count, ao_count);
+                                    shorten_id(&pid, 8), old_hb_count, hb_count, old_ao_count, ao_count);
                             }
                         }).await;
                         
                         if let Err(e) = update_result {
-                            error!("Failed to update reserves for {}: {}", &pid[..8], e);
+                            error!("Failed to update reserves for {}: {}", shorten_id(&pid, 8), e);
                         }
                     },
                     Err(e) => {
-                        error!("Failed to fetch reserves for {}: {}", &pid[..8], e);
+                        error!("Failed to fetch reserves for {}: {}", shorten_id(&pid, 8), e);
                     }
                 }
-            });
+            }.instrument(span));
         }
         
-        // Check every 60 seconds for synced pools
-        sleep(Duration::from_secs(60)).await;
+        sleep(Duration::from_secs(state.config().monitoring.synced_pools_interval)).await;
     }
 }
 
@@ -462,28 +839,35 @@ async fn monitor_loop(state: Arc<AppState>) {
         let active = state.queue.get_active_processes().await;
         
         for process in active {
-            // Skip if process hasn't been initialized yet
-            if !process.cron_initialized {
+            // Skip if process hasn't been initialized yet or is paused
+            if !process.cron_initialized || process.paused {
                 continue;
             }
             
             let client = state.client.clone();
             let queue = state.queue.clone();
             let process_id = process.process_id.clone();
+            let config = state.config();
             
+            let span = info_span!("check", process_id = %process_id);
             tokio::spawn(async move {
-                if let Err(e) = check_process(&client, &queue, &process).await {
-                    error!("Error checking process {}: {}", process_id, e);
+                if let Err(e) = check_process(&client, &queue, &process, &config).await {
+                    if is_empty_slot_response(&e) {
+                        debug!("Empty slot response for process {}", process_id);
+                    } else {
+                        error!("Error checking process {}: {}", process_id, e);
+                    }
                 }
-            });
+            }.instrument(span));
         }
         
         // Try to activate next process
         let active_count = state.queue.active.read().await.len();
         let queued_count = state.queue.queued.read().await.len();
         
-        if active_count < 5 && queued_count > 0 {
-            debug!("Active: {}/5, Queued: {} - attempting to activate more", active_count, queued_count);
+        let max_active = state.queue.max_active_processes();
+        if active_count < max_active && queued_count > 0 {
+            debug!("Active: {}/{}, Queued: {} - attempting to activate more", active_count, max_active, queued_count);
         }
         
         while let Some(config) = state.queue.activate_next().await {
@@ -492,21 +876,22 @@ async fn monitor_loop(state: Arc<AppState>) {
             let client = state.client.clone();
             let queue = state.queue.clone();
             
+            let span = info_span!("init", process_id = %config.process_id);
             tokio::spawn(async move {
                 if let Err(e) = initialize_process(&client, &queue, &config).await {
                     error!("Failed to initialize {}: {}", config.process_id, e);
                     // Important: Remove from active on error so slot can be reused
-                    let _ = queue.mark_error(&config.process_id, e.to_string()).await;
+                    let _ = queue.mark_init_failed(&config.process_id, e.to_string()).await;
                 }
-            });
+            }.instrument(span));
         }
         
-        // Save state
-        if let Err(e) = state::save_state(&state.queue).await {
+        // Save state, skipping the write when nothing changed since the last save
+        if let Err(e) = state::save_state_if_changed(&state.store, &state.queue, &state.config().state).await {
             error!("Failed to save state: {}", e);
         }
         
-        sleep(Duration::from_secs(15)).await;
+        sleep(Duration::from_secs(state.config().monitoring.monitor_loop_interval)).await;
     }
 }
 
@@ -514,12 +899,31 @@ async fn check_process(
     client: &HyperBeamClient,
     queue: &QueueManager,
     process: &models::ProcessStatus,
+    config: &ServiceConfig,
 ) -> Result<()> {
-    let result = client.check_slots(None, &process.process_id).await?;
+    let monitoring = &config.monitoring;
+    let base_url = process.base_url.as_deref();
+    let result = client.check_slots(base_url, &process.process_id).await?;
+    
+    if !process.is_plausible_reading(result.computed_slot, result.current_slot, monitoring.max_slots_per_minute) {
+        warn!("Discarding implausible slot reading for {} - Computed: {}, Current: {}",
+            process.process_id, result.computed_slot, result.current_slot);
+        let _ = queue.update_process_status(&process.process_id, |status| {
+            status.log_activity(LogLevel::Warn, format!(
+                "Discarded implausible slot reading - Computed: {}, Current: {}",
+                result.computed_slot, result.current_slot
+            ));
+        }).await;
+        return Ok(());
+    }
     
-    let previous_computed = process.computed_slot;
+    let mut stalled = false;
     
     queue.update_process_status(&process.process_id, |status| {
+        // Read from the live status rather than `process`, which may predate another check
+        let previous_computed = status.computed_slot;
+        let previous_check = status.last_checked;
+        
         // Update slots
         status.computed_slot = Some(result.computed_slot);
         status.current_slot = Some(result.current_slot);
@@ -527,23 +931,46 @@ async fn check_process(
         
         // Update metrics
         status.metrics.check_count += 1;
-        status.metrics.api_response_times.push(result.computed_response_time);
-        status.metrics.api_response_times.push(result.current_response_time);
-        
-        if status.metrics.api_response_times.len() > 20 {
-            status.metrics.api_response_times = status.metrics.api_response_times[status.metrics.api_response_times.len() - 20..].to_vec();
-        }
+        status.metrics.record_slot_sample(result.computed_slot, result.current_slot);
+        status.metrics.record_response_time(result.computed_response_time, monitoring.response_time_window);
+        status.metrics.record_response_time(result.current_response_time, monitoring.response_time_window);
         
         // Track advancement
         if let Some(prev) = previous_computed {
             if result.computed_slot > prev {
                 status.metrics.slots_advanced_last_check = result.computed_slot - prev;
                 status.metrics.total_slots_advanced += status.metrics.slots_advanced_last_check;
+                status.last_advanced_at = Some(Utc::now());
+                status.log_activity(LogLevel::Info, format!(
+                    "Computed slot advanced {} -> {}", prev, result.computed_slot
+                ));
+            } else if prev - result.computed_slot > monitoring.slot_regression_threshold {
+                warn!("Computed slot of {} went backwards {} -> {}, resetting sync rate baseline",
+                    process.process_id, prev, result.computed_slot);
+                status.log_activity(LogLevel::Warn, format!(
+                    "Computed slot regressed {} -> {}", prev, result.computed_slot
+                ));
+                status.metrics.reset_sync_baseline();
             } else {
                 status.metrics.slots_advanced_last_check = 0;
             }
         }
         
+        if let (Some(_), Some(checked)) = (previous_computed, previous_check) {
+            let minutes = (Utc::now() - checked).num_milliseconds() as f64 / 60_000.0;
+            let advanced = status.metrics.slots_advanced_last_check;
+            status.metrics.update_instant_rate(advanced, minutes, monitoring.rate_smoothing);
+        }
+        
+        if previous_computed.is_some() && status.metrics.record_progress(result.deficit(), monitoring.stall_threshold) {
+            warn!("Process {} has not advanced in {} checks (computed slot {}, deficit {})",
+                process.process_id, status.metrics.checks_without_progress, result.computed_slot, result.deficit());
+            status.log_activity(LogLevel::Warn, format!(
+                "Stalled: no progress in {} checks", status.metrics.checks_without_progress
+            ));
+            stalled = true;
+        }
+        
         // Track initial deficit
         if status.metrics.initial_slot_deficit.is_none() {
             status.metrics.initial_slot_deficit = Some(result.deficit());
@@ -559,14 +986,30 @@ async fn check_process(
         }
     }).await.map_err(|e| anyhow!(e))?;
     
+    if stalled && config.notifications.notify_on_stall {
+        notify_stalled(client, queue, config.notifications.webhook_url.as_deref(), &process.process_id).await;
+    }
+    
     // Check if synced
-    if result.is_synced() {
+    if result.is_synced(monitoring.sync_tolerance) {
+        if monitoring.confirm_synced_read && !client.confirm_synced(base_url, &process.process_id, monitoring.sync_tolerance).await {
+            debug!("Sync of {} not confirmed by sequential read", process.process_id);
+            return Ok(());
+        }
+        
+        if !queue.mark_synced(&process.process_id).await.map_err(|e| anyhow!(e))? {
+            return Ok(());
+        }
         info!("Process {} is synced!", process.process_id);
-        queue.mark_synced(&process.process_id).await.map_err(|e| anyhow!(e))?;
+        notify_synced(client, queue, config.notifications.webhook_url.as_deref(), &process.process_id).await;
+        
+        if !monitoring.fetch_reserves {
+            return Ok(());
+        }
         
         // Immediately fetch reserves for newly synced pool
         info!("Fetching reserves for newly synced pool: {}", process.process_id);
-        if let Ok(reserves) = client.fetch_reserves(None, &process.process_id).await {
+        if let Ok(reserves) = client.fetch_reserves(base_url, &process.process_id).await {
             let _ = queue.update_process_status(&process.process_id, |status| {
                 status.hb_reserves = reserves.hb_reserves;
                 status.ao_reserves = reserves.ao_reserves;
@@ -579,17 +1022,40 @@ async fn check_process(
     Ok(())
 }
 
+/// Send the sync webhook for a process that was just marked synced, if one is configured.
+async fn notify_synced(client: &HyperBeamClient, queue: &QueueManager, webhook_url: Option<&str>, process_id: &str) {
+    let Some(url) = webhook_url else {
+        return;
+    };
+    if let Some(process) = queue.get_process(process_id).await {
+        client.notify_synced(url, models::SyncNotification::from(&process));
+    }
+}
+
+/// Send the stall webhook for a process that just crossed the stall threshold, if one is configured.
+async fn notify_stalled(client: &HyperBeamClient, queue: &QueueManager, webhook_url: Option<&str>, process_id: &str) {
+    let Some(url) = webhook_url else {
+        return;
+    };
+    if let Some(process) = queue.get_process(process_id).await {
+        client.notify_stalled(url, models::StallNotification::from(&process));
+    }
+}
+
 async fn initialize_process(
     client: &HyperBeamClient,
     queue: &QueueManager,
     config: &ProcessConfig,
 ) -> Result<()> {
-    info!("Initializing cron for {}", config.name);
+    let attempt = queue.record_init_attempt(&config.process_id).await.map_err(|e| anyhow!(e))?;
+    info!("Initializing cron for {} (attempt {})", config.name, attempt);
     
     client.initialize_cron(config.base_url.as_deref(), &config.process_id).await?;
     
     queue.update_process_status(&config.process_id, |status| {
         status.cron_initialized = true;
+        status.init_attempts = 0;
+        status.log_activity(LogLevel::Info, "Cron initialized");
     }).await.map_err(|e| anyhow!(e))?;
     
     // Try to check slots after initializing, but don't fail if it doesn't work
@@ -616,50 +1082,38 @@ async fn initialize_process(
         Err(e) => {
             // Process might not be deployed yet, just log and continue
             info!("Process {} initialized (cron set), but couldn't fetch slots yet: {}", config.name, e);
+            let _ = queue.update_process_status(&config.process_id, |status| {
+                status.log_activity(LogLevel::Warn, format!("Initial slot check failed: {}", e));
+            }).await;
         }
     }
     
     Ok(())
 }
 
-async fn render_tui(State(state): State<Arc<AppState>>) -> Html<String> {
+async fn render_tui(
+    State(state): State<Arc<AppState>>,
+    Query(sort): Query<SortQuery>,
+) -> Html<String> {
+    let config = state.config();
     let (_, queued_count, synced_count) = state.queue.get_status().await;
     let runtime = (Utc::now() - state.start_time).num_seconds();
     let queue_preview = state.queue.get_queue_preview(10).await;
-    let all_synced: Vec<_> = state.queue.synced.read().await.values().cloned().collect();
+    let mut all_synced: Vec<_> = state.queue.synced.read().await.values().cloned().collect();
+    sort.sort(&mut all_synced);
     let cron_list = state.cron_list.read().await.clone();
+    let errored = state.queue.get_errored_processes().await;
+    let initializing = state.queue.get_initializing_processes().await;
     
-    // Get active processes based on cron list
-    let mut active_from_crons: Vec<models::ProcessStatus> = Vec::new();
-    let all_processes = state.queue.all_processes.read().await;
-    
-    for cron_item in &cron_list {
-        // Extract process ID from path
-        if let Some(process_id) = cron_item.path
-            .strip_prefix("/")
-            .and_then(|p| p.split("~").next()) {
-            
-            // Check if we're tracking this process
-            if let Some(process) = all_processes.get(process_id) {
-                let mut process_with_cron = process.clone();
-                // Update with cron created time
-                let created_at = chrono::DateTime::from_timestamp_millis(cron_item.created_at as i64);
-                process_with_cron.cron_created_at = created_at;
-                
-                // Calculate real-time sync rate based on cron creation
-                if let Some(created) = created_at {
-                    let minutes_elapsed = (Utc::now() - created).num_seconds() as f64 / 60.0;
-                    if minutes_elapsed > 0.0 && process_with_cron.metrics.total_slots_advanced > 0 {
-                        process_with_cron.metrics.avg_sync_rate = process_with_cron.metrics.total_slots_advanced as f64 / minutes_elapsed;
-                    }
-                }
-                
-                active_from_crons.push(process_with_cron);
-            }
-        }
-    }
+    let mut active_from_crons = state.active_from_crons.read().await.clone();
+    sort.sort(&mut active_from_crons);
     
     let active_count = active_from_crons.len();
+    // Same figures as /api/status and the event stream
+    let aggregate_rate = state.queue.aggregate_sync_rate().await;
+    let desynced_count = state.queue.count_desynced(config.monitoring.sync_tolerance).await;
+    let refresh_interval = config.ui.refresh_interval.max(1);
+    let ui_filter = config.reserves.filter.ignoring(&config.ui.ignored_reserve_keys);
     
     let html = format!(r#"
 <!DOCTYPE html>
@@ -687,6 +1141,13 @@ async fn render_tui(State(state): State<Arc<AppState>>) -> Html<String> {
             margin-bottom: 20px;
             background: #f8f8f8;
         }}
+        .banner {{
+            border: 3px dashed #000000;
+            padding: 10px;
+            text-align: center;
+            margin-bottom: 20px;
+            font-weight: bold;
+        }}
         h1 {{
             margin: 0;
             font-size: 24px;
@@ -761,17 +1222,17 @@ async fn render_tui(State(state): State<Arc<AppState>>) -> Html<String> {
             font-style: italic;
         }}
     </style>
-    <meta http-equiv="refresh" content="5">
+    <meta http-equiv="refresh" content="{}">
 </head>
 <body>
     <div class="container">
         <div class="header">
             <h1>HYDRATION SERVICE</h1>
             <div class="stats">
-                Runtime: {}m {}s | Active: {}/{} | Queued: {} | Synced: {}
+                Runtime: {}m {}s | Active: {}/{} | Queued: {} | Synced: {} ({} desynced) | Throughput: {:.1} slots/min
             </div>
         </div>
-        
+        {}
         <div class="section">
             <div class="section-title">[ ACTIVE PROCESSES ]</div>
             <table>
@@ -781,7 +1242,26 @@ async fn render_tui(State(state): State<Arc<AppState>>) -> Html<String> {
                         <th>Computed</th>
                         <th>Current</th>
                         <th>Deficit</th>
+                        <th>Progress</th>
                         <th>Rate/min</th>
+                        <th>ETA</th>
+                        <th>Last Progress</th>
+                    </tr>
+                </thead>
+                <tbody>
+                    {}
+                </tbody>
+            </table>
+        </div>
+        
+        <div class="section">
+            <div class="section-title">[ INITIALIZING ({}) ]</div>
+            <table>
+                <thead>
+                    <tr>
+                        <th width="50%">Process ID</th>
+                        <th width="20%">Attempts</th>
+                        <th width="30%">Activated</th>
                     </tr>
                 </thead>
                 <tbody>
@@ -808,15 +1288,24 @@ async fn render_tui(State(state): State<Arc<AppState>>) -> Html<String> {
         
         <div class="section">
             <div class="section-title">[ SYNCED POOLS ({}) ]</div>
+            <table>
+                <thead>
+                    {}
+                </thead>
+                <tbody>
+                    {}
+                </tbody>
+            </table>
+        </div>
+        
+        <div class="section">
+            <div class="section-title">[ ERRORED PROCESSES ({}) ]</div>
             <table>
                 <thead>
                     <tr>
-                        <th width="20%">Process ID</th>
-                        <th width="8%">Computed</th>
-                        <th width="8%">Current</th>
-                        <th width="27%">HB Reserves</th>
-                        <th width="27%">AO Reserves</th>
-                        <th width="10%">Match</th>
+                        <th width="35%">Process ID</th>
+                        <th width="45%">Error</th>
+                        <th width="20%">Last Checked</th>
                     </tr>
                 </thead>
                 <tbody>
@@ -842,27 +1331,50 @@ async fn render_tui(State(state): State<Arc<AppState>>) -> Html<String> {
             </table>
         </div>
         
-        <div class="refresh">Page refreshes every 5 seconds</div>
+        <div class="refresh">Page refreshes every {} seconds</div>
     </div>
 </body>
 </html>
     "#,
+        refresh_interval,
         runtime / 60, runtime % 60,
-        active_count, 5, queued_count, synced_count,
-        render_active_table(&active_from_crons),
+        active_count, state.queue.max_active_processes(), queued_count, synced_count,
+        desynced_count, aggregate_rate,
+        render_ao_banner(state.client.ao_cu_reachable()),
+        render_active_table(&active_from_crons, config.monitoring.sync_tolerance),
+        initializing.len(),
+        render_initializing_table(&initializing),
         render_queue(&queue_preview),
         synced_count,
-        render_synced_table(&all_synced),
+        render_synced_header(config.monitoring.fetch_reserves),
+        render_synced_table(
+            &all_synced,
+            &config.reserves.filter,
+            &ui_filter,
+            config.monitoring.fetch_reserves,
+            config.monitoring.sync_tolerance,
+        ),
+        errored.len(),
+        render_errored_table(&errored),
         cron_list.len(),
-        render_cron_table(&cron_list)
+        render_cron_table(&cron_list),
+        refresh_interval
     );
     
     Html(html)
 }
 
-fn render_active_table(processes: &[models::ProcessStatus]) -> String {
+/// Warn that reserves cannot be compared while the AO CU is down; empty otherwise.
+fn render_ao_banner(ao_cu_reachable: bool) -> String {
+    if ao_cu_reachable {
+        return String::new();
+    }
+    r#"<div class="banner">AO CU UNREACHABLE - reserve comparisons are paused, slot monitoring continues</div>"#.to_string()
+}
+
+fn render_active_table(processes: &[models::ProcessStatus], sync_tolerance: u64) -> String {
     if processes.is_empty() {
-        return "<tr><td colspan='5'>No active processes (check cron list)</td></tr>".to_string();
+        return "<tr><td colspan='8'>No active processes (check cron list)</td></tr>".to_string();
     }
     
     processes.iter().map(|p| {
@@ -876,6 +1388,8 @@ fn render_active_table(processes: &[models::ProcessStatus]) -> String {
             }
         });
         
+        let progress = p.sync_progress_percent().map_or("-".to_string(), |pct| format!("{:.0}%", pct));
+        
         // Calculate rate based on cron creation time if available
         let rate = if let Some(cron_created) = p.cron_created_at {
             let minutes_elapsed = (Utc::now() - cron_created).num_seconds() as f64 / 60.0;
@@ -893,20 +1407,51 @@ fn render_active_table(processes: &[models::ProcessStatus]) -> String {
             "-".to_string()
         };
         
-        // Show process ID shortened if too long
-        let process_id_display = if p.process_id.len() > 43 {
-            format!("{}...{}", &p.process_id[..20], &p.process_id[p.process_id.len()-20..])
+        let eta = if p.is_synced(sync_tolerance) {
+            "<span class='synced'>SYNCED</span>".to_string()
         } else {
-            p.process_id.clone()
+            p.estimated_minutes_to_sync().map_or("-".to_string(), format_minutes)
         };
         
+        let last_progress = p.last_advanced_at.map_or("-".to_string(), |at| {
+            format!("{} ago", format_minutes((Utc::now() - at).num_seconds() as f64 / 60.0))
+        });
+        
+        // Show process ID shortened if too long
+        let process_id_display = elide_id(&p.process_id, 20, 20);
+        
         format!(
-            "<tr><td title='{}'>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
-            p.process_id, process_id_display, computed, current, deficit, rate
+            "<tr><td title='{}'>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            p.process_id, process_id_display, computed, current, deficit, progress, rate, eta, last_progress
         )
     }).collect::<Vec<_>>().join("\n")
 }
 
+fn format_minutes(minutes: f64) -> String {
+    let total = minutes.round() as u64;
+    if total >= 60 {
+        format!("{}h {}m", total / 60, total % 60)
+    } else {
+        format!("{}m", total)
+    }
+}
+
+/// The first `n` characters of an ID, or the whole ID if it is shorter.
+fn shorten_id(id: &str, n: usize) -> &str {
+    id.char_indices().nth(n).map_or(id, |(end, _)| &id[..end])
+}
+
+/// Keep the first `head` and last `tail` characters of an ID, joined by `...`, when
+/// that is shorter than the ID itself.
+fn elide_id(id: &str, head: usize, tail: usize) -> String {
+    let len = id.chars().count();
+    if len <= head + tail + 3 {
+        return id.to_string();
+    }
+    let tail_start = id.char_indices().nth(len - tail).map_or(id.len(), |(start, _)| start);
+    format!("{}...{}", shorten_id(id, head), &id[tail_start..])
+}
+
 fn render_queue(processes: &[models::ProcessStatus]) -> String {
     if processes.is_empty() {
         return "<tr><td colspan='3'>Queue is empty</td></tr>".to_string();
@@ -921,30 +1466,71 @@ fn render_queue(processes: &[models::ProcessStatus]) -> String {
     }).collect::<Vec<_>>().join("\n")
 }
 
-fn render_synced_table(processes: &[models::ProcessStatus]) -> String {
-    if processes.is_empty() {
-        return "<tr><td colspan='7'>No synced processes yet</td></tr>".to_string();
-    }
-    
+/// Reserve columns are left out when reserve fetching is disabled.
+fn render_synced_header(show_reserves: bool) -> &'static str {
+    if show_reserves {
+        r#"<tr>
+                        <th width="20%">Process ID</th>
+                        <th width="8%">Computed</th>
+                        <th width="8%">Current</th>
+                        <th width="8%">Sync Time</th>
+                        <th width="23%">HB Reserves</th>
+                        <th width="23%">AO Reserves</th>
+                        <th width="10%">Match</th>
+                    </tr>"#
+    } else {
+        r#"<tr>
+                        <th width="40%">Process ID</th>
+                        <th width="20%">Computed</th>
+                        <th width="20%">Current</th>
+                        <th width="20%">Sync Time</th>
+                    </tr>"#
+    }
+}
+
+/// `match_filter` decides whether reserves match; `display_filter`, which may ignore more
+/// keys, only decides which tokens are listed.
+fn render_synced_table(
+    processes: &[models::ProcessStatus],
+    match_filter: &ReserveFilter,
+    display_filter: &ReserveFilter,
+    show_reserves: bool,
+    sync_tolerance: u64,
+) -> String {
+    if processes.is_empty() {
+        let columns = if show_reserves { 7 } else { 4 };
+        return format!("<tr><td colspan='{}'>No synced processes yet</td></tr>", columns);
+    }
+    
     processes.iter().map(|p| {
         let computed = p.computed_slot.map_or("-".to_string(), |s| s.to_string());
         let current = p.current_slot.map_or("-".to_string(), |s| s.to_string());
         
+        let sync_time = p.metrics.time_to_sync()
+            .map_or("-".to_string(), |d| format_minutes(d.num_seconds() as f64 / 60.0));
+        
         // Check if still synced
-        let is_still_synced = p.computed_slot == p.current_slot;
+        let is_still_synced = p.is_synced(sync_tolerance);
         let current_display = if !is_still_synced && p.current_slot.is_some() {
             format!("<span class='error'>{}</span>", current)
         } else {
             current
         };
         
+        if !show_reserves {
+            return format!(
+                "<tr><td title='{}'>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                p.process_id, p.process_id, computed, current_display, sync_time
+            );
+        }
+        
         // Format reserves in a structured way with sorted tokens
         let (hb_reserves_str, ao_reserves_str) = match (&p.hb_reserves, &p.ao_reserves) {
             (Some(hb), Some(ao)) => {
                 // Get all valid token IDs from both sources
                 let mut all_tokens: Vec<String> = hb.keys()
                     .chain(ao.keys())
-                    .filter(|k| k.len() == 43 && !["TokenA", "TokenB", "K"].contains(&k.as_str()))
+                    .filter(|k| display_filter.is_token_key(k))
                     .cloned()
                     .collect::<std::collections::HashSet<_>>()
                     .into_iter()
@@ -953,6 +1539,13 @@ fn render_synced_table(processes: &[models::ProcessStatus]) -> String {
                 // Sort tokens for consistent display
                 all_tokens.sort();
                 
+                // Per-token comparison matching `reserves_match`, so "1000" and "1000.0" agree
+                let matching: std::collections::HashSet<String> = p.reserve_diffs(match_filter)
+                    .into_iter()
+                    .filter(|diff| diff.matches)
+                    .map(|diff| diff.token)
+                    .collect();
+                
                 if all_tokens.is_empty() {
                     ("No token reserves".to_string(), "No token reserves".to_string())
                 } else {
@@ -969,7 +1562,7 @@ fn render_synced_table(processes: &[models::ProcessStatus]) -> String {
                     let ao_str = all_tokens.iter()
                         .map(|token| {
                             let amount = ao.get(token).map(|s| s.as_str()).unwrap_or("0");
-                            let style = if hb.get(token) != ao.get(token) {
+                            let style = if !matching.contains(token) {
                                 "style='color: #666666; text-decoration: underline;'"
                             } else {
                                 ""
@@ -984,7 +1577,7 @@ fn render_synced_table(processes: &[models::ProcessStatus]) -> String {
             },
             (Some(hb), None) => {
                 let mut tokens: Vec<_> = hb.keys()
-                    .filter(|k| k.len() == 43 && !["TokenA", "TokenB", "K"].contains(&k.as_str()))
+                    .filter(|k| display_filter.is_token_key(k))
                     .collect();
                 tokens.sort();
                 
@@ -1003,7 +1596,7 @@ fn render_synced_table(processes: &[models::ProcessStatus]) -> String {
             },
             (None, Some(ao)) => {
                 let mut tokens: Vec<_> = ao.keys()
-                    .filter(|k| k.len() == 43)
+                    .filter(|k| display_filter.is_token_key(k))
                     .collect();
                 tokens.sort();
                 
@@ -1026,7 +1619,7 @@ fn render_synced_table(processes: &[models::ProcessStatus]) -> String {
         // Check if reserves match
         let match_status = match (&p.hb_reserves, &p.ao_reserves) {
             (None, _) | (_, None) => "<span style='color: #999;'>[FETCHING]</span>",
-            _ => match p.reserves_match() {
+            _ => match p.reserves_match(match_filter) {
                 Some(true) => "<span class='synced'>[OK]</span>",
                 Some(false) => "<span class='error'>[DIFF]</span>",
                 None => "<span style='color: #999;'>[FETCHING]</span>",
@@ -1034,12 +1627,70 @@ fn render_synced_table(processes: &[models::ProcessStatus]) -> String {
         };
         
         format!(
-            "<tr><td title='{}'>{}</td><td>{}</td><td>{}</td><td style='font-size: 11px; font-family: monospace;'>{}</td><td style='font-size: 11px; font-family: monospace;'>{}</td><td>{}</td></tr>",
-            p.process_id, p.process_id, computed, current_display, hb_reserves_str, ao_reserves_str, match_status
+            "<tr><td title='{}'>{}</td><td>{}</td><td>{}</td><td>{}</td><td style='font-size: 11px; font-family: monospace;'>{}</td><td style='font-size: 11px; font-family: monospace;'>{}</td><td>{}</td></tr>",
+            p.process_id, p.process_id, computed, current_display, sync_time, hb_reserves_str, ao_reserves_str, match_status
+        )
+    }).collect::<Vec<_>>().join("\n")
+}
+
+fn render_initializing_table(processes: &[models::ProcessStatus]) -> String {
+    if processes.is_empty() {
+        return "<tr><td colspan='3'>No processes initializing</td></tr>".to_string();
+    }
+    
+    processes.iter().map(|p| {
+        let activated = p.activated_at
+            .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_else(|| "-".to_string());
+        
+        format!(
+            "<tr><td title='{}'>{}</td><td>{}</td><td>{}</td></tr>",
+            p.process_id, p.process_id, p.init_attempts, activated
+        )
+    }).collect::<Vec<_>>().join("\n")
+}
+
+/// Escape text for use in HTML content and quoted attribute values.
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn render_errored_table(processes: &[models::ProcessStatus]) -> String {
+    if processes.is_empty() {
+        return "<tr><td colspan='3'>No errored processes</td></tr>".to_string();
+    }
+    
+    processes.iter().map(|p| {
+        // Errors often carry upstream response bodies, so they must not be rendered as markup
+        let error = escape_html(p.error.as_deref().unwrap_or("-"));
+        let process_id = escape_html(&p.process_id);
+        let last_checked = p.last_checked
+            .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_else(|| "-".to_string());
+        
+        format!(
+            "<tr><td title='{}'>{}</td><td class='error'>{}</td><td>{}</td></tr>",
+            process_id, process_id, error, last_checked
         )
     }).collect::<Vec<_>>().join("\n")
 }
 
+/// Processes as returned by list endpoints, without the per-process detail.
+fn list_view(processes: Vec<models::ProcessStatus>) -> Vec<models::ProcessStatus> {
+    processes.into_iter().map(models::ProcessStatus::for_list).collect()
+}
+
 fn render_cron_table(cron_items: &[CronItem]) -> String {
     if cron_items.is_empty() {
         return "<tr><td colspan='4'>No active crons</td></tr>".to_string();
@@ -1047,10 +1698,7 @@ fn render_cron_table(cron_items: &[CronItem]) -> String {
     
     cron_items.iter().map(|item| {
         // Extract process ID from path (format: /processId~process@1.0/now)
-        let process_id = item.path
-            .strip_prefix("/")
-            .and_then(|p| p.split("~").next())
-            .unwrap_or("unknown");
+        let process_id = parse_process_id_from_cron_path(&item.path).unwrap_or("unknown");
         
         // Format timestamp
         let created = chrono::DateTime::from_timestamp_millis(item.created_at as i64)
@@ -1058,11 +1706,7 @@ fn render_cron_table(cron_items: &[CronItem]) -> String {
             .unwrap_or_else(|| "unknown".to_string());
         
         // Shorten task ID for display
-        let task_id_display = if item.task_id.len() > 20 {
-            format!("{}...{}", &item.task_id[..10], &item.task_id[item.task_id.len()-7..])
-        } else {
-            item.task_id.clone()
-        };
+        let task_id_display = elide_id(&item.task_id, 10, 7);
         
         format!(
             "<tr><td title='{}'>{}</td><td>{}</td><td title='{}'>{}</td><td>{}</td></tr>",
@@ -1071,79 +1715,2543 @@ fn render_cron_table(cron_items: &[CronItem]) -> String {
     }).collect::<Vec<_>>().join("\n")
 }
 
-async fn get_status(State(state): State<Arc<AppState>>) -> Json<ApiResponse<ApiStatus>> {
+async fn build_status(state: &AppState) -> ApiStatus {
     let (active_count, queued_count, synced_count) = state.queue.get_status().await;
-    let runtime = (Utc::now() - state.start_time).num_seconds() as u64;
+    let now = Utc::now();
+    let runtime = (now - state.start_time).num_seconds() as u64;
     
-    let status = ApiStatus {
+    ApiStatus {
         active_count,
         queued_count,
         synced_count,
+        max_active_processes: state.queue.max_active_processes(),
         total_count: active_count + queued_count + synced_count,
+        start_time: state.start_time,
+        server_time: now,
         runtime_seconds: runtime,
-        active_processes: state.queue.get_active_processes().await,
-        queue_preview: state.queue.get_queue_preview(10).await,
-        recent_synced: state.queue.get_recent_synced(10).await,
-    };
+        total_runtime_seconds: state.queue.total_runtime_seconds(),
+        aggregate_sync_rate: state.queue.aggregate_sync_rate().await,
+        desynced_count: state.queue.count_desynced(state.config().monitoring.sync_tolerance).await,
+        total_upstream_requests: state.client.stats.total_requests(),
+        active_processes: list_view(state.queue.get_active_processes().await),
+        queue_preview: list_view(state.queue.get_queue_preview(10).await),
+        recent_synced: list_view(state.queue.get_recent_synced(10).await),
+    }
+}
+
+async fn get_status(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<StatusQuery>,
+    Query(sort): Query<SortQuery>,
+) -> Json<ApiResponse<ApiStatus>> {
+    let mut status = build_status(&state).await;
+    query.apply(&mut status);
+    sort.sort(&mut status.active_processes);
+    sort.sort(&mut status.recent_synced);
     
     Json(ApiResponse {
         success: true,
         data: Some(status),
         error: None,
+        error_code: None,
+    })
+}
+
+async fn stream_status(
+    State(state): State<Arc<AppState>>,
+) -> Sse<impl Stream<Item = Result<Event, axum::Error>>> {
+    let mut ticker = tokio::time::interval(Duration::from_secs(state.config().ui.refresh_interval.max(1)));
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    
+    let mut shutdown = state.shutdown.clone();
+    
+    // The stream is dropped when the client disconnects, which stops the ticker
+    let stream = futures::stream::unfold((state, ticker), |(state, mut ticker)| async move {
+        ticker.tick().await;
+        let event = Event::default().event("status").json_data(build_status(&state).await);
+        Some((event, (state, ticker)))
+    })
+    .take_until(async move {
+        let _ = shutdown.wait_for(|stopping| *stopping).await;
+    });
+    
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+fn health_status(state: &AppState) -> HealthStatus {
+    HealthStatus {
+        status: "ok",
+        uptime_seconds: (Utc::now() - state.start_time).num_seconds() as u64,
+        hyperbeam_reachable: state.hyperbeam_reachable.load(Ordering::Relaxed),
+        ao_cu_reachable: state.client.ao_cu_reachable(),
+        ready: state.ready.load(Ordering::Relaxed),
+    }
+}
+
+/// Liveness: always 200 while the server is answering.
+async fn get_health(State(state): State<Arc<AppState>>) -> Json<HealthStatus> {
+    Json(health_status(&state))
+}
+
+/// Readiness: 503 until state is loaded and the initial cron list fetch completes.
+async fn get_readiness(State(state): State<Arc<AppState>>) -> (StatusCode, Json<HealthStatus>) {
+    let health = health_status(&state);
+    let code = if health.ready { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+    (code, Json(health))
+}
+
+async fn get_version() -> Json<models::VersionInfo> {
+    Json(models::VersionInfo {
+        version: env!("CARGO_PKG_VERSION"),
+        git_sha: option_env!("GIT_SHA"),
+        state_version: state::STATE_VERSION,
+    })
+}
+
+async fn get_laggards(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<LaggardsQuery>,
+) -> Json<ApiResponse<Vec<Laggard>>> {
+    let config = state.config();
+    let limit = query.limit.unwrap_or(config.limits.queue_preview_limit);
+    let laggards = state.queue.get_laggards(
+        limit,
+        query.include_synced,
+        query.include_stalled,
+        config.monitoring.sync_tolerance,
+        config.monitoring.stall_threshold,
+    ).await;
+    
+    Json(ApiResponse {
+        success: true,
+        data: Some(laggards.iter().map(Laggard::from).collect()),
+        error: None,
+        error_code: None,
+    })
+}
+
+async fn get_trends(State(state): State<Arc<AppState>>) -> Json<ApiResponse<Vec<TrendSample>>> {
+    Json(ApiResponse {
+        success: true,
+        data: Some(state.trends.read().await.iter().cloned().collect()),
+        error: None,
+        error_code: None,
+    })
+}
+
+async fn get_metrics(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let (active_count, queued_count, synced_count) = state.queue.get_status().await;
+    let mut processes = state.queue.get_active_processes().await;
+    processes.extend(state.queue.synced.read().await.values().cloned());
+    
+    let snapshot = metrics::MetricsSnapshot {
+        active_count,
+        queued_count,
+        synced_count,
+        hb_requests: state.client.stats.hb_requests(),
+        ao_requests: state.client.stats.ao_requests(),
+        request_errors: state.client.stats.errors(),
+        processes,
+    };
+    
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        metrics::render_prometheus(&snapshot),
+    )
+}
+
+/// Activated processes still waiting for their cron to be initialized.
+async fn get_initializing(State(state): State<Arc<AppState>>) -> Json<ApiResponse<Vec<models::ProcessStatus>>> {
+    let processes = state.queue.get_initializing_processes().await
+        .into_iter()
+        .map(models::ProcessStatus::with_derived_fields)
+        .map(models::ProcessStatus::for_list)
+        .collect();
+    
+    Json(ApiResponse {
+        success: true,
+        data: Some(processes),
+        error: None,
+        error_code: None,
+    })
+}
+
+/// Tracked processes that currently have a cron on HyperBEAM. This can differ from
+/// `active_processes` in `/api/status`, which is the service's own active set: a process
+/// may still have a cron after it was marked synced, or lose its cron while active.
+async fn get_active_from_crons(State(state): State<Arc<AppState>>) -> Json<ApiResponse<Vec<models::ProcessStatus>>> {
+    let processes = state.active_from_crons.read().await
+        .iter()
+        .cloned()
+        .map(models::ProcessStatus::with_derived_fields)
+        .map(models::ProcessStatus::for_list)
+        .collect();
+    
+    Json(ApiResponse {
+        success: true,
+        data: Some(processes),
+        error: None,
+        error_code: None,
+    })
+}
+
+async fn get_cron_list(State(state): State<Arc<AppState>>) -> Json<ApiResponse<Vec<CronItem>>> {
+    let cron_list = state.cron_list.read().await.clone();
+    
+    Json(ApiResponse {
+        success: true,
+        data: Some(cron_list),
+        error: None,
+        error_code: None,
     })
 }
 
-async fn get_state(State(state): State<Arc<AppState>>) -> Result<Json<models::StateFile>, StatusCode> {
-    state::save_state(&state.queue).await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+async fn export_synced_csv(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let mut synced: Vec<_> = state.queue.synced.read().await.values().cloned().collect();
+    synced.sort_by_key(|s| s.synced_at);
+    
+    (
+        [
+            (header::CONTENT_TYPE, "text/csv"),
+            (header::CONTENT_DISPOSITION, "attachment; filename=\"synced.csv\""),
+        ],
+        export::render_synced_csv(&synced),
+    )
+}
+
+const MAX_QUEUE_PAGE_SIZE: usize = 500;
+
+/// Re-read config.toml and the environment and apply the result to the running service.
+async fn reload_config(
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<ApiResponse<ServiceConfig>>) {
+    apply_reloaded_config(&state, ServiceConfig::load())
+}
+
+/// Swap in a freshly loaded config, unless loading failed or it changes settings
+/// that need a restart.
+fn apply_reloaded_config(
+    state: &AppState,
+    loaded: Result<ServiceConfig>,
+) -> (StatusCode, Json<ApiResponse<ServiceConfig>>) {
+    let result = loaded
+        .and_then(|new_config| state.config().check_reloadable(&new_config).map(|_| new_config));
     
-    let content = tokio::fs::read_to_string("hydration-state.json")
-        .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    match result {
+        Ok(new_config) => {
+            state.queue.set_max_active_processes(new_config.limits.max_active_processes);
+            state.queue.set_max_queue_size(new_config.limits.max_queue_size);
+            state.queue.set_max_init_attempts(new_config.limits.max_init_attempts);
+            state.queue.set_queue_strategy(new_config.limits.queue_strategy);
+            *state.config.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(new_config.clone());
+            info!("Configuration reloaded");
+            
+            (StatusCode::OK, Json(ApiResponse {
+                success: true,
+                data: Some(new_config.redacted()),
+                error: None,
+                error_code: None,
+            }))
+        }
+        Err(e) => {
+            warn!("Configuration reload rejected: {}", e);
+            (StatusCode::BAD_REQUEST, Json(ApiResponse {
+                success: false,
+                data: None,
+                error: Some(e.to_string()),
+                error_code: Some(ErrorCode::InvalidConfig),
+            }))
+        }
+    }
+}
+
+async fn get_queue(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<QueueQuery>,
+) -> Json<ApiResponse<QueuePage>> {
+    let limit = query.limit.unwrap_or(50).clamp(1, MAX_QUEUE_PAGE_SIZE);
+    let (processes, total) = state.queue.get_queue_page(query.offset, limit).await;
     
-    let state_file: models::StateFile = serde_json::from_str(&content)
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Json(ApiResponse {
+        success: true,
+        data: Some(QueuePage {
+            total,
+            offset: query.offset,
+            limit,
+            processes: list_view(processes),
+        }),
+        error: None,
+        error_code: None,
+    })
+}
+
+async fn get_synced(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<SyncedQuery>,
+) -> Json<ApiResponse<Vec<models::ProcessStatus>>> {
+    let processes = match query.since {
+        Some(cutoff) => state.queue.get_synced_since(cutoff).await,
+        None => state.queue.get_recent_synced(usize::MAX).await,
+    };
     
-    Ok(Json(state_file))
+    Json(ApiResponse {
+        success: true,
+        data: Some(list_view(processes)),
+        error: None,
+        error_code: None,
+    })
+}
+
+async fn clear_queue(State(state): State<Arc<AppState>>) -> Json<ApiResponse<usize>> {
+    Json(ApiResponse {
+        success: true,
+        data: Some(state.queue.clear_queue().await),
+        error: None,
+        error_code: None,
+    })
+}
+
+async fn get_state(State(state): State<Arc<AppState>>) -> Json<models::StateFile> {
+    Json(state::build_state(&state.queue, &state.config().state).await)
 }
 
 async fn add_to_queue(
     State(state): State<Arc<AppState>>,
-    Json(request): Json<AddProcessRequest>,
-) -> Json<ApiResponse<String>> {
+    Json(payload): Json<AddProcessPayload>,
+) -> Response {
+    match payload {
+        AddProcessPayload::Single(request) => {
+            let result = add_one(&state.queue, request, state.config().limits.enforce_unique_names).await;
+            let status = queue_full_status(&state.queue, result.success).await;
+            (status, Json(ApiResponse {
+                success: result.success,
+                data: result.success.then(|| format!("Process {} added to queue", result.process_id)),
+                error: result.error,
+                error_code: result.error_code,
+            })).into_response()
+        }
+        AddProcessPayload::Batch(requests) | AddProcessPayload::Wrapped { processes: requests } => {
+            let unique_names = state.config().limits.enforce_unique_names;
+            let mut results = Vec::with_capacity(requests.len());
+            for request in requests {
+                results.push(add_one(&state.queue, request, unique_names).await);
+            }
+            let success = results.iter().all(|r| r.success);
+            let status = queue_full_status(&state.queue, success).await;
+            (status, Json(ApiResponse {
+                success,
+                data: Some(results),
+                error: None,
+                error_code: None,
+            })).into_response()
+        }
+    }
+}
+
+/// 503 when an add failed and the queue is at capacity, so callers know to back off.
+async fn queue_full_status(queue: &QueueManager, success: bool) -> StatusCode {
+    if !success && queue.is_queue_full().await {
+        StatusCode::SERVICE_UNAVAILABLE
+    } else {
+        StatusCode::OK
+    }
+}
+
+async fn add_one(queue: &QueueManager, request: AddProcessRequest, unique_names: bool) -> AddProcessResult {
     let config = ProcessConfig {
         name: request.name,
         process_id: request.process_id.clone(),
         base_url: request.base_url,
     };
     
-    match state.queue.add_to_queue(config).await {
-        Ok(_) => Json(ApiResponse {
+    let result = if unique_names && !queue.get_by_name(&config.name).await.is_empty() {
+        Err(QueueError::DuplicateName(config.name))
+    } else {
+        queue.add_to_queue(config).await
+    };
+    AddProcessResult {
+        process_id: request.process_id,
+        success: result.is_ok(),
+        error: result.as_ref().err().map(|e| e.to_string()),
+        error_code: result.err().map(|e| e.code()),
+    }
+}
+
+/// HTTP status for a failed API call, by error category.
+fn error_status(code: ErrorCode) -> StatusCode {
+    match code {
+        ErrorCode::NotFound => StatusCode::NOT_FOUND,
+        ErrorCode::Duplicate | ErrorCode::InvalidState => StatusCode::CONFLICT,
+        ErrorCode::QueueFull => StatusCode::SERVICE_UNAVAILABLE,
+        ErrorCode::NotAllowed => StatusCode::FORBIDDEN,
+        ErrorCode::Upstream => StatusCode::BAD_GATEWAY,
+        ErrorCode::InvalidConfig => StatusCode::BAD_REQUEST,
+    }
+}
+
+/// Pair a response with the status its error code calls for, 200 on success.
+fn respond<T>(response: ApiResponse<T>) -> (StatusCode, Json<ApiResponse<T>>) {
+    let status = response.error_code.map(error_status).unwrap_or(StatusCode::OK);
+    (status, Json(response))
+}
+
+async fn restart_process(
+    State(state): State<Arc<AppState>>,
+    Path(process_id): Path<String>,
+) -> (StatusCode, Json<ApiResponse<String>>) {
+    match state.queue.restart_process(&process_id).await {
+        Ok(_) => respond(ApiResponse {
             success: true,
-            data: Some(format!("Process {} added to queue", request.process_id)),
+            data: Some(format!("Process {} restarted", process_id)),
             error: None,
+            error_code: None,
         }),
-        Err(e) => Json(ApiResponse {
+        Err(e) => respond(ApiResponse {
             success: false,
             data: None,
-            error: Some(e),
+            error: Some(e.to_string()),
+            error_code: Some(e.code()),
         }),
     }
 }
 
-async fn restart_process(
+async fn pause_process(
     State(state): State<Arc<AppState>>,
     Path(process_id): Path<String>,
-) -> Json<ApiResponse<String>> {
-    match state.queue.restart_process(&process_id).await {
-        Ok(_) => Json(ApiResponse {
+) -> (StatusCode, Json<ApiResponse<String>>) {
+    set_paused(&state, process_id, true).await
+}
+
+async fn unpause_process(
+    State(state): State<Arc<AppState>>,
+    Path(process_id): Path<String>,
+) -> (StatusCode, Json<ApiResponse<String>>) {
+    set_paused(&state, process_id, false).await
+}
+
+async fn set_paused(state: &AppState, process_id: String, paused: bool) -> (StatusCode, Json<ApiResponse<String>>) {
+    match state.queue.set_paused(&process_id, paused).await {
+        Ok(_) => respond(ApiResponse {
             success: true,
-            data: Some(format!("Process {} restarted", process_id)),
+            data: Some(format!("Process {} {}", process_id, if paused { "paused" } else { "unpaused" })),
+            error: None,
+            error_code: None,
+        }),
+        Err(e) => respond(ApiResponse {
+            success: false,
+            data: None,
+            error: Some(e.to_string()),
+            error_code: Some(e.code()),
+        }),
+    }
+}
+
+async fn reset_metrics(
+    State(state): State<Arc<AppState>>,
+    Path(process_id): Path<String>,
+) -> (StatusCode, Json<ApiResponse<String>>) {
+    match state.queue.reset_metrics(&process_id).await {
+        Ok(_) => respond(ApiResponse {
+            success: true,
+            data: Some(format!("Metrics reset for process {}", process_id)),
             error: None,
+            error_code: None,
+        }),
+        Err(e) => respond(ApiResponse {
+            success: false,
+            data: None,
+            error: Some(e.to_string()),
+            error_code: Some(e.code()),
+        }),
+    }
+}
+
+/// Re-initialize the cron even if it was already set up, for processes whose cron
+/// was cancelled on the HyperBEAM side, and clear the slot readings so the next
+/// check starts fresh.
+async fn force_resync(
+    State(state): State<Arc<AppState>>,
+    Path(process_id): Path<String>,
+) -> (StatusCode, Json<ApiResponse<String>>) {
+    let Some(process) = state.queue.get_process(&process_id).await else {
+        return respond(ApiResponse {
+            success: false,
+            data: None,
+            error: Some(format!("Process {} not found", process_id)),
+            error_code: Some(ErrorCode::NotFound),
+        });
+    };
+    
+    // A cron started for any other state would run outside the active set and its limit
+    if process.state != ProcessState::Active {
+        let e = QueueError::WrongState { process_id, expected: "active" };
+        return respond(ApiResponse {
+            success: false,
+            data: None,
+            error: Some(e.to_string()),
+            error_code: Some(e.code()),
+        });
+    }
+    
+    if let Err(e) = state.client.initialize_cron(process.base_url.as_deref(), &process_id).await {
+        return respond(ApiResponse {
+            success: false,
+            data: None,
+            error: Some(format!("Failed to re-initialize cron for {}: {}", process_id, e)),
+            error_code: Some(ErrorCode::Upstream),
+        });
+    }
+    
+    let result = state.queue.update_process_status(&process_id, |status| {
+        status.cron_initialized = true;
+        status.log_activity(LogLevel::Info, "Cron re-initialized by force-resync");
+        status.computed_slot = None;
+        status.current_slot = None;
+        status.last_checked = None;
+    }).await;
+    
+    match result {
+        Ok(_) => {
+            info!("Force-resynced cron for {}", process_id);
+            respond(ApiResponse {
+                success: true,
+                data: Some(format!("Cron re-initialized for process {}", process_id)),
+                error: None,
+                error_code: None,
+            })
+        }
+        Err(e) => respond(ApiResponse {
+            success: false,
+            data: None,
+            error: Some(e.to_string()),
+            error_code: Some(e.code()),
         }),
-        Err(e) => Json(ApiResponse {
+    }
+}
+
+/// Stop the process's cron on HyperBEAM without removing the process. The process is
+/// paused, and an active one requeued, so hydration halts until it is unpaused.
+async fn stop_cron(
+    State(state): State<Arc<AppState>>,
+    Path(process_id): Path<String>,
+) -> (StatusCode, Json<ApiResponse<String>>) {
+    let Some(process) = state.queue.get_process(&process_id).await else {
+        return respond(ApiResponse {
+            success: false,
+            data: None,
+            error: Some(format!("Process {} not found", process_id)),
+            error_code: Some(ErrorCode::NotFound),
+        });
+    };
+    
+    let stopped = match state.client.cancel_cron(process.base_url.as_deref(), &process_id).await {
+        Ok(stopped) => stopped,
+        Err(e) => {
+            return respond(ApiResponse {
+                success: false,
+                data: None,
+                error: Some(format!("Failed to stop cron for {}: {}", process_id, e)),
+                error_code: Some(ErrorCode::Upstream),
+            });
+        }
+    };
+    
+    let note = format!("Cron stopped by operator ({} task(s) cancelled)", stopped);
+    let result = state.queue.mark_cron_stopped(&process_id, note).await;
+    
+    match result {
+        Ok(_) => {
+            info!("Stopped {} cron task(s) for {}", stopped, process_id);
+            respond(ApiResponse {
+                success: true,
+                data: Some(if stopped == 0 {
+                    format!("No running cron found for process {}", process_id)
+                } else {
+                    format!("Cron stopped for process {}", process_id)
+                }),
+                error: None,
+                error_code: None,
+            })
+        }
+        Err(e) => respond(ApiResponse {
             success: false,
             data: None,
-            error: Some(e),
+            error: Some(e.to_string()),
+            error_code: Some(e.code()),
         }),
     }
-}
\ No newline at end of file
+}
+
+/// Fetch HB and AO reserves right away, whatever state the process is in.
+async fn refresh_reserves(
+    State(state): State<Arc<AppState>>,
+    Path(process_id): Path<String>,
+) -> (StatusCode, Json<ApiResponse<ReservesResult>>) {
+    let Some(process) = state.queue.get_process(&process_id).await else {
+        return respond(ApiResponse {
+            success: false,
+            data: None,
+            error: Some(format!("Process {} not found", process_id)),
+            error_code: Some(ErrorCode::NotFound),
+        });
+    };
+    
+    let reserves = match state.client.fetch_reserves(process.base_url.as_deref(), &process_id).await {
+        // Each side fails soft, so nothing came back if both are missing
+        Ok(ReservesResult { hb_reserves: None, ao_reserves: None }) => {
+            return respond(ApiResponse {
+                success: false,
+                data: None,
+                error: Some(format!("Failed to fetch reserves for {} from HyperBEAM or the AO CU", process_id)),
+                error_code: Some(ErrorCode::Upstream),
+            });
+        }
+        Ok(reserves) => reserves,
+        Err(e) => {
+            return respond(ApiResponse {
+                success: false,
+                data: None,
+                error: Some(format!("Failed to fetch reserves for {}: {}", process_id, e)),
+                error_code: Some(ErrorCode::Upstream),
+            });
+        }
+    };
+    
+    let result = state.queue.update_process_status(&process_id, |status| {
+        status.hb_reserves = reserves.hb_reserves.clone();
+        status.ao_reserves = reserves.ao_reserves.clone();
+        status.reserves_last_checked = Some(Utc::now());
+    }).await;
+    
+    match result {
+        Ok(_) => respond(ApiResponse {
+            success: true,
+            data: Some(reserves),
+            error: None,
+            error_code: None,
+        }),
+        Err(e) => respond(ApiResponse {
+            success: false,
+            data: None,
+            error: Some(e.to_string()),
+            error_code: Some(e.code()),
+        }),
+    }
+}
+
+async fn get_process(
+    State(state): State<Arc<AppState>>,
+    Path(process_id): Path<String>,
+) -> (StatusCode, Json<ApiResponse<models::ProcessStatus>>) {
+    match state.queue.get_process(&process_id).await {
+        Some(mut process) => {
+            process.reserve_diffs = Some(process.reserve_diffs(&state.config().reserves.filter));
+            (StatusCode::OK, Json(ApiResponse {
+                success: true,
+                data: Some(process),
+                error: None,
+                error_code: None,
+            }))
+        }
+        None => (StatusCode::NOT_FOUND, Json(ApiResponse {
+            success: false,
+            data: None,
+            error: Some(format!("Process {} not found", process_id)),
+            error_code: Some(ErrorCode::NotFound),
+        })),
+    }
+}
+
+/// Recent slot samples for one process, oldest first, for charting its convergence.
+async fn get_process_history(
+    State(state): State<Arc<AppState>>,
+    Path(process_id): Path<String>,
+    Query(query): Query<HistoryQuery>,
+) -> (StatusCode, Json<ApiResponse<Vec<SlotSample>>>) {
+    match state.queue.get_process(&process_id).await {
+        Some(process) => {
+            let points = query.points.unwrap_or(MAX_SLOT_HISTORY);
+            (StatusCode::OK, Json(ApiResponse {
+                success: true,
+                data: Some(process.metrics.recent_slot_samples(points).to_vec()),
+                error: None,
+                error_code: None,
+            }))
+        }
+        None => (StatusCode::NOT_FOUND, Json(ApiResponse {
+            success: false,
+            data: None,
+            error: Some(format!("Process {} not found", process_id)),
+            error_code: Some(ErrorCode::NotFound),
+        })),
+    }
+}
+
+async fn get_processes_by_name(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+) -> (StatusCode, Json<ApiResponse<Vec<models::ProcessStatus>>>) {
+    let processes = state.queue.get_by_name(&name).await;
+    if processes.is_empty() {
+        return (StatusCode::NOT_FOUND, Json(ApiResponse {
+            success: false,
+            data: None,
+            error: Some(format!("No process named {}", name)),
+            error_code: Some(ErrorCode::NotFound),
+        }));
+    }
+    
+    (StatusCode::OK, Json(ApiResponse {
+        success: true,
+        data: Some(list_view(processes)),
+        error: None,
+        error_code: None,
+    }))
+}
+
+async fn remove_process(
+    State(state): State<Arc<AppState>>,
+    Path(process_id): Path<String>,
+) -> (StatusCode, Json<ApiResponse<String>>) {
+    match state.queue.remove_process(&process_id).await {
+        Ok(_) => respond(ApiResponse {
+            success: true,
+            data: Some(format!("Process {} removed", process_id)),
+            error: None,
+            error_code: None,
+        }),
+        Err(e) => respond(ApiResponse {
+            success: false,
+            data: None,
+            error: Some(e.to_string()),
+            error_code: Some(e.code()),
+        }),
+    }
+}
+
+async fn set_priority(
+    State(state): State<Arc<AppState>>,
+    Path(process_id): Path<String>,
+    Json(request): Json<SetPriorityRequest>,
+) -> (StatusCode, Json<ApiResponse<String>>) {
+    match state.queue.set_queue_position(&process_id, request.position).await {
+        Ok(position) => respond(ApiResponse {
+            success: true,
+            data: Some(format!("Process {} moved to queue position {}", process_id, position)),
+            error: None,
+            error_code: None,
+        }),
+        Err(e) => respond(ApiResponse {
+            success: false,
+            data: None,
+            error: Some(e.to_string()),
+            error_code: Some(e.code()),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::QueueStrategy;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::collections::HashMap;
+    use crate::test_support::{client_for, config_for, record_requests, serve, slot_node, MockSlots, RequestLog};
+
+    fn manager() -> QueueManager {
+        QueueManager::new(10, 0, 0, Vec::new(), Vec::new(), QueueStrategy::Fifo)
+    }
+
+    fn ids(processes: &[models::ProcessStatus]) -> Vec<&str> {
+        processes.iter().map(|p| p.process_id.as_str()).collect()
+    }
+
+    async fn add_active(queue: &QueueManager, process_id: &str) {
+        queue.add_to_queue(ProcessConfig {
+            name: format!("pool-{}", process_id),
+            process_id: process_id.to_string(),
+            base_url: None,
+        }).await.unwrap();
+        assert_eq!(queue.activate_next().await.unwrap().process_id, process_id);
+    }
+
+    /// The API served on a local port, with state kept in a temp dir.
+    struct TestApp {
+        state: Arc<AppState>,
+        url: String,
+        http: reqwest::Client,
+        _state_dir: tempfile::TempDir,
+        _shutdown: watch::Sender<bool>,
+    }
+
+    impl TestApp {
+        async fn new(mut config: ServiceConfig) -> Self {
+            let state_dir = tempfile::tempdir().unwrap();
+            config.state.path = state_dir.path().join("state.json").to_string_lossy().into_owned();
+            let (shutdown_tx, shutdown_rx) = watch::channel(false);
+            let state = app_state_with_shutdown(Arc::new(manager()), config, shutdown_rx);
+            let url = serve(router(state.clone())).await;
+
+            Self {
+                state,
+                url,
+                http: reqwest::Client::new(),
+                _state_dir: state_dir,
+                _shutdown: shutdown_tx,
+            }
+        }
+
+        async fn get(&self, path: &str) -> (reqwest::StatusCode, serde_json::Value) {
+            let response = self.http.get(format!("{}{}", self.url, path)).send().await.unwrap();
+            (response.status(), response.json().await.unwrap())
+        }
+
+        async fn html(&self, path: &str) -> String {
+            self.http.get(format!("{}{}", self.url, path)).send().await.unwrap().text().await.unwrap()
+        }
+
+        async fn post(&self, path: &str) -> (reqwest::StatusCode, serde_json::Value) {
+            self.send(self.http.post(format!("{}{}", self.url, path))).await
+        }
+
+        async fn post_json(&self, path: &str, body: serde_json::Value) -> (reqwest::StatusCode, serde_json::Value) {
+            self.send(self.http.post(format!("{}{}", self.url, path)).json(&body)).await
+        }
+
+        async fn send(&self, request: reqwest::RequestBuilder) -> (reqwest::StatusCode, serde_json::Value) {
+            let response = request.send().await.unwrap();
+            (response.status(), response.json().await.unwrap())
+        }
+    }
+
+    fn app_state(queue: Arc<QueueManager>, config: ServiceConfig) -> Arc<AppState> {
+        app_state_with_shutdown(queue, config, watch::channel(false).1)
+    }
+
+    fn app_state_with_shutdown(queue: Arc<QueueManager>, config: ServiceConfig, shutdown: watch::Receiver<bool>) -> Arc<AppState> {
+        Arc::new(AppState {
+            queue,
+            client: Arc::new(client_for(&config)),
+            start_time: Utc::now(),
+            cron_list: Arc::new(RwLock::new(Vec::new())),
+            active_from_crons: Arc::new(RwLock::new(Vec::new())),
+            store: state::open_store(&config.state).unwrap(),
+            config: std::sync::RwLock::new(Arc::new(config)),
+            shutdown,
+            hyperbeam_reachable: AtomicBool::new(false),
+            ready: AtomicBool::new(false),
+            trends: RwLock::new(VecDeque::new()),
+        })
+    }
+
+    async fn process(queue: &QueueManager, process_id: &str) -> models::ProcessStatus {
+        queue.all_processes.read().await.get(process_id).cloned().unwrap()
+    }
+
+    #[tokio::test]
+    async fn implausible_slot_jumps_are_discarded() {
+        let slots = MockSlots::new(100, 1000);
+        let mut config = config_for(&serve(slot_node(slots.clone())).await);
+        config.monitoring.max_slots_per_minute = 1000.0;
+        let client = client_for(&config);
+        let queue = manager();
+        add_active(&queue, "pid").await;
+
+        check_process(&client, &queue, &process(&queue, "pid").await, &config).await.unwrap();
+        queue.update_process_status("pid", |status| {
+            status.last_checked = Some(Utc::now() - chrono::Duration::minutes(1));
+        }).await.unwrap();
+
+        // A node bug reports a current slot millions ahead a minute later
+        slots.set(100, 5_000_000);
+        let checked = process(&queue, "pid").await;
+        check_process(&client, &queue, &checked, &config).await.unwrap();
+
+        let status = process(&queue, "pid").await;
+        assert_eq!((status.computed_slot, status.current_slot), (Some(100), Some(1000)));
+        assert_eq!(status.metrics.check_count, 1);
+        assert!(status.activity_log.iter().any(|entry| entry.message.contains("Discarded implausible slot reading")));
+
+        // Advancing within the bound is accepted
+        slots.set(600, 1500);
+        check_process(&client, &queue, &checked, &config).await.unwrap();
+        let status = process(&queue, "pid").await;
+        assert_eq!((status.computed_slot, status.current_slot), (Some(600), Some(1500)));
+    }
+
+    #[tokio::test]
+    async fn regressing_slots_reset_the_rate_baseline() {
+        let slots = MockSlots::new(100, 1000);
+        let mut config = config_for(&serve(slot_node(slots.clone())).await);
+        config.monitoring.slot_regression_threshold = 10;
+        let client = client_for(&config);
+        let queue = manager();
+        add_active(&queue, "pid").await;
+        let started = Utc::now() - chrono::Duration::minutes(10);
+
+        let check = |computed: u64| {
+            slots.set(computed, 1000);
+            let (client, queue, config) = (&client, &queue, &config);
+            async move {
+                queue.update_process_status("pid", |status| {
+                    status.last_checked = status.last_checked.map(|_| Utc::now() - chrono::Duration::minutes(1));
+                }).await.unwrap();
+                let process = queue.get_process("pid").await.unwrap();
+                check_process(client, queue, &process, config).await.unwrap();
+                queue.get_process("pid").await.unwrap().metrics
+            }
+        };
+
+        check(100).await;
+        queue.update_process_status("pid", |status| status.metrics.sync_start_time = Some(started)).await.unwrap();
+        let metrics = check(200).await;
+        assert_eq!(metrics.total_slots_advanced, 100);
+        assert!(metrics.avg_sync_rate > 0.0);
+
+        // A small step back stays within the threshold and keeps the baseline
+        let metrics = check(195).await;
+        assert_eq!((metrics.total_slots_advanced, metrics.slots_advanced_last_check), (100, 0));
+        assert_eq!(metrics.sync_start_time, Some(started));
+
+        // A larger one restarts the rate from the new slot
+        let metrics = check(150).await;
+        assert_eq!(metrics.total_slots_advanced, 0);
+        assert_eq!(metrics.avg_sync_rate, 0.0);
+        assert!(metrics.sync_start_time.unwrap() > started);
+        let metrics = check(160).await;
+        assert_eq!(metrics.total_slots_advanced, 10);
+        let process = queue.get_process("pid").await.unwrap();
+        assert!(process.activity_log.iter().any(|entry| entry.message == "Computed slot regressed 195 -> 150"));
+    }
+
+    #[tokio::test]
+    async fn checks_keep_the_configured_number_of_response_times() {
+        let mut config = config_for(&serve(slot_node(MockSlots::new(0, 100))).await);
+        config.monitoring.response_time_window = 3;
+        let client = client_for(&config);
+        let queue = manager();
+        add_active(&queue, "pid").await;
+
+        for _ in 0..4 {
+            let process = queue.get_process("pid").await.unwrap();
+            check_process(&client, &queue, &process, &config).await.unwrap();
+        }
+        let metrics = queue.get_process("pid").await.unwrap().metrics;
+        assert_eq!(metrics.check_count, 4);
+        assert_eq!(metrics.api_response_times.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn last_advanced_at_only_moves_when_the_computed_slot_does() {
+        let slots = MockSlots::new(100, 1000);
+        let config = config_for(&serve(slot_node(slots.clone())).await);
+        let client = client_for(&config);
+        let queue = manager();
+        add_active(&queue, "pid").await;
+
+        let check = |computed: u64| {
+            slots.set(computed, 1000);
+            let (client, queue, config) = (&client, &queue, &config);
+            async move {
+                let process = queue.get_process("pid").await.unwrap();
+                check_process(client, queue, &process, config).await.unwrap();
+                queue.get_process("pid").await.unwrap().last_advanced_at
+            }
+        };
+
+        assert_eq!(check(100).await, None, "the first reading is only a baseline");
+        assert_eq!(check(100).await, None);
+        let advanced = check(150).await.unwrap();
+        assert_eq!(check(150).await, Some(advanced));
+        assert_eq!(check(140).await, Some(advanced));
+        assert!(check(200).await.unwrap() >= advanced);
+    }
+
+    #[tokio::test]
+    async fn reset_metrics_keeps_state_and_slots() {
+        let queue = Arc::new(manager());
+        add_active(&queue, "pool").await;
+        queue.update_process_status("pool", |status| {
+            status.cron_initialized = true;
+            status.computed_slot = Some(900);
+            status.current_slot = Some(1000);
+            status.metrics.check_count = 12;
+            status.metrics.total_slots_advanced = 400;
+            status.metrics.avg_sync_rate = 33.0;
+            status.metrics.instant_sync_rate = Some(40.0);
+            status.metrics.initial_slot_deficit = Some(500);
+            status.metrics.sync_start_time = Some(Utc::now());
+            status.metrics.record_slot_sample(900, 1000);
+        }).await.unwrap();
+        let state = app_state(queue.clone(), ServiceConfig::default());
+
+        let (status, Json(response)) = reset_metrics(State(state), Path("pool".to_string())).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(response.success);
+
+        let process = process(&queue, "pool").await;
+        assert_eq!(process.state, ProcessState::Active);
+        assert!(process.cron_initialized);
+        assert_eq!((process.computed_slot, process.current_slot), (Some(900), Some(1000)));
+        assert_eq!(process.metrics.check_count, 0);
+        assert_eq!(process.metrics.total_slots_advanced, 0);
+        assert_eq!(process.metrics.avg_sync_rate, 0.0);
+        assert_eq!(process.metrics.instant_sync_rate, None);
+        assert_eq!(process.metrics.initial_slot_deficit, None);
+        assert_eq!(process.metrics.sync_start_time, None);
+        assert!(process.metrics.slot_history.is_empty());
+        assert_eq!(queue.get_status().await, (1, 0, 0));
+    }
+
+    /// A node whose current slot moves on once a computed slot read has finished, so
+    /// a concurrent read looks synced while a sequential one sees the new slot.
+    fn skewed_slot_node() -> Router {
+        let computed_reads = Arc::new(AtomicU64::new(0));
+        let finished = computed_reads.clone();
+        Router::new()
+            .route("/:process/compute/at-slot", get(move || async move {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                finished.fetch_add(1, Ordering::SeqCst);
+                "100"
+            }))
+            .route("/:process/slot/current", get(move || async move {
+                (100 + computed_reads.load(Ordering::SeqCst)).to_string()
+            }))
+    }
+
+    #[tokio::test]
+    async fn a_skewed_synced_reading_is_not_confirmed_by_a_sequential_read() {
+        for confirm_synced_read in [false, true] {
+            let mut config = config_for(&serve(skewed_slot_node()).await);
+            config.monitoring.confirm_synced_read = confirm_synced_read;
+            config.monitoring.fetch_reserves = false;
+            let client = client_for(&config);
+            let queue = manager();
+            add_active(&queue, "pid").await;
+
+            check_process(&client, &queue, &process(&queue, "pid").await, &config).await.unwrap();
+
+            let status = process(&queue, "pid").await;
+            assert_eq!((status.computed_slot, status.current_slot), (Some(100), Some(100)));
+            let expected = if confirm_synced_read { ProcessState::Active } else { ProcessState::Synced };
+            assert_eq!(status.state, expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn uninitialized_active_processes_are_requeued_on_recovery() {
+        let queue = Arc::new(manager());
+        add_active(&queue, "pool").await;
+        assert!(!process(&queue, "pool").await.cron_initialized);
+
+        let client = Arc::new(client_for(&config_for("http://127.0.0.1:9")));
+        recover_uninitialized_active(&client, &queue, UninitializedActivePolicy::Requeue).await;
+        assert_eq!(process(&queue, "pool").await.state, ProcessState::Queued);
+        assert_eq!(queue.get_status().await, (0, 1, 0));
+    }
+
+    #[tokio::test]
+    async fn uninitialized_active_processes_are_reinitialized_on_recovery() {
+        let node = slot_node(MockSlots::new(90, 100)).route("/~cron@1.0/once", get(|| async { "ok" }));
+        let (node, log) = record_requests(node);
+        let config = config_for(&serve(node).await);
+        let queue = Arc::new(manager());
+        add_active(&queue, "pool").await;
+
+        recover_uninitialized_active(&Arc::new(client_for(&config)), &queue, UninitializedActivePolicy::Reinitialize).await;
+        let mut status = process(&queue, "pool").await;
+        for _ in 0..50 {
+            if status.computed_slot.is_some() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            status = process(&queue, "pool").await;
+        }
+        assert_eq!(status.state, ProcessState::Active);
+        assert!(status.cron_initialized);
+        assert_eq!((status.computed_slot, status.current_slot), (Some(90), Some(100)));
+        assert!(log.contains("GET /~cron@1.0/once?cron-path=/pool~process@1.0/now"));
+    }
+
+    #[tokio::test]
+    async fn reconcile_requeues_synced_processes_only_when_configured() {
+        for requeue_synced_on_config in [false, true] {
+            let queue = manager();
+            add_active(&queue, "pool").await;
+            queue.mark_synced("pool").await.unwrap();
+
+            let listed = vec![ProcessConfig {
+                name: "renamed".to_string(),
+                process_id: "pool".to_string(),
+                base_url: Some("http://other".to_string()),
+            }];
+            let reconcile = ReconcileConfig { requeue_synced_on_config, ..Default::default() };
+            reconcile_config(&queue, listed, &reconcile).await;
+
+            let process = queue.all_processes.read().await.get("pool").cloned().unwrap();
+            if requeue_synced_on_config {
+                assert_eq!(process.state, ProcessState::Queued);
+                assert_eq!(queue.get_status().await, (0, 1, 0));
+            } else {
+                assert_eq!(process.state, ProcessState::Synced);
+                assert_eq!(queue.get_status().await, (0, 0, 1));
+            }
+            assert_eq!(process.name, "renamed");
+            assert_eq!(process.base_url.as_deref(), Some("http://other"));
+        }
+    }
+
+    /// Log output captured from a subscriber installed for the current thread.
+    #[derive(Clone, Default)]
+    struct CapturedLogs(Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl std::io::Write for CapturedLogs {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl CapturedLogs {
+        fn text(&self) -> String {
+            String::from_utf8_lossy(&self.0.lock().unwrap()).into_owned()
+        }
+    }
+
+    #[tokio::test]
+    async fn shutdown_report_counts_what_was_persisted() {
+        let logs = CapturedLogs::default();
+        let writer = logs.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(move || writer.clone())
+            .with_ansi(false)
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let queue = Arc::new(manager());
+        add_active(&queue, "active").await;
+        queue.add_to_queue(ProcessConfig {
+            name: "queued".to_string(),
+            process_id: "queued".to_string(),
+            base_url: None,
+        }).await.unwrap();
+        let state = app_state(queue, ServiceConfig::default());
+
+        for (saved, persisted) in [(true, "persisted=2"), (false, "persisted=0")] {
+            log_shutdown_report(&state, saved).await;
+            let text = logs.text();
+            let report = text.lines()
+                .rfind(|line| line.contains("Shutdown report"))
+                .unwrap_or_else(|| panic!("no shutdown report logged:\n{}", text));
+            for field in [persisted, "queued=1", "active=1", "synced=0", "errored=0", "hb_requests=0", "ao_requests=0", "request_errors=0", "uptime_seconds="] {
+                assert!(report.contains(field), "{} missing from {}", field, report);
+            }
+        }
+    }
+
+    /// The queue slot monitor running with the given settings over one queued process,
+    /// and a count of its current-slot reads.
+    async fn run_queue_slot_monitor(delay: u64, interval: u64) -> (Arc<AppState>, impl Fn() -> usize) {
+        let (node, log) = record_requests(slot_node(MockSlots::new(0, 100)));
+        let mut config = config_for(&serve(node).await);
+        config.monitoring.queue_slots_delay = delay;
+        config.monitoring.queue_slots_interval = interval;
+        let state = app_state(Arc::new(manager()), config);
+        state.queue.add_to_queue(ProcessConfig {
+            name: "pool".to_string(),
+            process_id: "queued".to_string(),
+            base_url: None,
+        }).await.unwrap();
+
+        tokio::spawn(monitor_queue_slots(state.clone()));
+        (state, move || log.requests().iter().filter(|r| r.contains("/slot/current")).count())
+    }
+
+    #[tokio::test]
+    async fn queue_slot_monitor_uses_the_configured_delay_and_interval() {
+        let (_, fast) = run_queue_slot_monitor(0, 1).await;
+        let (_, slow) = run_queue_slot_monitor(0, 3600).await;
+        let (_, delayed) = run_queue_slot_monitor(3600, 1).await;
+
+        // Wait for the third read on a 1s interval; the others must not have moved by then
+        for _ in 0..100 {
+            if fast() >= 3 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        assert!(fast() >= 3);
+        assert_eq!(slow(), 1);
+        assert_eq!(delayed(), 0);
+    }
+
+    #[tokio::test]
+    async fn process_detail_is_found_or_404() {
+        let queue = Arc::new(manager());
+        add_active(&queue, "pool").await;
+        queue.update_process_status("pool", |status| {
+            status.metrics.api_response_times = vec![12.0, 30.0];
+        }).await.unwrap();
+        let state = app_state(queue, ServiceConfig::default());
+
+        let (status, Json(body)) = get_process(State(state.clone()), Path("pool".to_string())).await;
+        let body = serde_json::to_value(body).unwrap();
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["success"], true);
+        assert_eq!(body["data"]["process_id"], "pool");
+        assert_eq!(body["data"]["state"], "active");
+        assert_eq!(body["data"]["metrics"]["api_response_times"], serde_json::json!([12.0, 30.0]));
+
+        let (status, Json(body)) = get_process(State(state), Path("missing".to_string())).await;
+        let body = serde_json::to_value(body).unwrap();
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["success"], false);
+        assert_eq!(body["data"], serde_json::Value::Null);
+        assert_eq!(body["error_code"], "NOT_FOUND");
+    }
+
+    /// POST `body` to `path` on `router`, served on a local port.
+    async fn post_json(router: Router, path: &str, body: serde_json::Value) -> (reqwest::StatusCode, serde_json::Value) {
+        let url = serve(router).await;
+        let response = reqwest::Client::new().post(format!("{}{}", url, path)).json(&body).send().await.unwrap();
+        (response.status(), response.json().await.unwrap())
+    }
+
+    #[tokio::test]
+    async fn batch_add_reports_each_process() {
+        let queue = Arc::new(manager());
+        add_active(&queue, "existing").await;
+        let router = Router::new()
+            .route("/api/queue/add", post(add_to_queue))
+            .with_state(app_state(queue.clone(), ServiceConfig::default()));
+
+        let pool = |id: &str| serde_json::json!({ "name": format!("pool-{}", id), "process_id": id });
+        let (status, body) = post_json(router.clone(), "/api/queue/add", serde_json::json!([
+            pool("a"), pool("existing"), pool("b"), pool("a"),
+        ])).await;
+        assert_eq!(status, reqwest::StatusCode::OK);
+        assert_eq!(body["success"], false);
+        let results = body["data"].as_array().unwrap();
+        let outcomes: Vec<_> = results.iter().map(|r| (r["process_id"].as_str().unwrap(), r["success"].as_bool().unwrap())).collect();
+        assert_eq!(outcomes, [("a", true), ("existing", false), ("b", true), ("a", false)]);
+        assert_eq!(results[1]["error"], "Process existing already exists");
+        assert_eq!(results[1]["error_code"], "DUPLICATE");
+        assert_eq!(results[3]["error_code"], "DUPLICATE");
+        assert_eq!(queue.get_status().await, (1, 2, 0));
+
+        let (_, body) = post_json(router, "/api/queue/add", serde_json::json!({ "processes": [pool("c")] })).await;
+        assert_eq!(body["success"], true);
+        assert_eq!(body["data"][0]["process_id"], "c");
+        assert_eq!(queue.get_status().await, (1, 3, 0));
+    }
+
+    #[tokio::test]
+    async fn status_stream_sends_an_event_per_interval_until_shutdown() {
+        let mut config = config_for("http://127.0.0.1:9");
+        config.ui.refresh_interval = 1;
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+        let state = app_state_with_shutdown(Arc::new(manager()), config, shutdown_rx);
+        add_active(&state.queue, "pool").await;
+        let url = serve(Router::new().route("/api/stream", get(stream_status)).with_state(state)).await;
+
+        let mut response = reqwest::get(format!("{}/api/stream", url)).await.unwrap();
+        assert_eq!(response.headers()[reqwest::header::CONTENT_TYPE], "text/event-stream");
+
+        let mut events = Vec::new();
+        let mut buffer = String::new();
+        while events.len() < 2 {
+            let chunk = tokio::time::timeout(Duration::from_secs(5), response.chunk()).await.unwrap().unwrap().unwrap();
+            buffer.push_str(std::str::from_utf8(&chunk).unwrap());
+            while let Some((event, rest)) = buffer.split_once("\n\n") {
+                if event.lines().any(|line| line == "event: status") {
+                    let data = event.lines().find_map(|line| line.strip_prefix("data: ")).unwrap();
+                    events.push(serde_json::from_str::<serde_json::Value>(data).unwrap());
+                }
+                buffer = rest.to_string();
+            }
+        }
+        for event in &events {
+            assert_eq!(event["active_count"], 1);
+            assert_eq!(event["active_processes"][0]["process_id"], "pool");
+        }
+
+        // Shutting down ends the stream
+        shutdown_tx.send(true).unwrap();
+        let end = tokio::time::timeout(Duration::from_secs(5), async {
+            while response.chunk().await.unwrap().is_some() {}
+        }).await;
+        assert!(end.is_ok());
+    }
+
+    #[tokio::test]
+    async fn dashboard_refreshes_at_the_configured_interval() {
+        for (configured, expected) in [(2, 2), (0, 1)] {
+            let mut config = config_for("http://127.0.0.1:9");
+            config.ui.refresh_interval = configured;
+            let app = TestApp::new(config).await;
+
+            let html = app.html("/").await;
+            assert!(html.contains(&format!(r#"<meta http-equiv="refresh" content="{}">"#, expected)));
+            assert!(html.contains(&format!("Page refreshes every {} seconds", expected)));
+        }
+    }
+
+    #[tokio::test]
+    async fn dashboard_lists_errored_processes() {
+        let app = TestApp::new(config_for("http://127.0.0.1:9")).await;
+        let html = app.html("/").await;
+        assert!(html.contains("[ ERRORED PROCESSES (0) ]"));
+        assert!(html.contains("No errored processes"));
+
+        add_active(&app.state.queue, "broken").await;
+        add_active(&app.state.queue, "healthy").await;
+        app.state.queue.mark_error("broken", "cron init failed: HTTP 500".to_string()).await.unwrap();
+        assert_eq!(ids(&app.state.queue.get_errored_processes().await), ["broken"]);
+
+        let html = app.html("/").await;
+        assert!(html.contains("[ ERRORED PROCESSES (1) ]"));
+        assert!(html.contains("<td class='error'>cron init failed: HTTP 500</td>"));
+        assert!(!html.contains("No errored processes"));
+    }
+
+    #[tokio::test]
+    async fn errored_processes_render_escaped_in_error_order() {
+        let queue = manager();
+        for id in ["later", "earlier", "it's<b>"] {
+            add_active(&queue, id).await;
+        }
+        queue.mark_error("earlier", "timeout".to_string()).await.unwrap();
+        queue.mark_error("later", "<html><body>502 Bad Gateway</body></html>".to_string()).await.unwrap();
+        queue.mark_error("it's<b>", "node said 'no' & <script>alert(1)</script>".to_string()).await.unwrap();
+        let errored = queue.get_errored_processes().await;
+        assert_eq!(ids(&errored), ["earlier", "later", "it's<b>"]);
+
+        let rows = render_errored_table(&errored);
+        assert!(!rows.contains("<html>") && !rows.contains("<script>") && !rows.contains("<b>"), "{}", rows);
+        assert!(rows.contains("&lt;html&gt;&lt;body&gt;502 Bad Gateway&lt;/body&gt;&lt;/html&gt;"), "{}", rows);
+        assert!(rows.contains("node said &#39;no&#39; &amp; &lt;script&gt;"), "{}", rows);
+        assert!(rows.contains("<td title='it&#39;s&lt;b&gt;'>it&#39;s&lt;b&gt;</td>"), "{}", rows);
+        let earlier = rows.find("timeout").unwrap();
+        assert!(earlier < rows.find("502 Bad Gateway").unwrap());
+    }
+
+    #[test]
+    fn ids_are_shortened_without_panicking() {
+        let long = "abcdefghijklmnopqrstuvwxyz0123456789ABCDEFG";
+        assert_eq!(shorten_id(long, 8), "abcdefgh");
+        assert_eq!(shorten_id("abc", 8), "abc");
+        assert_eq!(shorten_id("abcdefgh", 8), "abcdefgh");
+        assert_eq!(shorten_id("", 8), "");
+        assert_eq!(shorten_id("ééééé", 2), "éé");
+
+        assert_eq!(elide_id(long, 6, 4), "abcdef...DEFG");
+        assert_eq!(elide_id("short", 6, 4), "short");
+        // Eliding would not make the ID any shorter
+        assert_eq!(elide_id("abcdefghijklm", 6, 4), "abcdefghijklm");
+        assert_eq!(elide_id("", 6, 4), "");
+        assert_eq!(elide_id("ééééééééééééééé", 2, 2), "éé...éé");
+    }
+
+    #[test]
+    fn minutes_are_formatted_as_hours_and_minutes() {
+        assert_eq!(format_minutes(0.0), "0m");
+        assert_eq!(format_minutes(59.4), "59m");
+        assert_eq!(format_minutes(59.6), "1h 0m");
+        assert_eq!(format_minutes(135.0), "2h 15m");
+    }
+
+    #[tokio::test]
+    async fn slot_checks_use_the_stored_base_url() {
+        let (node, log) = record_requests(slot_node(MockSlots::new(40, 50)));
+        let node_url = serve(node).await;
+        // Nothing listens on the configured default node
+        let config = config_for("http://127.0.0.1:9");
+        let client = client_for(&config);
+        let queue = manager();
+        add_active(&queue, "pid").await;
+        queue.update_process_config("pid", "pool".to_string(), Some(node_url)).await;
+
+        let process = queue.get_process("pid").await.unwrap();
+        check_process(&client, &queue, &process, &config).await.unwrap();
+
+        let status = queue.get_process("pid").await.unwrap();
+        assert_eq!((status.computed_slot, status.current_slot), (Some(40), Some(50)));
+        assert!(log.contains("GET /pid~process@1.0/compute/at-slot"));
+        assert!(log.contains("GET /pid~process@1.0/slot/current"));
+    }
+
+    fn cron_item(process_id: &str, task_id: &str) -> CronItem {
+        CronItem {
+            created_at: 1_700_000_000_000,
+            path: format!("/{}~process@1.0/now", process_id),
+            pid: "cron-pid".to_string(),
+            task_id: task_id.to_string(),
+            cron_type: "once".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn cron_endpoint_returns_the_cached_list() {
+        let state = app_state(Arc::new(manager()), config_for("http://127.0.0.1:9"));
+        let url = serve(Router::new().route("/api/cron", get(get_cron_list)).with_state(state.clone())).await;
+        let get_cron = || async {
+            let response = reqwest::get(format!("{}/api/cron", url)).await.unwrap();
+            (response.status(), response.json::<serde_json::Value>().await.unwrap())
+        };
+        let (_, body) = get_cron().await;
+        assert_eq!(body["data"], serde_json::json!([]));
+
+        *state.cron_list.write().await = vec![cron_item("pool", "task-1")];
+        let (status, body) = get_cron().await;
+        assert_eq!(status, reqwest::StatusCode::OK);
+        assert_eq!(body["success"], true);
+        assert_eq!(body["data"], serde_json::json!([{
+            "created_at": 1_700_000_000_000u64,
+            "path": "/pool~process@1.0/now",
+            "pid": "cron-pid",
+            "task_id": "task-1",
+            "type": "once",
+        }]));
+    }
+
+    #[tokio::test]
+    async fn state_endpoint_reflects_the_manager_without_writing() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = config_for("http://127.0.0.1:9");
+        config.state.path = dir.path().join("state.json").to_string_lossy().into_owned();
+        let state = app_state(Arc::new(manager()), config);
+        let queue = &state.queue;
+        add_active(queue, "active").await;
+        add_active(queue, "synced").await;
+        queue.mark_synced("synced").await.unwrap();
+        queue.add_to_queue(ProcessConfig { name: "pool-queued".to_string(), process_id: "queued".to_string(), base_url: None }).await.unwrap();
+
+        let url = serve(Router::new().route("/api/state", get(get_state)).with_state(state.clone())).await;
+        let response = reqwest::get(format!("{}/api/state", url)).await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["active_process_ids"], serde_json::json!(["active"]));
+        assert_eq!(body["synced_process_ids"], serde_json::json!(["synced"]));
+        assert_eq!(body["queued_process_ids"], serde_json::json!(["queued"]));
+        let processes = body["processes"].as_object().unwrap();
+        assert_eq!(processes.len(), 3);
+        assert_eq!(processes["synced"]["state"], "synced");
+        assert_eq!(processes["queued"]["name"], "pool-queued");
+        assert!(!std::path::Path::new(&state.config().state.path).exists());
+    }
+
+    /// Settings for a server on a free local port with its state file in `dir`.
+    fn server_config(dir: &tempfile::TempDir) -> ServiceConfig {
+        let mut config = config_for("http://127.0.0.1:9");
+        config.server.host = "127.0.0.1".to_string();
+        config.server.port = 0;
+        config.state.path = dir.path().join("state.json").to_string_lossy().into_owned();
+        config
+    }
+
+    #[tokio::test]
+    async fn shutdown_saves_state_after_the_server_stops() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = app_state(Arc::new(manager()), server_config(&dir));
+        add_active(&state.queue, "pool").await;
+        assert!(state.store.read().unwrap().is_none());
+
+        let (stop_tx, stop_rx) = tokio::sync::oneshot::channel::<()>();
+        let server = tokio::spawn(serve_until_shutdown(state.clone(), async move {
+            let _ = stop_rx.await;
+        }));
+        stop_tx.send(()).unwrap();
+        server.await.unwrap().unwrap();
+
+        let saved = state.store.read().unwrap().unwrap();
+        assert_eq!(saved.active_process_ids, ["pool"]);
+    }
+
+    #[tokio::test]
+    async fn shutdown_report_is_logged_even_when_the_final_save_fails() {
+        let logs = CapturedLogs::default();
+        let writer = logs.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(move || writer.clone())
+            .with_ansi(false)
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let dir = tempfile::tempdir().unwrap();
+        let state = app_state(Arc::new(manager()), server_config(&dir));
+        add_active(&state.queue, "active").await;
+        state.queue.add_to_queue(ProcessConfig {
+            name: "queued".to_string(),
+            process_id: "queued".to_string(),
+            base_url: None,
+        }).await.unwrap();
+        // A directory in the way of the state file makes the final save fail
+        std::fs::create_dir(&state.config().state.path).unwrap();
+
+        serve_until_shutdown(state, async {}).await.unwrap();
+
+        let text = logs.text();
+        assert!(text.contains("Failed to save state on shutdown"), "{}", text);
+        let report = text.lines()
+            .find(|line| line.contains("Shutdown report"))
+            .unwrap_or_else(|| panic!("no shutdown report logged:\n{}", text));
+        for field in ["persisted=0", "queued=1", "active=1", "synced=0", "errored=0", "hb_requests=", "ao_requests=", "request_errors=", "uptime_seconds="] {
+            assert!(report.contains(field), "{} missing from {}", field, report);
+        }
+    }
+
+    #[tokio::test]
+    async fn readiness_waits_for_the_ready_flag() {
+        let state = app_state(Arc::new(manager()), config_for("http://127.0.0.1:9"));
+        let router = Router::new()
+            .route("/api/health", get(get_health))
+            .route("/api/health/ready", get(get_readiness))
+            .with_state(state.clone());
+        let url = serve(router).await;
+        let get_json = |path: &'static str| {
+            let url = format!("{}{}", url, path);
+            async move {
+                let response = reqwest::get(url).await.unwrap();
+                (response.status(), response.json::<serde_json::Value>().await.unwrap())
+            }
+        };
+
+        let (status, body) = get_json("/api/health").await;
+        assert_eq!(status, reqwest::StatusCode::OK);
+        assert_eq!(body["status"], "ok");
+        assert_eq!(body["ready"], false);
+        assert_eq!(body["hyperbeam_reachable"], false);
+        let (status, body) = get_json("/api/health/ready").await;
+        assert_eq!(status, reqwest::StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["ready"], false);
+
+        state.hyperbeam_reachable.store(true, Ordering::Relaxed);
+        state.ready.store(true, Ordering::Relaxed);
+        let (status, body) = get_json("/api/health/ready").await;
+        assert_eq!(status, reqwest::StatusCode::OK);
+        assert_eq!(body["ready"], true);
+        assert_eq!(body["hyperbeam_reachable"], true);
+        assert!(body["uptime_seconds"].is_u64());
+    }
+
+    async fn run_synced_pool_monitor(requeue_on_desync: bool, fetch_reserves: bool) -> (TestApp, RequestLog) {
+        let (node, log) = record_requests(slot_node(MockSlots::new(900, 1000)));
+        let mut config = config_for(&serve(node).await);
+        config.monitoring.requeue_on_desync = requeue_on_desync;
+        config.monitoring.desync_threshold = 50;
+        config.monitoring.fetch_reserves = fetch_reserves;
+        let app = TestApp::new(config).await;
+        add_active(&app.state.queue, "pool").await;
+        app.state.queue.mark_synced("pool").await.unwrap();
+
+        tokio::spawn(monitor_synced_pools(app.state.clone()));
+        (app, log)
+    }
+
+    #[tokio::test]
+    async fn desynced_pools_are_requeued_only_when_enabled() {
+        let (requeuing, _) = run_synced_pool_monitor(true, false).await;
+        let (keeping, _) = run_synced_pool_monitor(false, false).await;
+
+        // The monitor waits 5s before its first pass
+        for _ in 0..100 {
+            let requeued = requeuing.state.queue.get_process("pool").await.unwrap().state == ProcessState::Queued;
+            let checked = keeping.state.queue.get_process("pool").await.unwrap().last_checked.is_some();
+            if requeued && checked {
+                break;
+            }
+            sleep(Duration::from_millis(100)).await;
+        }
+
+        let requeued = requeuing.state.queue.get_process("pool").await.unwrap();
+        assert_eq!(requeued.state, ProcessState::Queued);
+        assert!(!requeuing.state.queue.synced.read().await.contains_key("pool"));
+        let kept = keeping.state.queue.get_process("pool").await.unwrap();
+        assert_eq!(kept.state, ProcessState::Synced);
+        assert_eq!(kept.computed_slot, Some(900));
+    }
+
+    /// GET `path` from the server at `url`.
+    async fn get_json(url: &str, path: &str) -> (reqwest::StatusCode, serde_json::Value) {
+        let response = reqwest::get(format!("{}{}", url, path)).await.unwrap();
+        (response.status(), response.json().await.unwrap())
+    }
+
+    #[tokio::test]
+    async fn queue_listing_pages_and_clamps_the_limit() {
+        let state = app_state(Arc::new(manager()), config_for("http://127.0.0.1:9"));
+        let url = serve(Router::new().route("/api/queue", get(get_queue)).with_state(state.clone())).await;
+        for id in ["a", "b", "c"] {
+            state.queue.add_to_queue(ProcessConfig { name: format!("pool-{}", id), process_id: id.to_string(), base_url: None }).await.unwrap();
+        }
+
+        let (_, body) = get_json(&url, "/api/queue?offset=1&limit=1").await;
+        assert_eq!(body["data"]["total"], 3);
+        assert_eq!(body["data"]["processes"][0]["process_id"], "b");
+        assert_eq!(body["data"]["processes"][0]["queue_position"], 1);
+
+        let (status, body) = get_json(&url, "/api/queue?offset=10").await;
+        assert_eq!(status, reqwest::StatusCode::OK);
+        assert_eq!(body["data"]["total"], 3);
+        assert_eq!(body["data"]["processes"], serde_json::json!([]));
+
+        let (_, body) = get_json(&url, "/api/queue?limit=0").await;
+        assert_eq!(body["data"]["limit"], 1);
+        assert_eq!(body["data"]["processes"].as_array().unwrap().len(), 1);
+
+        let (_, body) = get_json(&url, "/api/queue?limit=100000").await;
+        assert_eq!(body["data"]["limit"], MAX_QUEUE_PAGE_SIZE);
+        assert_eq!(body["data"]["processes"].as_array().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn force_resync_reinitializes_the_cron_and_clears_slots() {
+        let (node, log) = record_requests(Router::new().route("/~cron@1.0/once", get(|| async { "ok" })));
+        let state = app_state(Arc::new(manager()), config_for(&serve(node).await));
+        let router = Router::new().route("/api/process/:id/force-resync", post(force_resync)).with_state(state.clone());
+        add_active(&state.queue, "pool").await;
+        state.queue.update_process_status("pool", |status| {
+            status.cron_initialized = true;
+            status.computed_slot = Some(90);
+            status.current_slot = Some(100);
+            status.last_checked = Some(Utc::now());
+        }).await.unwrap();
+
+        let (status, body) = post_json(router, "/api/process/pool/force-resync", serde_json::Value::Null).await;
+        assert_eq!(status, reqwest::StatusCode::OK);
+        assert_eq!(body["success"], true);
+        assert!(log.contains("GET /~cron@1.0/once?cron-path=/pool~process@1.0/now"));
+        let process = state.queue.get_process("pool").await.unwrap();
+        assert!(process.cron_initialized);
+        assert_eq!((process.computed_slot, process.current_slot, process.last_checked), (None, None, None));
+    }
+
+    #[tokio::test]
+    async fn force_resync_is_rejected_unless_the_process_is_active() {
+        let (node, log) = record_requests(Router::new().route("/~cron@1.0/once", get(|| async { "ok" })));
+        let state = app_state(Arc::new(manager()), config_for(&serve(node).await));
+        let router = Router::new().route("/api/process/:id/force-resync", post(force_resync)).with_state(state.clone());
+        let queue = &state.queue;
+        for id in ["synced", "errored"] {
+            add_active(queue, id).await;
+        }
+        queue.mark_synced("synced").await.unwrap();
+        queue.mark_error("errored", "boom".to_string()).await.unwrap();
+        queue.add_to_queue(ProcessConfig { name: "queued".to_string(), process_id: "queued".to_string(), base_url: None }).await.unwrap();
+
+        for id in ["queued", "synced", "errored"] {
+            let path = format!("/api/process/{}/force-resync", id);
+            let (status, body) = post_json(router.clone(), &path, serde_json::Value::Null).await;
+            assert_eq!(status, reqwest::StatusCode::CONFLICT, "{}", id);
+            assert_eq!(body["success"], false, "{}", id);
+            assert_eq!(body["error"], format!("Process {} is not active", id));
+            assert_eq!(body["error_code"], "INVALID_STATE");
+            assert!(!queue.get_process(id).await.unwrap().cron_initialized);
+        }
+        assert!(log.requests().is_empty());
+    }
+
+    /// A token-shaped reserve key, `token_id_length` characters long.
+    fn token(c: char) -> String {
+        std::iter::repeat_n(c, ReserveFilter::default().token_id_length).collect()
+    }
+
+    fn synced_with_reserves(hb: &[(&str, &str)], ao: &[(&str, &str)]) -> models::ProcessStatus {
+        let reserves = |pairs: &[(&str, &str)]| Some(pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect());
+        let mut process = models::ProcessStatus::new("pool".to_string(), "pid".to_string(), None);
+        process.state = ProcessState::Synced;
+        process.hb_reserves = reserves(hb);
+        process.ao_reserves = reserves(ao);
+        process
+    }
+
+    #[test]
+    fn synced_table_underlines_only_numerically_different_amounts() {
+        let (same, different) = (token('a'), token('b'));
+        let process = synced_with_reserves(
+            &[(&same, "1000"), (&different, "1000")],
+            &[(&same, "1000.0"), (&different, "999")],
+        );
+        let filter = ReserveFilter::default();
+
+        let html = render_synced_table(&[process], &filter, &filter, true, 0);
+        let underlined = "style='color: #666666; text-decoration: underline;'";
+        assert!(html.contains(&format!("<div  title='{}'>1000.0</div>", same)), "{}", html);
+        assert!(html.contains(&format!("<div {} title='{}'>999</div>", underlined, different)), "{}", html);
+        assert_eq!(html.matches(underlined).count(), 1);
+    }
+
+    #[test]
+    fn synced_table_flags_the_current_slot_only_beyond_tolerance() {
+        let with_slots = |computed, current| {
+            let mut process = synced_with_reserves(&[], &[]);
+            process.computed_slot = Some(computed);
+            process.current_slot = Some(current);
+            process
+        };
+
+        let filter = ReserveFilter::default();
+        let render = |process, tolerance| render_synced_table(&[process], &filter, &filter, false, tolerance);
+
+        assert!(!render(with_slots(100, 100), 0).contains("class='error'"));
+        assert!(render(with_slots(100, 101), 0).contains("class='error'"));
+        assert!(!render(with_slots(100, 105), 5).contains("class='error'"));
+        assert!(render(with_slots(100, 106), 5).contains("class='error'"));
+    }
+
+    #[tokio::test]
+    async fn activity_log_is_only_served_by_the_detail_endpoint() {
+        let state = app_state(Arc::new(manager()), config_for("http://127.0.0.1:9"));
+        add_active(&state.queue, "pid").await;
+        let url = serve(
+            Router::new()
+                .route("/api/process/:id", get(get_process))
+                .route("/api/status", get(get_status))
+                .with_state(state.clone()),
+        ).await;
+
+        let (status, detail) = get_json(&url, "/api/process/pid").await;
+        assert_eq!(status, reqwest::StatusCode::OK);
+        assert_eq!(detail["data"]["activity_log"][0]["message"], "Activated");
+
+        let (_, list) = get_json(&url, "/api/status").await;
+        let active = &list["data"]["active_processes"][0];
+        assert_eq!(active["process_id"], "pid");
+        assert!(active.get("activity_log").is_none());
+    }
+
+    /// Routes recording each JSON body posted to `/hook`, alongside the slot routes.
+    fn webhook_node(slots: MockSlots) -> (Router, Arc<std::sync::Mutex<Vec<serde_json::Value>>>) {
+        let received = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let hook = received.clone();
+        let node = slot_node(slots).route("/hook", post(move |Json(body): Json<serde_json::Value>| async move {
+            hook.lock().unwrap().push(body);
+        }));
+        (node, received)
+    }
+
+    #[tokio::test]
+    async fn syncing_posts_the_webhook_payload() {
+        let (node, received) = webhook_node(MockSlots::new(100, 100));
+        let url = serve(node).await;
+        let mut config = config_for(&url);
+        config.notifications.webhook_url = Some(format!("{}/hook", url));
+        config.monitoring.fetch_reserves = false;
+        let client = client_for(&config);
+        let queue = manager();
+        add_active(&queue, "pid").await;
+
+        let process = queue.get_process("pid").await.unwrap();
+        check_process(&client, &queue, &process, &config).await.unwrap();
+        for _ in 0..50 {
+            if !received.lock().unwrap().is_empty() {
+                break;
+            }
+            sleep(Duration::from_millis(20)).await;
+        }
+
+        let synced = queue.get_process("pid").await.unwrap();
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0]["process_id"], "pid");
+        assert_eq!(received[0]["name"], "pool-pid");
+        assert_eq!(received[0]["synced_at"], serde_json::to_value(synced.synced_at).unwrap());
+        assert!(received[0].get("time_to_sync_seconds").is_some());
+    }
+
+    #[tokio::test]
+    async fn dashboard_throughput_matches_the_status_api() {
+        let app = TestApp::new(config_for("http://127.0.0.1:9")).await;
+        let queue = &app.state.queue;
+        for (id, rate) in [("fast", 10.0), ("slow", 2.5), ("drifted", 0.0)] {
+            add_active(queue, id).await;
+            queue.update_process_status(id, |status| status.metrics.avg_sync_rate = rate).await.unwrap();
+        }
+        queue.mark_synced("drifted").await.unwrap();
+        queue.update_process_status("drifted", |status| {
+            status.computed_slot = Some(90);
+            status.current_slot = Some(100);
+        }).await.unwrap();
+        // Still initializing, so not in the cron list snapshot; the queue counts it anyway
+        assert!(app.state.active_from_crons.read().await.is_empty());
+
+        let (_, body) = app.get("/api/status").await;
+        assert_eq!(body["data"]["aggregate_sync_rate"], 12.5);
+        assert_eq!(body["data"]["desynced_count"], 1);
+        let html = app.html("/").await;
+        assert!(html.contains("Synced: 1 (1 desynced) | Throughput: 12.5 slots/min"), "{}", html);
+    }
+
+    #[tokio::test]
+    async fn reloaded_intervals_are_picked_up_by_running_loops() {
+        let (state, reads) = run_queue_slot_monitor(0, 1).await;
+        for _ in 0..100 {
+            if reads() >= 2 {
+                break;
+            }
+            sleep(Duration::from_millis(50)).await;
+        }
+        assert!(reads() >= 2);
+
+        let mut new_config = (*state.config()).clone();
+        new_config.monitoring.queue_slots_interval = 3600;
+        new_config.limits.max_active_processes = 3;
+        let (status, Json(response)) = apply_reloaded_config(&state, Ok(new_config));
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(response.data.unwrap().monitoring.queue_slots_interval, 3600);
+        assert_eq!(state.config().monitoring.queue_slots_interval, 3600);
+        assert_eq!(state.queue.max_active_processes(), 3);
+
+        // At most the read already waiting out the old interval still happens
+        sleep(Duration::from_millis(1500)).await;
+        let settled = reads();
+        sleep(Duration::from_millis(1500)).await;
+        assert_eq!(reads(), settled);
+    }
+
+    #[tokio::test]
+    async fn reloads_that_need_a_restart_are_rejected() {
+        let state = app_state(Arc::new(manager()), config_for("http://127.0.0.1:9"));
+        let mut new_config = (*state.config()).clone();
+        new_config.server.port += 1;
+        new_config.monitoring.queue_slots_interval = 3600;
+
+        let (status, Json(response)) = apply_reloaded_config(&state, Ok(new_config));
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(response.error_code, Some(ErrorCode::InvalidConfig));
+        assert_eq!(response.error.unwrap(), "Cannot reload without a restart, changed: server");
+        assert_ne!(state.config().monitoring.queue_slots_interval, 3600);
+
+        let (status, _) = apply_reloaded_config(&state, Err(anyhow!("Invalid configuration")));
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn adding_to_a_full_queue_is_a_503() {
+        let state = app_state(Arc::new(manager()), ServiceConfig::default());
+        state.queue.set_max_queue_size(1);
+        let router = Router::new().route("/api/queue/add", post(add_to_queue)).with_state(state.clone());
+        let pool = |id: &str| serde_json::json!({ "name": format!("pool-{}", id), "process_id": id });
+
+        let (status, body) = post_json(router.clone(), "/api/queue/add", pool("a")).await;
+        assert_eq!((status, body["success"].as_bool()), (reqwest::StatusCode::OK, Some(true)));
+
+        let (status, body) = post_json(router, "/api/queue/add", pool("b")).await;
+        assert_eq!(status, reqwest::StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["success"], false);
+        assert_eq!(body["error"], "Queue full (max 1 processes)");
+        assert_eq!(body["error_code"], "QUEUE_FULL");
+        assert_eq!(state.queue.get_status().await, (0, 1, 0));
+    }
+
+    #[tokio::test]
+    async fn status_times_are_rfc3339_strings() {
+        let app = TestApp::new(config_for("http://127.0.0.1:9")).await;
+        let (_, body) = app.get("/api/status").await;
+        let time = |field: &str| {
+            let value = body["data"][field].as_str().unwrap_or_else(|| panic!("{} is not a string", field));
+            chrono::DateTime::parse_from_rfc3339(value).unwrap().with_timezone(&Utc)
+        };
+
+        assert_eq!(time("start_time"), app.state.start_time);
+        assert!(time("server_time") >= time("start_time"));
+        assert!(body["data"]["runtime_seconds"].is_u64());
+    }
+
+    #[tokio::test]
+    async fn paused_processes_are_skipped_until_unpaused() {
+        let (node, log) = record_requests(slot_node(MockSlots::new(0, 100)));
+        let mut config = config_for(&serve(node).await);
+        config.monitoring.monitor_loop_interval = 1;
+        let app = TestApp::new(config).await;
+        let queue = &app.state.queue;
+        add_active(queue, "pool").await;
+        queue.update_process_status("pool", |status| status.cron_initialized = true).await.unwrap();
+        queue.add_to_queue(ProcessConfig { name: "waiting".to_string(), process_id: "waiting".to_string(), base_url: None }).await.unwrap();
+        queue.set_paused("pool", true).await.unwrap();
+        queue.set_paused("waiting", true).await.unwrap();
+
+        tokio::spawn(monitor_loop(app.state.clone()));
+        sleep(Duration::from_millis(1500)).await;
+        assert!(log.requests().is_empty(), "{:?}", log.requests());
+        assert_eq!(queue.get_process("pool").await.unwrap().last_checked, None);
+        assert_eq!(queue.get_process("waiting").await.unwrap().state, ProcessState::Queued);
+
+        let (status, _) = app.post("/api/process/pool/unpause").await;
+        assert_eq!(status, reqwest::StatusCode::OK);
+        for _ in 0..50 {
+            if queue.get_process("pool").await.unwrap().last_checked.is_some() {
+                break;
+            }
+            sleep(Duration::from_millis(100)).await;
+        }
+        let process = queue.get_process("pool").await.unwrap();
+        assert_eq!((process.computed_slot, process.current_slot), (Some(0), Some(100)));
+        assert!(log.contains("GET /pool~process@1.0/slot/current"));
+        assert_eq!(queue.get_process("waiting").await.unwrap().state, ProcessState::Queued);
+    }
+
+    /// Slot routes plus a cron list endpoint serving whatever `crons` holds.
+    fn cron_node(crons: Arc<std::sync::Mutex<Vec<CronItem>>>) -> Router {
+        slot_node(MockSlots::new(0, 100)).route("/~cron@1.0/list/serialize~json@1.0", get(move || async move {
+            let body = crons.lock().unwrap().clone();
+            Json(serde_json::json!({ "body": body, "device": "cron@1.0", "status": 200 }))
+        }))
+    }
+
+    #[tokio::test]
+    async fn active_from_crons_follows_each_cron_list_refresh() {
+        let crons = Arc::new(std::sync::Mutex::new(vec![cron_item("a", "task-a")]));
+        let mut config = config_for(&serve(cron_node(crons.clone())).await);
+        config.monitoring.cron_list_interval = 1;
+        let app = TestApp::new(config).await;
+        for id in ["a", "b"] {
+            app.state.queue.add_to_queue(ProcessConfig { name: format!("pool-{}", id), process_id: id.to_string(), base_url: None }).await.unwrap();
+        }
+
+        async fn wait_for(app: &TestApp, expected: &[&str]) {
+            for _ in 0..50 {
+                if ids(&app.state.active_from_crons.read().await) == expected {
+                    return;
+                }
+                sleep(Duration::from_millis(100)).await;
+            }
+            panic!("active from crons never became {:?}", expected);
+        }
+
+        tokio::spawn(monitor_cron_list(app.state.clone()));
+        wait_for(&app, &["a"]).await;
+        assert!(app.state.hyperbeam_reachable.load(Ordering::Relaxed));
+
+        *crons.lock().unwrap() = vec![cron_item("b", "task-b"), cron_item("untracked", "task-c")];
+        wait_for(&app, &["b"]).await;
+    }
+
+    #[tokio::test]
+    async fn active_endpoint_lists_tracked_processes_with_a_cron() {
+        let app = TestApp::new(config_for("http://127.0.0.1:9")).await;
+        add_active(&app.state.queue, "synced").await;
+        app.state.queue.mark_synced("synced").await.unwrap();
+        add_active(&app.state.queue, "cronless").await;
+        *app.state.cron_list.write().await = vec![cron_item("synced", "task-1"), cron_item("untracked", "task-2")];
+        refresh_active_from_crons(&app.state).await;
+
+        let (status, body) = app.get("/api/active").await;
+        assert_eq!(status, reqwest::StatusCode::OK);
+        let processes = body["data"].as_array().unwrap();
+        assert_eq!(processes.len(), 1);
+        assert_eq!(processes[0]["process_id"], "synced");
+        assert_eq!(processes[0]["state"], "synced");
+        assert_eq!(processes[0]["cron_created_at"], serde_json::to_value(chrono::DateTime::from_timestamp_millis(1_700_000_000_000)).unwrap());
+    }
+
+    #[tokio::test]
+    async fn initialization_gives_up_after_the_maximum_attempts() {
+        let config = config_for(&serve(Router::new()).await);
+        let client = client_for(&config);
+        let queue = QueueManager::new(10, 0, 2, Vec::new(), Vec::new(), QueueStrategy::Fifo);
+        queue.add_to_queue(ProcessConfig { name: "pool".to_string(), process_id: "bad".to_string(), base_url: None }).await.unwrap();
+
+        for attempt in 1..=2 {
+            let config = queue.activate_next().await.unwrap();
+            let error = initialize_process(&client, &queue, &config).await.unwrap_err();
+            queue.mark_init_failed("bad", error.to_string()).await.unwrap();
+            let process = queue.get_process("bad").await.unwrap();
+            assert_eq!(process.init_attempts, attempt);
+            assert_eq!(process.state, ProcessState::Error);
+            if attempt == 1 {
+                assert!(!process.error.unwrap().starts_with("Max init attempts exceeded"));
+                queue.restart_process("bad").await.unwrap();
+            }
+        }
+
+        let process = queue.get_process("bad").await.unwrap();
+        assert!(process.error.unwrap().starts_with("Max init attempts exceeded"));
+        assert_eq!(
+            queue.restart_process("bad").await,
+            Err(QueueError::InitAttemptsExhausted { process_id: "bad".to_string(), max: 2 }),
+        );
+        assert_eq!(queue.get_status().await, (0, 0, 0));
+        assert!(queue.activate_next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn version_endpoint_reports_the_crate_version() {
+        let app = TestApp::new(config_for("http://127.0.0.1:9")).await;
+        let (status, body) = app.get("/api/version").await;
+        assert_eq!(status, reqwest::StatusCode::OK);
+        assert_eq!(body["version"], env!("CARGO_PKG_VERSION"));
+        assert_eq!(body["state_version"], state::STATE_VERSION);
+        assert_eq!(body["git_sha"], serde_json::json!(option_env!("GIT_SHA")));
+    }
+
+    #[tokio::test]
+    async fn status_filters_combine_across_every_list() {
+        let app = TestApp::new(config_for("http://127.0.0.1:9")).await;
+        let queue = &app.state.queue;
+        for (id, computed) in [("usdc-far", 0), ("eth-near", 450), ("usdc-done", 500)] {
+            add_active(queue, id).await;
+            queue.update_process_status(id, |status| {
+                status.computed_slot = Some(computed);
+                status.current_slot = Some(500);
+            }).await.unwrap();
+        }
+        queue.mark_synced("usdc-done").await.unwrap();
+        queue.add_to_queue(ProcessConfig { name: "USDC waiting".to_string(), process_id: "q".to_string(), base_url: None }).await.unwrap();
+
+        let lists = |query: &'static str| {
+            let app = &app;
+            async move {
+                let (status, body) = app.get(&format!("/api/status{}", query)).await;
+                assert_eq!(status, reqwest::StatusCode::OK);
+                assert_eq!(body["data"]["total_count"], 4, "counts ignore filters");
+                let ids = |list: &str| -> Vec<String> {
+                    body["data"][list].as_array().unwrap().iter()
+                        .map(|p| p["process_id"].as_str().unwrap().to_string())
+                        .collect()
+                };
+                (ids("active_processes"), ids("queue_preview"), ids("recent_synced"))
+            }
+        };
+        let expect = |active: &[&str], queued: &[&str], synced: &[&str]| {
+            let owned = |ids: &[&str]| ids.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+            (owned(active), owned(queued), owned(synced))
+        };
+
+        assert_eq!(lists("").await, expect(&["usdc-far", "eth-near"], &["q"], &["usdc-done"]));
+        assert_eq!(lists("?state=active").await, expect(&["usdc-far", "eth-near"], &[], &[]));
+        assert_eq!(lists("?state=queued").await, expect(&[], &["q"], &[]));
+        assert_eq!(lists("?state=synced").await, expect(&[], &[], &["usdc-done"]));
+        // Matches the process ID or, case-insensitively, the name
+        assert_eq!(lists("?search=usdc").await, expect(&["usdc-far"], &["q"], &["usdc-done"]));
+        assert_eq!(lists("?min_deficit=100").await, expect(&["usdc-far"], &[], &[]));
+        assert_eq!(lists("?min_deficit=50").await, expect(&["usdc-far", "eth-near"], &[], &[]));
+        assert_eq!(lists("?state=active&search=eth").await, expect(&["eth-near"], &[], &[]));
+        assert_eq!(lists("?state=synced&min_deficit=1").await, expect(&[], &[], &[]));
+        assert_eq!(lists("?search=usdc&min_deficit=100").await, expect(&["usdc-far"], &[], &[]));
+        assert_eq!(lists("?state=active&search=usdc&min_deficit=600").await, expect(&[], &[], &[]));
+    }
+
+    #[tokio::test]
+    async fn status_sort_applies_to_active_and_synced_lists() {
+        let app = TestApp::new(config_for("http://127.0.0.1:9")).await;
+        let queue = &app.state.queue;
+        for (id, rate) in [("a", 5.0), ("b", 20.0), ("c", 10.0), ("d", 1.0), ("e", 30.0), ("f", 15.0)] {
+            add_active(queue, id).await;
+            queue.update_process_status(id, |status| status.metrics.avg_sync_rate = rate).await.unwrap();
+        }
+        for id in ["d", "e", "f"] {
+            queue.mark_synced(id).await.unwrap();
+        }
+
+        let ids = |list: &serde_json::Value| -> Vec<String> {
+            list.as_array().unwrap().iter().map(|p| p["process_id"].as_str().unwrap().to_string()).collect()
+        };
+        let (_, body) = app.get("/api/status?sort=rate&order=asc").await;
+        assert_eq!(ids(&body["data"]["active_processes"]), ["a", "c", "b"]);
+        assert_eq!(ids(&body["data"]["recent_synced"]), ["d", "f", "e"]);
+
+        let (_, body) = app.get("/api/status?sort=process_id&order=desc").await;
+        assert_eq!(ids(&body["data"]["active_processes"]), ["c", "b", "a"]);
+        assert_eq!(ids(&body["data"]["recent_synced"]), ["f", "e", "d"]);
+    }
+
+    #[tokio::test]
+    async fn check_process_measures_advancement_from_the_live_status() {
+        let slots = MockSlots::new(100, 1000);
+        let config = config_for(&serve(slot_node(slots.clone())).await);
+        let client = client_for(&config);
+        let queue = manager();
+        add_active(&queue, "pid").await;
+
+        // Snapshot taken before the first check ran
+        let stale = queue.get_process("pid").await.unwrap();
+        check_process(&client, &queue, &stale, &config).await.unwrap();
+
+        queue.update_process_status("pid", |status| {
+            status.last_checked = Some(Utc::now() - chrono::Duration::minutes(1));
+        }).await.unwrap();
+        slots.set(150, 1000);
+        check_process(&client, &queue, &stale, &config).await.unwrap();
+
+        let status = queue.get_process("pid").await.unwrap();
+        assert_eq!(status.computed_slot, Some(150));
+        assert_eq!(status.metrics.slots_advanced_last_check, 50);
+        assert_eq!(status.metrics.total_slots_advanced, 50);
+        assert!(status.metrics.instant_sync_rate.is_some_and(|rate| (rate - 50.0).abs() < 1.0));
+    }
+
+    #[tokio::test]
+    async fn stop_cron_cancels_only_the_process_tasks() {
+        let crons = Arc::new(std::sync::Mutex::new(vec![
+            cron_item("pool", "task-1"),
+            cron_item("other", "task-2"),
+            cron_item("pool", "task-gone"),
+        ]));
+        let node = cron_node(crons).route("/~cron@1.0/stop", get(|Query(query): Query<HashMap<String, String>>| async move {
+            if query["task"] == "task-gone" { StatusCode::NOT_FOUND } else { StatusCode::OK }
+        }));
+        let (node, log) = record_requests(node);
+        let app = TestApp::new(config_for(&serve(node).await)).await;
+        for id in ["pool", "idle"] {
+            add_active(&app.state.queue, id).await;
+            app.state.queue.update_process_status(id, |status| status.cron_initialized = true).await.unwrap();
+        }
+
+        let (status, body) = app.post("/api/process/pool/stop-cron").await;
+        assert_eq!(status, reqwest::StatusCode::OK);
+        assert_eq!(body["data"], "Cron stopped for process pool");
+        let stops: Vec<_> = log.requests().into_iter().filter(|r| r.contains("/stop")).collect();
+        assert_eq!(stops, ["GET /~cron@1.0/stop?task=task-1", "GET /~cron@1.0/stop?task=task-gone"]);
+        assert!(!app.state.queue.get_process("pool").await.unwrap().cron_initialized);
+
+        // Nothing to cancel is not an error
+        let (status, body) = app.post("/api/process/idle/stop-cron").await;
+        assert_eq!(status, reqwest::StatusCode::OK);
+        assert_eq!(body["data"], "No running cron found for process idle");
+        assert!(!app.state.queue.get_process("idle").await.unwrap().cron_initialized);
+    }
+
+    #[tokio::test]
+    async fn a_stopped_cron_stays_stopped_across_monitor_ticks_and_restarts() {
+        let crons = Arc::new(std::sync::Mutex::new(vec![cron_item("pool", "task-1")]));
+        let node = cron_node(crons)
+            .route("/~cron@1.0/stop", get(|| async { StatusCode::OK }))
+            .route("/~cron@1.0/once", get(|| async { "ok" }));
+        let (node, log) = record_requests(node);
+        let mut config = config_for(&serve(node).await);
+        config.monitoring.monitor_loop_interval = 1;
+        let app = TestApp::new(config.clone()).await;
+        let queue = &app.state.queue;
+        queue.set_max_active_processes(1);
+        add_active(queue, "pool").await;
+        queue.update_process_status("pool", |status| status.cron_initialized = true).await.unwrap();
+        queue.add_to_queue(ProcessConfig { name: "next".to_string(), process_id: "next".to_string(), base_url: None }).await.unwrap();
+
+        let (status, _) = app.post("/api/process/pool/stop-cron").await;
+        assert_eq!(status, reqwest::StatusCode::OK);
+        let process = queue.get_process("pool").await.unwrap();
+        assert_eq!(process.state, ProcessState::Queued);
+        assert!(process.paused && !process.cron_initialized);
+
+        // The freed slot goes to the next queued process, not back to the stopped one
+        tokio::spawn(monitor_loop(app.state.clone()));
+        for _ in 0..100 {
+            if queue.get_process("next").await.unwrap().cron_initialized {
+                break;
+            }
+            sleep(Duration::from_millis(20)).await;
+        }
+        assert!(queue.get_process("next").await.unwrap().cron_initialized);
+        assert_eq!(queue.get_process("pool").await.unwrap().state, ProcessState::Queued);
+
+        // Neither does a restart restart the stopped cron
+        state::save_state(&app.state.store, queue, &config.state).await.unwrap();
+        let restarted = Arc::new(manager());
+        assert!(state::load_state(&app.state.store, &restarted).await.unwrap());
+        recover_uninitialized_active(&app.state.client, &restarted, UninitializedActivePolicy::Reinitialize).await;
+        assert!(restarted.activate_next().await.is_none());
+        sleep(Duration::from_millis(100)).await;
+        let process = restarted.get_process("pool").await.unwrap();
+        assert_eq!(process.state, ProcessState::Queued);
+        assert!(process.paused && !process.cron_initialized);
+        assert!(!log.contains("GET /~cron@1.0/once?cron-path=/pool~process@1.0/now"));
+    }
+
+    #[tokio::test]
+    async fn synced_endpoint_filters_by_sync_time() {
+        let app = TestApp::new(config_for("http://127.0.0.1:9")).await;
+        let now = Utc::now();
+        for (id, minutes_ago) in [("old", 180), ("recent", 30), ("newest", 5)] {
+            add_active(&app.state.queue, id).await;
+            app.state.queue.mark_synced(id).await.unwrap();
+            app.state.queue.update_process_status(id, |status| {
+                status.synced_at = Some(now - chrono::Duration::minutes(minutes_ago));
+            }).await.unwrap();
+        }
+        let synced = |query: String| {
+            let app = &app;
+            async move {
+                let (status, body) = app.get(&format!("/api/synced{}", query)).await;
+                assert_eq!(status, reqwest::StatusCode::OK);
+                body["data"].as_array().unwrap().iter()
+                    .map(|p| p["process_id"].as_str().unwrap().to_string())
+                    .collect::<Vec<_>>()
+            }
+        };
+        let since = |minutes: i64| {
+            let cutoff = now - chrono::Duration::minutes(minutes);
+            format!("?since={}", cutoff.to_rfc3339_opts(chrono::SecondsFormat::Micros, true))
+        };
+
+        assert_eq!(synced(String::new()).await, ["newest", "recent", "old"]);
+        assert_eq!(synced(since(60)).await, ["newest", "recent"]);
+        // The cutoff itself is included
+        assert_eq!(synced(since(30)).await, ["newest", "recent"]);
+        assert_eq!(synced(since(1)).await, Vec::<String>::new());
+    }
+
+    #[tokio::test]
+    async fn spawned_checks_log_with_the_process_id() {
+        let logs = CapturedLogs::default();
+        let writer = logs.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(move || writer.clone())
+            .with_ansi(false)
+            .finish();
+        // The test runtime is single-threaded, so spawned tasks see this subscriber too
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let app = TestApp::new(config_for(&serve(Router::new()).await)).await;
+        add_active(&app.state.queue, "pool").await;
+        app.state.queue.update_process_status("pool", |status| status.cron_initialized = true).await.unwrap();
+        tokio::spawn(monitor_loop(app.state.clone()));
+
+        for _ in 0..50 {
+            if logs.text().contains("Error checking process pool") {
+                break;
+            }
+            sleep(Duration::from_millis(20)).await;
+        }
+        let line = logs.text().lines()
+            .find(|line| line.contains("Error checking process pool"))
+            .map(str::to_string)
+            .unwrap_or_else(|| panic!("no check error logged:\n{}", logs.text()));
+        assert!(line.contains("check{process_id=pool}"), "{}", line);
+    }
+
+    #[tokio::test]
+    async fn clearing_the_queue_leaves_active_and_synced_pools() {
+        let app = TestApp::new(config_for("http://127.0.0.1:9")).await;
+        let queue = &app.state.queue;
+        add_active(queue, "active").await;
+        add_active(queue, "synced").await;
+        queue.mark_synced("synced").await.unwrap();
+        for id in ["a", "b", "c"] {
+            queue.add_to_queue(ProcessConfig { name: format!("pool-{}", id), process_id: id.to_string(), base_url: None }).await.unwrap();
+        }
+
+        let (status, body) = app.post("/api/queue/clear").await;
+        assert_eq!(status, reqwest::StatusCode::OK);
+        assert_eq!(body["data"], 3);
+        assert_eq!(queue.get_status().await, (1, 0, 1));
+        for id in ["a", "b", "c"] {
+            assert!(queue.get_process(id).await.is_none());
+        }
+        assert_eq!(queue.get_process("active").await.unwrap().state, ProcessState::Active);
+        assert_eq!(queue.get_process("synced").await.unwrap().state, ProcessState::Synced);
+
+        let (_, body) = app.post("/api/queue/clear").await;
+        assert_eq!(body["data"], 0);
+    }
+
+    #[tokio::test]
+    async fn synced_pool_monitor_skips_reserves_when_disabled() {
+        let (_fetching, fetching_log) = run_synced_pool_monitor(false, true).await;
+        let (skipping, skipping_log) = run_synced_pool_monitor(false, false).await;
+        let is_reserve_call = |request: &String| request.contains("/reserves") || request.contains("/dry-run");
+
+        // The monitor waits 5s before its first pass
+        for _ in 0..100 {
+            let checked = skipping.state.queue.get_process("pool").await.unwrap().last_checked.is_some();
+            if checked && fetching_log.requests().iter().any(is_reserve_call) {
+                break;
+            }
+            sleep(Duration::from_millis(100)).await;
+        }
+
+        assert!(fetching_log.requests().iter().any(is_reserve_call));
+        assert!(skipping.state.queue.get_process("pool").await.unwrap().last_checked.is_some());
+        assert!(skipping_log.contains("GET /pool~process@1.0/slot/current"));
+        assert!(!skipping_log.requests().iter().any(is_reserve_call), "{:?}", skipping_log.requests());
+    }
+
+    /// A node answering both reserve requests: HyperBEAM with `hb`, the AO CU with `ao`.
+    fn reserves_node(hb: Option<HashMap<String, String>>, ao: Option<HashMap<String, String>>) -> Router {
+        let hb_route = get(move || async move {
+            match hb {
+                Some(reserves) => Json(reserves).into_response(),
+                None => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+            }
+        });
+        let ao_route = post(move || async move {
+            match ao {
+                Some(reserves) => {
+                    let tags: Vec<_> = reserves.iter().map(|(k, v)| serde_json::json!({ "name": k, "value": v })).collect();
+                    Json(serde_json::json!({ "Messages": [{ "Tags": tags }] })).into_response()
+                }
+                None => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+            }
+        });
+        Router::new()
+            .route("/:process/now/reserves", hb_route)
+            .route("/dry-run", ao_route)
+    }
+
+    #[tokio::test]
+    async fn refresh_reserves_reports_an_upstream_error_when_both_sides_fail() {
+        let (node, log) = record_requests(reserves_node(None, None));
+        let app = TestApp::new(config_for(&serve(node).await)).await;
+        add_active(&app.state.queue, "pool").await;
+
+        let (status, body) = app.post("/api/process/pool/reserves/refresh").await;
+        assert_eq!(status, reqwest::StatusCode::BAD_GATEWAY);
+        assert_eq!(body["success"], false);
+        assert_eq!(body["error_code"], "UPSTREAM");
+        assert!(log.contains("GET /pool~process@1.0/now/reserves"));
+        assert!(log.contains("POST /dry-run?process-id=pool"));
+        assert!(app.state.queue.get_process("pool").await.unwrap().reserves_last_checked.is_none());
+    }
+
+    #[tokio::test]
+    async fn refresh_reserves_stores_whichever_side_answered() {
+        let hb = HashMap::from([(token('a'), "100".to_string())]);
+        let (node, log) = record_requests(reserves_node(Some(hb.clone()), None));
+        let app = TestApp::new(config_for(&serve(node).await)).await;
+        add_active(&app.state.queue, "pool").await;
+
+        let (_, body) = app.post("/api/process/pool/reserves/refresh").await;
+        assert_eq!(body["success"], true);
+        assert!(log.contains("POST /dry-run?process-id=pool"));
+        let process = app.state.queue.get_process("pool").await.unwrap();
+        assert_eq!(process.hb_reserves, Some(hb));
+        assert_eq!(process.ao_reserves, None);
+        assert!(process.reserves_last_checked.is_some());
+    }
+
+    #[tokio::test]
+    async fn process_actions_map_error_codes_to_http_statuses() {
+        let app = TestApp::new(config_for("http://127.0.0.1:9")).await;
+        let url = |path: &str| format!("{}/api/process/{}", app.url, path);
+        let priority = serde_json::json!({ "position": 0 });
+
+        let missing = [
+            app.http.post(url("missing/restart")),
+            app.http.post(url("missing/pause")),
+            app.http.post(url("missing/unpause")),
+            app.http.post(url("missing/reset-metrics")),
+            app.http.post(url("missing/force-resync")),
+            app.http.post(url("missing/stop-cron")),
+            app.http.post(url("missing/reserves/refresh")),
+            app.http.delete(url("missing")),
+            app.http.put(url("missing/priority")).json(&priority),
+        ];
+        for request in missing {
+            let (status, body) = app.send(request).await;
+            assert_eq!(status, reqwest::StatusCode::NOT_FOUND, "{}", body);
+            assert_eq!(body["error_code"], "NOT_FOUND");
+        }
+
+        // Only queued processes have a queue position
+        add_active(&app.state.queue, "pool").await;
+        let (status, body) = app.send(app.http.put(url("pool/priority")).json(&priority)).await;
+        assert_eq!(status, reqwest::StatusCode::CONFLICT);
+        assert_eq!(body["error_code"], "INVALID_STATE");
+
+        let (status, body) = app.post("/api/process/pool/pause").await;
+        assert_eq!(status, reqwest::StatusCode::OK);
+        assert_eq!(body["success"], true);
+        let (status, _) = app.send(app.http.delete(url("pool"))).await;
+        assert_eq!(status, reqwest::StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn trend_samples_accumulate_up_to_the_cap() {
+        let app = TestApp::new(config_for("http://127.0.0.1:9")).await;
+        record_trend_sample(&app.state).await;
+        add_active(&app.state.queue, "pool").await;
+        record_trend_sample(&app.state).await;
+
+        let (status, body) = app.get("/api/trends").await;
+        assert_eq!(status, reqwest::StatusCode::OK);
+        let samples = body["data"].as_array().unwrap();
+        assert_eq!(samples.len(), 2);
+        assert_eq!((samples[0]["active"].as_u64(), samples[1]["active"].as_u64()), (Some(0), Some(1)));
+
+        for _ in 0..MAX_TREND_SAMPLES {
+            record_trend_sample(&app.state).await;
+        }
+        let trends = app.state.trends.read().await;
+        assert_eq!(trends.len(), MAX_TREND_SAMPLES);
+        // The two oldest samples were dropped
+        assert!(trends.iter().all(|sample| sample.active == 1));
+        assert!(trends.iter().zip(trends.iter().skip(1)).all(|(a, b)| a.timestamp <= b.timestamp));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn serves_health_over_a_unix_socket() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("hydration.sock");
+        // A socket file left behind by a previous run must not block binding
+        drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+
+        let mut config = config_for("http://127.0.0.1:9");
+        config.server.unix_socket = Some(path.to_string_lossy().into_owned());
+        let app = TestApp::new(config).await;
+        let (stop_tx, stop_rx) = tokio::sync::oneshot::channel::<()>();
+        let server = tokio::spawn(serve_until_shutdown(app.state.clone(), async move {
+            let _ = stop_rx.await;
+        }));
+
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(5);
+        let mut stream = loop {
+            match tokio::net::UnixStream::connect(&path).await {
+                Ok(stream) => break stream,
+                Err(_) if tokio::time::Instant::now() < deadline => {
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+                }
+                Err(e) => panic!("socket never accepted connections: {}", e),
+            }
+        };
+        stream
+            .write_all(b"GET /api/health HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+        let (_, body) = response.split_once("\r\n\r\n").unwrap();
+        let body: serde_json::Value = serde_json::from_str(body).unwrap();
+        assert_eq!(body["status"], "ok");
+
+        stop_tx.send(()).unwrap();
+        server.await.unwrap().unwrap();
+        assert!(!path.exists());
+    }
+
+    #[tokio::test]
+    async fn processes_can_be_looked_up_by_name() {
+        let app = TestApp::new(config_for("http://127.0.0.1:9")).await;
+        for id in ["a", "b"] {
+            let (_, body) = app.post_json("/api/queue/add", serde_json::json!({ "name": "pool", "process_id": id })).await;
+            assert_eq!(body["success"], true);
+        }
+        app.post_json("/api/queue/add", serde_json::json!({ "name": "other", "process_id": "c" })).await;
+
+        let (status, body) = app.get("/api/process/by-name/pool").await;
+        assert_eq!(status, reqwest::StatusCode::OK);
+        let mut found: Vec<_> = body["data"].as_array().unwrap().iter()
+            .map(|p| p["process_id"].as_str().unwrap().to_string())
+            .collect();
+        found.sort();
+        assert_eq!(found, ["a", "b"]);
+
+        let (status, body) = app.get("/api/process/by-name/missing").await;
+        assert_eq!(status, reqwest::StatusCode::NOT_FOUND);
+        assert_eq!(body["error_code"], "NOT_FOUND");
+    }
+
+    #[tokio::test]
+    async fn duplicate_names_are_rejected_when_enforced() {
+        let mut config = config_for("http://127.0.0.1:9");
+        config.limits.enforce_unique_names = true;
+        let app = TestApp::new(config).await;
+
+        let (_, body) = app.post_json("/api/queue/add", serde_json::json!({ "name": "pool", "process_id": "a" })).await;
+        assert_eq!(body["success"], true);
+        let (_, body) = app.post_json("/api/queue/add", serde_json::json!({ "name": "pool", "process_id": "b" })).await;
+        assert_eq!(body["success"], false);
+        assert_eq!(body["error_code"], "DUPLICATE");
+        assert_eq!(body["error"], "A process named pool already exists");
+
+        // Batches are checked against names added earlier in the same batch too
+        let (_, body) = app.post_json("/api/queue/add", serde_json::json!([
+            { "name": "fresh", "process_id": "c" },
+            { "name": "fresh", "process_id": "d" },
+        ])).await;
+        let outcomes: Vec<_> = body["data"].as_array().unwrap().iter().map(|r| r["success"].as_bool().unwrap()).collect();
+        assert_eq!(outcomes, [true, false]);
+        assert_eq!(app.state.queue.get_status().await, (0, 2, 0));
+    }
+
+    #[tokio::test]
+    async fn ao_failures_mark_the_cu_unreachable_until_it_recovers() {
+        let ao_up = Arc::new(AtomicBool::new(false));
+        let up = ao_up.clone();
+        let node = Router::new().route("/dry-run", post(move || {
+            let up = up.load(Ordering::Relaxed);
+            async move {
+                if up {
+                    Json(serde_json::json!({ "Messages": [] })).into_response()
+                } else {
+                    StatusCode::BAD_GATEWAY.into_response()
+                }
+            }
+        }));
+        let app = TestApp::new(config_for(&serve(node).await)).await;
+        assert!(app.state.client.ao_cu_reachable());
+
+        assert!(app.state.client.fetch_ao_reserves("pool").await.is_err());
+        assert!(!app.state.client.ao_cu_reachable());
+        let (_, body) = app.get("/api/health").await;
+        assert_eq!(body["ao_cu_reachable"], false);
+        assert!(app.html("/").await.contains("AO CU UNREACHABLE"));
+
+        ao_up.store(true, Ordering::Relaxed);
+        assert!(app.state.client.fetch_ao_reserves("pool").await.unwrap().is_empty());
+        let (_, body) = app.get("/api/health").await;
+        assert_eq!(body["ao_cu_reachable"], true);
+        assert!(!app.html("/").await.contains("AO CU UNREACHABLE"));
+    }
+
+    #[tokio::test]
+    async fn process_history_returns_the_newest_samples_oldest_first() {
+        let app = TestApp::new(config_for("http://127.0.0.1:9")).await;
+        add_active(&app.state.queue, "pool").await;
+        app.state.queue.update_process_status("pool", |status| {
+            for slot in 1..=5 {
+                status.metrics.record_slot_sample(slot * 10, 100);
+            }
+        }).await.unwrap();
+        let computed = |body: &serde_json::Value| -> Vec<u64> {
+            body["data"].as_array().unwrap().iter().map(|s| s["computed_slot"].as_u64().unwrap()).collect()
+        };
+
+        let (status, body) = app.get("/api/process/pool/history").await;
+        assert_eq!(status, reqwest::StatusCode::OK);
+        assert_eq!(computed(&body), [10, 20, 30, 40, 50]);
+        assert_eq!(body["data"][0]["current_slot"], 100);
+        assert!(body["data"][0]["timestamp"].is_string());
+
+        let (_, body) = app.get("/api/process/pool/history?points=2").await;
+        assert_eq!(computed(&body), [40, 50]);
+        let (_, body) = app.get("/api/process/pool/history?points=100").await;
+        assert_eq!(computed(&body).len(), 5);
+
+        let (status, body) = app.get("/api/process/missing/history").await;
+        assert_eq!(status, reqwest::StatusCode::NOT_FOUND);
+        assert_eq!(body["error_code"], "NOT_FOUND");
+    }
+
+    #[test]
+    fn ui_ignored_reserve_keys_are_hidden_but_still_compared() {
+        let (shown, hidden) = (token('a'), token('b'));
+        let process = synced_with_reserves(
+            &[(&shown, "100"), (&hidden, "5")],
+            &[(&shown, "100"), (&hidden, "6")],
+        );
+        let match_filter = ReserveFilter::default();
+        let display_filter = match_filter.ignoring(std::slice::from_ref(&hidden));
+
+        let html = render_synced_table(std::slice::from_ref(&process), &match_filter, &display_filter, true, 0);
+        assert!(html.contains(&shown));
+        assert!(!html.contains(&hidden));
+        assert!(html.contains("[DIFF]"));
+
+        // With the differing key matching, the hidden key does not change the result
+        let process = synced_with_reserves(
+            &[(&shown, "100"), (&hidden, "5")],
+            &[(&shown, "100"), (&hidden, "5")],
+        );
+        let html = render_synced_table(&[process], &match_filter, &display_filter, true, 0);
+        assert!(html.contains("[OK]"));
+        assert!(!html.contains(&hidden));
+    }
+}
@@ -0,0 +1,201 @@
+use crate::models::ProcessStatus;
+use std::fmt::Write;
+
+pub struct MetricsSnapshot {
+    pub active_count: usize,
+    pub queued_count: usize,
+    pub synced_count: usize,
+    pub hb_requests: u64,
+    pub ao_requests: u64,
+    pub request_errors: u64,
+    /// Only active and synced processes, to keep label cardinality bounded
+    pub processes: Vec<ProcessStatus>,
+}
+
+fn write_gauge(out: &mut String, name: &str, help: &str, value: impl std::fmt::Display) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} gauge", name);
+    let _ = writeln!(out, "{} {}", name, value);
+}
+
+fn write_counter(out: &mut String, name: &str, help: &str, samples: &[(&str, u64)]) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} counter", name);
+    for (labels, value) in samples {
+        if labels.is_empty() {
+            let _ = writeln!(out, "{} {}", name, value);
+        } else {
+            let _ = writeln!(out, "{}{{{}}} {}", name, labels, value);
+        }
+    }
+}
+
+/// Escape a label value as the exposition format requires: backslash, double quote
+/// and newline.
+fn escape_label_value(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+fn write_process_gauge(
+    out: &mut String,
+    name: &str,
+    help: &str,
+    processes: &[ProcessStatus],
+    value: impl Fn(&ProcessStatus) -> Option<f64>,
+) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} gauge", name);
+    for process in processes {
+        if let Some(v) = value(process) {
+            let _ = writeln!(out, "{}{{process_id=\"{}\"}} {}", name, escape_label_value(&process.process_id), v);
+        }
+    }
+}
+
+/// Render the snapshot in the Prometheus text exposition format.
+pub fn render_prometheus(snapshot: &MetricsSnapshot) -> String {
+    let mut out = String::new();
+
+    write_gauge(&mut out, "hydration_active_processes", "Number of active processes", snapshot.active_count);
+    write_gauge(&mut out, "hydration_queued_processes", "Number of queued processes", snapshot.queued_count);
+    write_gauge(&mut out, "hydration_synced_processes", "Number of synced processes", snapshot.synced_count);
+    write_counter(
+        &mut out,
+        "hydration_upstream_requests_total",
+        "Requests sent to HyperBEAM and the AO CU",
+        &[
+            ("target=\"hyperbeam\"", snapshot.hb_requests),
+            ("target=\"ao\"", snapshot.ao_requests),
+        ],
+    );
+    write_counter(
+        &mut out,
+        "hydration_upstream_request_errors_total",
+        "Upstream requests that failed or returned a non-success status",
+        &[("", snapshot.request_errors)],
+    );
+
+    write_process_gauge(
+        &mut out,
+        "hydration_slot_deficit",
+        "Slots the process is behind its current slot",
+        &snapshot.processes,
+        |p| Some(p.deficit().unwrap_or(0) as f64),
+    );
+    write_process_gauge(
+        &mut out,
+        "hydration_avg_sync_rate",
+        "Average sync rate in slots per minute",
+        &snapshot.processes,
+        |p| Some(p.metrics.avg_sync_rate),
+    );
+    write_process_gauge(
+        &mut out,
+        "hydration_api_response_time_ms",
+        "Mean of recent HyperBEAM slot request response times in milliseconds",
+        &snapshot.processes,
+        |p| {
+            let times = &p.metrics.api_response_times;
+            if times.is_empty() {
+                None
+            } else {
+                Some(times.iter().sum::<f64>() / times.len() as f64)
+            }
+        },
+    );
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::{HashMap, HashSet};
+
+    /// Parse the exposition text into `(name, labels) -> value`, checking that every
+    /// sample belongs to a metric declared with HELP and TYPE lines.
+    fn parse(text: &str) -> HashMap<(String, String), f64> {
+        let mut declared = HashSet::new();
+        let mut samples = HashMap::new();
+        for line in text.lines() {
+            if let Some(comment) = line.strip_prefix("# ") {
+                let mut parts = comment.splitn(3, ' ');
+                let (kind, name, rest) = (parts.next().unwrap(), parts.next().unwrap(), parts.next().unwrap());
+                match kind {
+                    "HELP" => assert!(!rest.is_empty()),
+                    "TYPE" => {
+                        assert!(["gauge", "counter"].contains(&rest), "{}", line);
+                        declared.insert(name.to_string());
+                    }
+                    _ => panic!("unexpected comment: {}", line),
+                }
+                continue;
+            }
+
+            let (series, value) = line.rsplit_once(' ').unwrap();
+            let (name, labels) = match series.split_once('{') {
+                Some((name, labels)) => (name, labels.strip_suffix('}').unwrap()),
+                None => (series, ""),
+            };
+            assert!(declared.contains(name), "undeclared metric: {}", line);
+            samples.insert((name.to_string(), labels.to_string()), value.parse().unwrap());
+        }
+        samples
+    }
+
+    fn process(id: &str, computed: u64, current: u64, response_times: Vec<f64>) -> ProcessStatus {
+        let mut status = ProcessStatus::new("pool".to_string(), id.to_string(), None);
+        status.computed_slot = Some(computed);
+        status.current_slot = Some(current);
+        status.metrics.avg_sync_rate = 12.5;
+        status.metrics.api_response_times = response_times;
+        status
+    }
+
+    #[test]
+    fn renders_parseable_exposition_text() {
+        let snapshot = MetricsSnapshot {
+            active_count: 1,
+            queued_count: 4,
+            synced_count: 1,
+            hb_requests: 20,
+            ao_requests: 3,
+            request_errors: 2,
+            processes: vec![process("a", 90, 100, vec![10.0, 30.0]), process("b", 100, 100, Vec::new())],
+        };
+        let samples = parse(&render_prometheus(&snapshot));
+        let value = |name: &str, labels: &str| samples.get(&(name.to_string(), labels.to_string())).copied();
+
+        assert_eq!(value("hydration_active_processes", ""), Some(1.0));
+        assert_eq!(value("hydration_queued_processes", ""), Some(4.0));
+        assert_eq!(value("hydration_synced_processes", ""), Some(1.0));
+        assert_eq!(value("hydration_upstream_requests_total", "target=\"hyperbeam\""), Some(20.0));
+        assert_eq!(value("hydration_upstream_requests_total", "target=\"ao\""), Some(3.0));
+        assert_eq!(value("hydration_upstream_request_errors_total", ""), Some(2.0));
+        assert_eq!(value("hydration_slot_deficit", "process_id=\"a\""), Some(10.0));
+        assert_eq!(value("hydration_slot_deficit", "process_id=\"b\""), Some(0.0));
+        assert_eq!(value("hydration_avg_sync_rate", "process_id=\"a\""), Some(12.5));
+        assert_eq!(value("hydration_api_response_time_ms", "process_id=\"a\""), Some(20.0));
+        // No samples, no series
+        assert_eq!(value("hydration_api_response_time_ms", "process_id=\"b\""), None);
+    }
+
+    #[test]
+    fn escapes_special_characters_in_process_ids() {
+        let snapshot = MetricsSnapshot {
+            active_count: 1,
+            queued_count: 0,
+            synced_count: 0,
+            hb_requests: 0,
+            ao_requests: 0,
+            request_errors: 0,
+            processes: vec![process("a\"b\\c\nd", 90, 100, Vec::new())],
+        };
+        let text = render_prometheus(&snapshot);
+        let samples = parse(&text);
+
+        let labels = r#"process_id="a\"b\\c\nd""#;
+        assert_eq!(samples.get(&("hydration_slot_deficit".to_string(), labels.to_string())), Some(&10.0), "{}", text);
+        assert_eq!(samples.get(&("hydration_avg_sync_rate".to_string(), labels.to_string())), Some(&12.5));
+    }
+}
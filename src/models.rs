@@ -1,8 +1,9 @@
 use chrono::{DateTime, Utc};
+use crate::config::ReserveFilter;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 #[serde(rename_all = "snake_case")]
 pub enum ProcessState {
     Queued,
@@ -20,6 +21,17 @@ pub struct ProcessConfig {
     pub base_url: Option<String>,
 }
 
+/// Number of slot samples kept per process
+pub const MAX_SLOT_HISTORY: usize = 100;
+
+/// One slot reading, for charting how a process converges on its current slot.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlotSample {
+    pub timestamp: DateTime<Utc>,
+    pub computed_slot: u64,
+    pub current_slot: u64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProcessMetrics {
     pub initial_slot_deficit: Option<u64>,
@@ -28,8 +40,124 @@ pub struct ProcessMetrics {
     pub sync_start_time: Option<DateTime<Utc>>,
     pub sync_end_time: Option<DateTime<Utc>>,
     pub avg_sync_rate: f64,
+    /// Exponential moving average of the per-check rate (slots/min), reacts faster than `avg_sync_rate`
+    #[serde(default)]
+    pub instant_sync_rate: Option<f64>,
     pub check_count: u64,
+    /// Consecutive checks where the computed slot did not advance despite a deficit
+    #[serde(default)]
+    pub checks_without_progress: u64,
     pub api_response_times: Vec<f64>,
+    /// Filled in on read from `api_response_times`
+    #[serde(default)]
+    pub p50_response_time: Option<f64>,
+    #[serde(default)]
+    pub p95_response_time: Option<f64>,
+    #[serde(default)]
+    pub time_to_sync_seconds: Option<i64>,
+    /// Recent slot samples, oldest first; kept in memory only and left out of list views
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub slot_history: Vec<SlotSample>,
+}
+
+impl ProcessMetrics {
+    /// Record a slot sample, dropping the oldest once the history is full.
+    pub fn record_slot_sample(&mut self, computed_slot: u64, current_slot: u64) {
+        if self.slot_history.len() >= MAX_SLOT_HISTORY {
+            self.slot_history.remove(0);
+        }
+        self.slot_history.push(SlotSample {
+            timestamp: Utc::now(),
+            computed_slot,
+            current_slot,
+        });
+    }
+
+    /// The most recent `points` samples, oldest first.
+    pub fn recent_slot_samples(&self, points: usize) -> &[SlotSample] {
+        &self.slot_history[self.slot_history.len().saturating_sub(points)..]
+    }
+
+    /// Restart rate tracking from now, e.g. after the computed slot went backwards
+    /// and the slots counted so far no longer describe real progress.
+    pub fn reset_sync_baseline(&mut self) {
+        self.sync_start_time = Some(Utc::now());
+        self.total_slots_advanced = 0;
+        self.slots_advanced_last_check = 0;
+        self.avg_sync_rate = 0.0;
+        self.instant_sync_rate = None;
+    }
+
+    /// Fold the rate seen over the last check into the moving average, weighting the
+    /// new sample by `smoothing` (0..=1).
+    pub fn update_instant_rate(&mut self, slots_advanced: u64, minutes: f64, smoothing: f64) {
+        if minutes <= 0.0 {
+            return;
+        }
+        let rate = slots_advanced as f64 / minutes;
+        self.instant_sync_rate = Some(match self.instant_sync_rate {
+            Some(previous) => smoothing * rate + (1.0 - smoothing) * previous,
+            None => rate,
+        });
+    }
+
+    /// Record a response time, keeping only the most recent `window` samples.
+    pub fn record_response_time(&mut self, millis: f64, window: usize) {
+        self.api_response_times.push(millis);
+        let excess = self.api_response_times.len().saturating_sub(window);
+        self.api_response_times.drain(..excess);
+    }
+
+    /// Count a check towards the stall detector. Returns true only on the check that
+    /// reaches `threshold`, so callers alert once per stall; a threshold of 0 never fires.
+    pub fn record_progress(&mut self, deficit: u64, threshold: u64) -> bool {
+        if self.slots_advanced_last_check > 0 || deficit == 0 {
+            self.checks_without_progress = 0;
+            return false;
+        }
+        self.checks_without_progress += 1;
+        threshold > 0 && self.checks_without_progress == threshold
+    }
+
+    /// Whether the stall detector has fired and the process has not advanced since.
+    pub fn is_stalled(&self, threshold: u64) -> bool {
+        threshold > 0 && self.checks_without_progress >= threshold
+    }
+
+    /// The moving average when available, otherwise the lifetime average.
+    pub fn effective_sync_rate(&self) -> f64 {
+        self.instant_sync_rate
+            .filter(|rate| *rate > 0.0)
+            .unwrap_or(self.avg_sync_rate)
+    }
+
+    /// Nearest-rank percentile of the recent response times, in milliseconds.
+    fn response_time_percentile(&self, percentile: f64) -> Option<f64> {
+        if self.api_response_times.is_empty() {
+            return None;
+        }
+        
+        let mut sorted = self.api_response_times.clone();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        let rank = (percentile / 100.0 * sorted.len() as f64).ceil() as usize;
+        Some(sorted[rank.clamp(1, sorted.len()) - 1])
+    }
+
+    pub fn p50_response_time(&self) -> Option<f64> {
+        self.response_time_percentile(50.0)
+    }
+
+    pub fn p95_response_time(&self) -> Option<f64> {
+        self.response_time_percentile(95.0)
+    }
+
+    /// How long the process took to catch up, from the first deficit reading to synced.
+    pub fn time_to_sync(&self) -> Option<chrono::Duration> {
+        match (self.sync_start_time, self.sync_end_time) {
+            (Some(start), Some(end)) if end >= start => Some(end - start),
+            _ => None,
+        }
+    }
 }
 
 impl Default for ProcessMetrics {
@@ -41,8 +169,14 @@ impl Default for ProcessMetrics {
             sync_start_time: None,
             sync_end_time: None,
             avg_sync_rate: 0.0,
+            instant_sync_rate: None,
             check_count: 0,
+            checks_without_progress: 0,
             api_response_times: Vec::new(),
+            p50_response_time: None,
+            p95_response_time: None,
+            time_to_sync_seconds: None,
+            slot_history: Vec::new(),
         }
     }
 }
@@ -51,42 +185,156 @@ impl Default for ProcessMetrics {
 pub struct ProcessStatus {
     pub name: String,
     pub process_id: String,
+    pub base_url: Option<String>,
     pub state: ProcessState,
     pub cron_initialized: bool,
     pub computed_slot: Option<u64>,
     pub current_slot: Option<u64>,
     pub last_checked: Option<DateTime<Utc>>,
+    /// Last check that saw the computed slot advance
+    #[serde(default)]
+    pub last_advanced_at: Option<DateTime<Utc>>,
     pub error: Option<String>,
+    /// Times this process has been marked errored, kept across restarts
+    #[serde(default)]
+    pub error_count: u64,
+    #[serde(default)]
+    pub last_error_at: Option<DateTime<Utc>>,
+    /// Cron initializations attempted since the last successful one
+    #[serde(default)]
+    pub init_attempts: u64,
     pub metrics: ProcessMetrics,
     pub queue_position: Option<usize>,
+    pub added_at: Option<DateTime<Utc>>,
     pub activated_at: Option<DateTime<Utc>>,
     pub synced_at: Option<DateTime<Utc>>,
+    /// Seconds spent active in completed activation periods
+    pub total_active_seconds: u64,
     pub hb_reserves: Option<HashMap<String, String>>,
     pub ao_reserves: Option<HashMap<String, String>>,
     pub reserves_last_checked: Option<DateTime<Utc>>,
     pub cron_created_at: Option<DateTime<Utc>>,
+    /// Paused processes are skipped by the monitor loops and never activated
+    #[serde(default)]
+    pub paused: bool,
+    /// Filled in on read, see `ProcessStatus::with_derived_fields`
+    #[serde(default)]
+    pub lifecycle: Option<LifecycleTimings>,
+    /// Per-token reserve comparison, only filled in for the process detail endpoint
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reserve_diffs: Option<Vec<ReserveDiff>>,
+    /// Most recent events for this process; kept in memory only and left out of list views
+    #[serde(default, skip_serializing_if = "VecDeque::is_empty")]
+    pub activity_log: VecDeque<LogEntry>,
+}
+
+/// Number of entries kept in each process's activity log
+pub const ACTIVITY_LOG_CAPACITY: usize = 50;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogLevel {
+    Info,
+    Warn,
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogEntry {
+    pub timestamp: DateTime<Utc>,
+    pub level: LogLevel,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReserveDiff {
+    pub token: String,
+    pub hb_amount: Option<String>,
+    pub ao_amount: Option<String>,
+    pub matches: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LifecycleTimings {
+    pub queued_secs: Option<u64>,
+    pub active_secs: Option<u64>,
+    pub synced_secs: Option<u64>,
 }
 
 impl ProcessStatus {
-    pub fn new(name: String, process_id: String) -> Self {
+    pub fn new(name: String, process_id: String, base_url: Option<String>) -> Self {
         Self {
             name,
             process_id,
+            base_url,
             state: ProcessState::Queued,
             cron_initialized: false,
             computed_slot: None,
             current_slot: None,
             last_checked: None,
+            last_advanced_at: None,
             error: None,
+            error_count: 0,
+            last_error_at: None,
+            init_attempts: 0,
             metrics: ProcessMetrics::default(),
             queue_position: None,
+            added_at: Some(Utc::now()),
             activated_at: None,
             synced_at: None,
+            total_active_seconds: 0,
             hb_reserves: None,
             ao_reserves: None,
             reserves_last_checked: None,
             cron_created_at: None,
+            paused: false,
+            lifecycle: None,
+            reserve_diffs: None,
+            activity_log: VecDeque::new(),
+        }
+    }
+
+    /// Append to the activity log, dropping the oldest entry once it is full.
+    pub fn log_activity(&mut self, level: LogLevel, message: impl Into<String>) {
+        if self.activity_log.len() >= ACTIVITY_LOG_CAPACITY {
+            self.activity_log.pop_front();
         }
+        self.activity_log.push_back(LogEntry {
+            timestamp: Utc::now(),
+            level,
+            message: message.into(),
+        });
+    }
+
+    /// Time spent in each phase, using the elapsed time so far for the current phase.
+    pub fn lifecycle_timings(&self) -> LifecycleTimings {
+        let now = Utc::now();
+        let secs = |from: DateTime<Utc>, to: Option<DateTime<Utc>>| {
+            (to.unwrap_or(now) - from).num_seconds().max(0) as u64
+        };
+        
+        LifecycleTimings {
+            queued_secs: self.added_at.map(|added| secs(added, self.activated_at)),
+            active_secs: self.activated_at.map(|activated| secs(activated, self.synced_at)),
+            synced_secs: self.synced_at.map(|synced| secs(synced, None)),
+        }
+    }
+
+    /// Fill in fields derived from the stored data for API responses.
+    pub fn with_derived_fields(mut self) -> Self {
+        self.lifecycle = Some(self.lifecycle_timings());
+        self.metrics.p50_response_time = self.metrics.p50_response_time();
+        self.metrics.p95_response_time = self.metrics.p95_response_time();
+        self.metrics.time_to_sync_seconds = self.metrics.time_to_sync().map(|d| d.num_seconds());
+        self
+    }
+
+    /// Drop the detail served only by the single-process endpoints, to keep list
+    /// responses small.
+    pub fn for_list(mut self) -> Self {
+        self.metrics.slot_history = Vec::new();
+        self.activity_log = VecDeque::new();
+        self
     }
 
     pub fn deficit(&self) -> Option<u64> {
@@ -96,23 +344,119 @@ impl ProcessStatus {
         }
     }
 
-    pub fn is_synced(&self) -> bool {
+    /// How much of the initial deficit has been caught up, from 0 to 100.
+    pub fn sync_progress_percent(&self) -> Option<f64> {
+        let initial = self.metrics.initial_slot_deficit?;
+        self.current_slot.zip(self.computed_slot)?;
+        if initial == 0 {
+            return Some(100.0);
+        }
+        
+        let remaining = self.deficit().unwrap_or(0) as f64;
+        Some(((1.0 - remaining / initial as f64) * 100.0).clamp(0.0, 100.0))
+    }
+
+    /// Total seconds spent active, including the current activation period.
+    pub fn active_seconds(&self) -> u64 {
+        match (&self.state, self.activated_at) {
+            (ProcessState::Active, Some(activated)) => {
+                self.total_active_seconds + (Utc::now() - activated).num_seconds().max(0) as u64
+            }
+            _ => self.total_active_seconds,
+        }
+    }
+
+    /// Fold the current activation period into `total_active_seconds`. Call before
+    /// moving a process out of the active state.
+    pub fn close_active_period(&mut self) {
+        if self.state == ProcessState::Active {
+            self.total_active_seconds = self.active_seconds();
+        }
+    }
+
+    /// Estimated minutes until synced, based on the moving average sync rate (slots/min).
+    /// Returns `None` when there is no deficit, no rate yet, or the estimate exceeds a year.
+    pub fn estimated_minutes_to_sync(&self) -> Option<f64> {
+        const MAX_ETA_MINUTES: f64 = 365.0 * 24.0 * 60.0;
+        
+        let deficit = self.deficit()?;
+        let rate = self.metrics.effective_sync_rate();
+        if rate <= 0.0 {
+            return None;
+        }
+        
+        let minutes = deficit as f64 / rate;
+        (minutes.is_finite() && minutes <= MAX_ETA_MINUTES).then_some(minutes)
+    }
+
+    pub fn eta_seconds(&self) -> Option<u64> {
+        self.estimated_minutes_to_sync().map(|m| (m * 60.0) as u64)
+    }
+
+    /// Whether new slot readings advance no faster than `max_slots_per_minute`
+    /// relative to the wall-clock time since the last check.
+    pub fn is_plausible_reading(&self, computed: u64, current: u64, max_slots_per_minute: f64) -> bool {
+        let Some(last_checked) = self.last_checked else {
+            return true;
+        };
+        let minutes = ((Utc::now() - last_checked).num_seconds().max(1) as f64) / 60.0;
+        
+        [(self.computed_slot, computed), (self.current_slot, current)]
+            .iter()
+            .all(|(prev, new)| match prev {
+                Some(prev) if new > prev => (new - prev) as f64 / minutes <= max_slots_per_minute,
+                _ => true,
+            })
+    }
+
+    /// Synced when the computed slot is at most `tolerance` slots behind the current slot.
+    pub fn is_synced(&self, tolerance: u64) -> bool {
         match (self.current_slot, self.computed_slot) {
-            (Some(current), Some(computed)) => current == computed,
+            (Some(current), Some(computed)) => computed <= current && current - computed <= tolerance,
             _ => false,
         }
     }
     
-    pub fn reserves_match(&self) -> Option<bool> {
+    /// Compare HB and AO reserves token by token, covering tokens present on either side.
+    /// Empty until both reserve sets have been fetched.
+    pub fn reserve_diffs(&self, filter: &ReserveFilter) -> Vec<ReserveDiff> {
+        let (Some(hb), Some(ao)) = (&self.hb_reserves, &self.ao_reserves) else {
+            return Vec::new();
+        };
+        
+        let mut tokens: Vec<&String> = hb.keys()
+            .chain(ao.keys())
+            .filter(|key| filter.is_token_key(key))
+            .collect();
+        tokens.sort();
+        tokens.dedup();
+        
+        tokens.into_iter().map(|token| {
+            let hb_amount = hb.get(token).cloned();
+            let ao_amount = ao.get(token).cloned();
+            let matches = match (&hb_amount, &ao_amount) {
+                (Some(hb), Some(ao)) => reserve_amounts_equal(hb, ao),
+                _ => false,
+            };
+            ReserveDiff {
+                token: token.clone(),
+                hb_amount,
+                ao_amount,
+                matches,
+            }
+        }).collect()
+    }
+
+    pub fn reserves_match(&self, filter: &ReserveFilter) -> Option<bool> {
         match (&self.hb_reserves, &self.ao_reserves) {
             (Some(hb), Some(ao)) => {
-                // Only compare actual token process IDs (43 chars), ignore TokenA/TokenB/K
+                // Only compare actual token process IDs
                 let hb_tokens: HashMap<&String, &String> = hb.iter()
-                    .filter(|(key, _)| key.len() == 43 && !["TokenA", "TokenB", "K"].contains(&key.as_str()))
+                    .filter(|(key, _)| filter.is_token_key(key))
                     .collect();
                 
                 let ao_tokens: HashMap<&String, &String> = ao.iter()
-                    .filter(|(key, _)| key.len() == 43)
+                    .filter(|(key, _)| filter.is_token_key(key))
                     .collect();
                 
                 // Check if we have the same tokens
@@ -124,8 +468,8 @@ impl ProcessStatus {
                 for (token_id, hb_amount) in hb_tokens.iter() {
                     match ao_tokens.get(token_id) {
                         Some(ao_amount) => {
-                            // Compare the raw reserve amounts
-                            if hb_amount != ao_amount {
+                            // Compare numerically so "1000" and "1000.0" match
+                            if !reserve_amounts_equal(hb_amount, ao_amount) {
                                 return Some(false);
                             }
                         },
@@ -143,6 +487,71 @@ impl ProcessStatus {
     }
 }
 
+/// Normalize a reserve amount to `(integer, fraction)` digit strings with leading
+/// and trailing zeros stripped, or `None` if it is not a plain decimal number.
+fn normalize_amount(amount: &str) -> Option<(&str, &str)> {
+    let amount = amount.trim().trim_matches('"').trim();
+    let (int, frac) = amount.split_once('.').unwrap_or((amount, ""));
+    if int.is_empty() && frac.is_empty() {
+        return None;
+    }
+    if !int.bytes().chain(frac.bytes()).all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    Some((int.trim_start_matches('0'), frac.trim_end_matches('0')))
+}
+
+fn reserve_amounts_equal(a: &str, b: &str) -> bool {
+    match (normalize_amount(a), normalize_amount(b)) {
+        (Some(a), Some(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// Payload posted to `notifications.webhook_url` when a process finishes syncing
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncNotification {
+    pub process_id: String,
+    pub name: String,
+    pub time_to_sync_seconds: Option<i64>,
+    pub synced_at: Option<DateTime<Utc>>,
+}
+
+/// Payload posted to `notifications.webhook_url` when a process is flagged as stalled
+#[derive(Debug, Clone, Serialize)]
+pub struct StallNotification {
+    pub event: &'static str,
+    pub process_id: String,
+    pub name: String,
+    pub computed_slot: Option<u64>,
+    pub current_slot: Option<u64>,
+    pub checks_without_progress: u64,
+}
+
+impl From<&ProcessStatus> for StallNotification {
+    fn from(process: &ProcessStatus) -> Self {
+        Self {
+            event: "stalled",
+            process_id: process.process_id.clone(),
+            name: process.name.clone(),
+            computed_slot: process.computed_slot,
+            current_slot: process.current_slot,
+            checks_without_progress: process.metrics.checks_without_progress,
+        }
+    }
+}
+
+impl From<&ProcessStatus> for SyncNotification {
+    fn from(process: &ProcessStatus) -> Self {
+        Self {
+            process_id: process.process_id.clone(),
+            name: process.name.clone(),
+            time_to_sync_seconds: process.metrics.time_to_sync().map(|d| d.num_seconds()),
+            synced_at: process.synced_at,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StateFile {
     pub version: String,
@@ -150,18 +559,46 @@ pub struct StateFile {
     pub queued_process_ids: Vec<String>,
     pub active_process_ids: Vec<String>,
     pub synced_process_ids: Vec<String>,
+    #[serde(default)]
+    pub total_runtime_seconds: u64,
     pub processes: HashMap<String, ProcessStatusData>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProcessStatusData {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub base_url: Option<String>,
     pub state: ProcessState,
     pub cron_initialized: bool,
     pub computed_slot: Option<u64>,
     pub current_slot: Option<u64>,
     pub last_checked: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub last_advanced_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub error: Option<String>,
+    #[serde(default)]
+    pub error_count: u64,
+    #[serde(default)]
+    pub last_error_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub init_attempts: u64,
     pub synced_at: Option<DateTime<Utc>>,
     pub activated_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub added_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub total_active_seconds: u64,
+    #[serde(default)]
+    pub hb_reserves: Option<HashMap<String, String>>,
+    #[serde(default)]
+    pub ao_reserves: Option<HashMap<String, String>>,
+    #[serde(default)]
+    pub reserves_last_checked: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub paused: bool,
     pub metrics: ProcessMetricsData,
 }
 
@@ -173,6 +610,9 @@ pub struct ProcessMetricsData {
     pub sync_end_time: Option<DateTime<Utc>>,
     pub avg_sync_rate: f64,
     pub check_count: u64,
+    /// Only written when `state.persist_response_times` is enabled
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub api_response_times: Vec<f64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -187,13 +627,201 @@ pub struct ApiStatus {
     pub active_count: usize,
     pub queued_count: usize,
     pub synced_count: usize,
+    pub max_active_processes: usize,
     pub total_count: usize,
+    pub start_time: DateTime<Utc>,
+    pub server_time: DateTime<Utc>,
     pub runtime_seconds: u64,
+    pub total_runtime_seconds: u64,
+    /// Sum of the average sync rates of all active processes, in slots per minute
+    pub aggregate_sync_rate: f64,
+    /// Synced processes whose computed slot has fallen behind again
+    pub desynced_count: usize,
+    /// Requests sent to HyperBEAM and the AO CU since startup
+    pub total_upstream_requests: u64,
     pub active_processes: Vec<ProcessStatus>,
     pub queue_preview: Vec<ProcessStatus>,
     pub recent_synced: Vec<ProcessStatus>,
 }
 
+/// Service-wide counts sampled periodically for `/api/trends`
+#[derive(Debug, Clone, Serialize)]
+pub struct TrendSample {
+    pub timestamp: DateTime<Utc>,
+    pub active: usize,
+    pub queued: usize,
+    pub synced: usize,
+    /// Sum of the average sync rates of all active processes, in slots per minute
+    pub aggregate_rate: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Laggard {
+    pub name: String,
+    pub process_id: String,
+    pub deficit: Option<u64>,
+    pub avg_sync_rate: f64,
+    pub eta_seconds: Option<u64>,
+    pub checks_without_progress: u64,
+}
+
+impl From<&ProcessStatus> for Laggard {
+    fn from(status: &ProcessStatus) -> Self {
+        Self {
+            name: status.name.clone(),
+            process_id: status.process_id.clone(),
+            deficit: status.deficit(),
+            avg_sync_rate: status.metrics.avg_sync_rate,
+            eta_seconds: status.eta_seconds(),
+            checks_without_progress: status.metrics.checks_without_progress,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LaggardsQuery {
+    pub limit: Option<usize>,
+    #[serde(default)]
+    pub include_synced: bool,
+    /// Also list processes flagged as stalled, which are otherwise left out
+    #[serde(default)]
+    pub include_stalled: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HealthStatus {
+    pub status: &'static str,
+    pub uptime_seconds: u64,
+    pub hyperbeam_reachable: bool,
+    /// False when the last AO dry-run failed; reserves are not compared until it recovers
+    pub ao_cu_reachable: bool,
+    pub ready: bool,
+}
+
+/// Optional filters for `/api/status`; with none set every list is returned as is.
+#[derive(Debug, Default, Deserialize)]
+pub struct StatusQuery {
+    /// Only return the list for processes in this state
+    pub state: Option<ProcessState>,
+    /// Case-insensitive substring of the name or process ID
+    pub search: Option<String>,
+    pub min_deficit: Option<u64>,
+}
+
+impl StatusQuery {
+    pub fn matches(&self, process: &ProcessStatus) -> bool {
+        let search_matches = self.search.as_ref().is_none_or(|search| {
+            let search = search.to_lowercase();
+            process.name.to_lowercase().contains(&search) || process.process_id.to_lowercase().contains(&search)
+        });
+        let deficit_matches = self.min_deficit
+            .is_none_or(|min| process.deficit().unwrap_or(0) >= min);
+        search_matches && deficit_matches
+    }
+    
+    /// Apply the filters to the process lists of a status, leaving the counts untouched.
+    pub fn apply(&self, status: &mut ApiStatus) {
+        let lists = [
+            (ProcessState::Active, &mut status.active_processes),
+            (ProcessState::Queued, &mut status.queue_preview),
+            (ProcessState::Synced, &mut status.recent_synced),
+        ];
+        for (state, list) in lists {
+            if self.state.as_ref().is_some_and(|wanted| *wanted != state) {
+                list.clear();
+            } else {
+                list.retain(|p| self.matches(p));
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortKey {
+    #[default]
+    Deficit,
+    Rate,
+    ProcessId,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+/// `?sort=deficit|rate|process_id&order=asc|desc`. Defaults to the largest deficit first.
+#[derive(Debug, Default, Deserialize)]
+pub struct SortQuery {
+    #[serde(default)]
+    pub sort: SortKey,
+    /// Defaults to descending for numeric keys and ascending for `process_id`
+    pub order: Option<SortOrder>,
+}
+
+impl SortQuery {
+    /// Sort deterministically, breaking ties by process ID.
+    pub fn sort(&self, processes: &mut [ProcessStatus]) {
+        let order = self.order.unwrap_or(match self.sort {
+            SortKey::ProcessId => SortOrder::Asc,
+            _ => SortOrder::Desc,
+        });
+        
+        processes.sort_by(|a, b| {
+            let ordering = match self.sort {
+                SortKey::Deficit => a.deficit().unwrap_or(0).cmp(&b.deficit().unwrap_or(0)),
+                SortKey::Rate => a.metrics.avg_sync_rate.total_cmp(&b.metrics.avg_sync_rate),
+                SortKey::ProcessId => std::cmp::Ordering::Equal,
+            };
+            let ordering = match order {
+                SortOrder::Asc => ordering,
+                SortOrder::Desc => ordering.reverse(),
+            };
+            let tie_break = match (self.sort, order) {
+                (SortKey::ProcessId, SortOrder::Desc) => b.process_id.cmp(&a.process_id),
+                _ => a.process_id.cmp(&b.process_id),
+            };
+            ordering.then(tie_break)
+        });
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct VersionInfo {
+    pub version: &'static str,
+    pub git_sha: Option<&'static str>,
+    pub state_version: &'static str,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HistoryQuery {
+    /// Number of most recent samples to return, capped at `MAX_SLOT_HISTORY`
+    pub points: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SyncedQuery {
+    /// RFC 3339 timestamp; only processes synced at or after it are returned
+    pub since: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct QueueQuery {
+    #[serde(default)]
+    pub offset: usize,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct QueuePage {
+    pub total: usize,
+    pub offset: usize,
+    pub limit: usize,
+    pub processes: Vec<ProcessStatus>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct AddProcessRequest {
     pub name: String,
@@ -201,11 +829,50 @@ pub struct AddProcessRequest {
     pub base_url: Option<String>,
 }
 
+/// Body of `POST /api/queue/add`: a single process, an array, or `{ "processes": [...] }`.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum AddProcessPayload {
+    Single(AddProcessRequest),
+    Batch(Vec<AddProcessRequest>),
+    Wrapped { processes: Vec<AddProcessRequest> },
+}
+
+#[derive(Debug, Serialize)]
+pub struct AddProcessResult {
+    pub process_id: String,
+    pub success: bool,
+    pub error: Option<String>,
+    pub error_code: Option<ErrorCode>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SetPriorityRequest {
+    pub position: usize,
+}
+
 #[derive(Debug, Serialize)]
 pub struct ApiResponse<T> {
     pub success: bool,
     pub data: Option<T>,
+    /// Human-readable message for display
     pub error: Option<String>,
+    /// Machine-readable category of `error`
+    pub error_code: Option<ErrorCode>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    NotFound,
+    Duplicate,
+    QueueFull,
+    NotAllowed,
+    /// The process is not in a state the operation applies to
+    InvalidState,
+    /// HyperBEAM or the AO CU failed or could not be reached
+    Upstream,
+    InvalidConfig,
 }
 
 #[derive(Debug, Serialize)]
@@ -235,6 +902,7 @@ pub struct AODryRunResponse {
     #[serde(rename = "Messages")]
     pub messages: Option<Vec<AOMessage>>,
     #[serde(rename = "GasUsed")]
+    #[allow(dead_code)]
     pub gas_used: Option<u64>,
 }
 
@@ -251,4 +919,297 @@ impl AOTag {
             value: value.to_string(),
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn instant_rate_follows_a_step_change_faster_than_the_average() {
+        let mut metrics = ProcessMetrics::default();
+        metrics.update_instant_rate(10, 1.0, 0.5);
+        assert_eq!(metrics.instant_sync_rate, Some(10.0));
+
+        // The rate jumps from 10 to 100 slots/min; each check halves the remaining gap
+        metrics.update_instant_rate(100, 1.0, 0.5);
+        assert_eq!(metrics.instant_sync_rate, Some(55.0));
+        metrics.update_instant_rate(100, 1.0, 0.5);
+        assert_eq!(metrics.instant_sync_rate, Some(77.5));
+
+        // A zero-length interval is ignored rather than dividing by zero
+        metrics.update_instant_rate(100, 0.0, 0.5);
+        assert_eq!(metrics.instant_sync_rate, Some(77.5));
+    }
+
+    #[test]
+    fn stall_detection_fires_once_at_the_threshold() {
+        let mut metrics = ProcessMetrics::default();
+        let fired: Vec<bool> = (0..4).map(|_| metrics.record_progress(100, 3)).collect();
+        assert_eq!(fired, [false, false, true, false]);
+        assert_eq!(metrics.checks_without_progress, 4);
+        assert!(metrics.is_stalled(3));
+
+        // Progress resets the counter
+        metrics.slots_advanced_last_check = 5;
+        assert!(!metrics.record_progress(95, 3));
+        assert_eq!(metrics.checks_without_progress, 0);
+        assert!(!metrics.is_stalled(3));
+
+        // No deficit is not a stall, and a threshold of 0 never fires
+        metrics.slots_advanced_last_check = 0;
+        assert!(!metrics.record_progress(0, 1));
+        assert_eq!(metrics.checks_without_progress, 0);
+        assert!(!(0..5).any(|_| metrics.record_progress(100, 0)));
+        assert!(!metrics.is_stalled(0));
+    }
+
+    #[test]
+    fn response_times_are_trimmed_to_the_window() {
+        let mut metrics = ProcessMetrics::default();
+        for millis in 1..=5 {
+            metrics.record_response_time(millis as f64, 3);
+        }
+        assert_eq!(metrics.api_response_times, [3.0, 4.0, 5.0]);
+
+        // Shrinking the window drops the oldest samples on the next record
+        metrics.record_response_time(6.0, 2);
+        assert_eq!(metrics.api_response_times, [5.0, 6.0]);
+    }
+
+    #[test]
+    fn effective_rate_prefers_the_moving_average() {
+        let mut metrics = ProcessMetrics {
+            avg_sync_rate: 20.0,
+            ..ProcessMetrics::default()
+        };
+        assert_eq!(metrics.effective_sync_rate(), 20.0);
+
+        metrics.instant_sync_rate = Some(0.0);
+        assert_eq!(metrics.effective_sync_rate(), 20.0);
+
+        metrics.instant_sync_rate = Some(35.0);
+        assert_eq!(metrics.effective_sync_rate(), 35.0);
+    }
+
+    fn status_with_slots(computed: u64, current: u64) -> ProcessStatus {
+        let mut status = ProcessStatus::new("pool".to_string(), "pid".to_string(), None);
+        status.computed_slot = Some(computed);
+        status.current_slot = Some(current);
+        status
+    }
+
+    #[test]
+    fn sync_progress_is_the_share_of_the_initial_deficit_caught_up() {
+        let progress = |initial: Option<u64>, computed: u64, current: u64| {
+            let mut status = status_with_slots(computed, current);
+            status.metrics.initial_slot_deficit = initial;
+            status.sync_progress_percent()
+        };
+
+        assert_eq!(progress(Some(1000), 0, 1000), Some(0.0));
+        assert_eq!(progress(Some(1000), 870, 1000), Some(87.0));
+        assert_eq!(progress(Some(1000), 1000, 1000), Some(100.0));
+        // The deficit grew beyond where it started
+        assert_eq!(progress(Some(1000), 0, 1500), Some(0.0));
+        // Computed ahead of current has no deficit left
+        assert_eq!(progress(Some(1000), 1200, 1000), Some(100.0));
+        assert_eq!(progress(Some(0), 100, 100), Some(100.0));
+        assert_eq!(progress(None, 0, 1000), None);
+
+        let mut unread = ProcessStatus::new("pool".to_string(), "pid".to_string(), None);
+        unread.metrics.initial_slot_deficit = Some(1000);
+        assert_eq!(unread.sync_progress_percent(), None);
+    }
+
+    #[test]
+    fn is_synced_honours_the_tolerance_boundary() {
+        assert!(status_with_slots(100, 100).is_synced(0));
+        assert!(!status_with_slots(99, 100).is_synced(0));
+        assert!(status_with_slots(95, 100).is_synced(5));
+        assert!(!status_with_slots(94, 100).is_synced(5));
+        // Computed ahead of current is not synced, whatever the tolerance
+        assert!(!status_with_slots(101, 100).is_synced(5));
+        assert!(!ProcessStatus::new("pool".to_string(), "pid".to_string(), None).is_synced(5));
+    }
+
+    #[test]
+    fn estimated_minutes_to_sync_covers_deficit_and_rate_combinations() {
+        let eta = |computed, current, rate| {
+            let mut status = status_with_slots(computed, current);
+            status.metrics.avg_sync_rate = rate;
+            status.estimated_minutes_to_sync()
+        };
+
+        assert_eq!(eta(900, 1000, 10.0), Some(10.0));
+        assert_eq!(eta(0, 40_000, 500.0), Some(80.0));
+        // No deficit, no rate yet, or a rate too slow to finish within a year
+        assert_eq!(eta(1000, 1000, 10.0), None);
+        assert_eq!(eta(900, 1000, 0.0), None);
+        assert_eq!(eta(0, u64::MAX, 0.001), None);
+        // No slot readings
+        let mut unread = ProcessStatus::new("pool".to_string(), "pid".to_string(), None);
+        unread.metrics.avg_sync_rate = 10.0;
+        assert_eq!(unread.estimated_minutes_to_sync(), None);
+    }
+
+    #[test]
+    fn response_time_percentiles_use_the_nearest_rank() {
+        let percentiles = |times: &[f64]| {
+            let metrics = ProcessMetrics { api_response_times: times.to_vec(), ..Default::default() };
+            (metrics.p50_response_time(), metrics.p95_response_time())
+        };
+
+        assert_eq!(percentiles(&[]), (None, None));
+        assert_eq!(percentiles(&[42.0]), (Some(42.0), Some(42.0)));
+        assert_eq!(percentiles(&[30.0, 10.0]), (Some(10.0), Some(30.0)));
+        // 1..=20 in reverse: the 10th and 19th smallest
+        let times: Vec<f64> = (1..=20).rev().map(f64::from).collect();
+        assert_eq!(percentiles(&times), (Some(10.0), Some(19.0)));
+        // One slow request in 20 stays above p95, a second one reaches it
+        let mut times = vec![100.0; 19];
+        times.push(5000.0);
+        assert_eq!(percentiles(&times), (Some(100.0), Some(100.0)));
+        times.push(5000.0);
+        assert_eq!(percentiles(&times), (Some(100.0), Some(5000.0)));
+    }
+
+    fn token(c: char) -> String {
+        c.to_string().repeat(43)
+    }
+
+    fn with_reserves(hb: Option<&[(&str, &str)]>, ao: Option<&[(&str, &str)]>) -> ProcessStatus {
+        let map = |reserves: &[(&str, &str)]| reserves.iter()
+            .map(|(key, amount)| (key.to_string(), amount.to_string()))
+            .collect();
+        let mut status = ProcessStatus::new("pool".to_string(), "pid".to_string(), None);
+        status.hb_reserves = hb.map(map);
+        status.ao_reserves = ao.map(map);
+        status
+    }
+
+    fn amounts_match(hb: &str, ao: &str) -> Option<bool> {
+        let a = token('a');
+        with_reserves(Some(&[(&a, hb)]), Some(&[(&a, ao)])).reserves_match(&ReserveFilter::default())
+    }
+
+    #[test]
+    fn reserve_amounts_match_regardless_of_formatting() {
+        for (hb, ao) in [
+            ("1000", "1000"),
+            ("1000", "1000.0"),
+            ("1000.500", "1000.5"),
+            ("0001000", "1000"),
+            (" 1000 ", "1000"),
+            ("\"1000\"", "1000"),
+            ("0", "0.000"),
+            ("340282366920938463463374607431768211456", "340282366920938463463374607431768211456.0"),
+        ] {
+            assert_eq!(amounts_match(hb, ao), Some(true), "{:?} vs {:?}", hb, ao);
+        }
+
+        for (hb, ao) in [("1000", "1000.5"), ("1000", "100"), ("1000", "10000"), ("abc", "1000")] {
+            assert_eq!(amounts_match(hb, ao), Some(false), "{:?} vs {:?}", hb, ao);
+        }
+    }
+
+    #[test]
+    fn slot_history_caps_and_keeps_the_newest_samples_in_order() {
+        let mut metrics = ProcessMetrics::default();
+        for slot in 0..(MAX_SLOT_HISTORY as u64 + 25) {
+            metrics.record_slot_sample(slot, slot + 10);
+        }
+
+        assert_eq!(metrics.slot_history.len(), MAX_SLOT_HISTORY);
+        let computed: Vec<u64> = metrics.slot_history.iter().map(|s| s.computed_slot).collect();
+        let expected: Vec<u64> = (25..MAX_SLOT_HISTORY as u64 + 25).collect();
+        assert_eq!(computed, expected);
+        assert!(metrics.slot_history.windows(2).all(|w| w[0].timestamp <= w[1].timestamp));
+
+        let recent: Vec<u64> = metrics.recent_slot_samples(3).iter().map(|s| s.computed_slot).collect();
+        assert_eq!(recent, [122, 123, 124]);
+        assert_eq!(metrics.recent_slot_samples(1000).len(), MAX_SLOT_HISTORY);
+    }
+
+    #[test]
+    fn list_view_leaves_out_slot_history_and_activity() {
+        let mut status = ProcessStatus::new("pool".to_string(), "pid".to_string(), None);
+        status.metrics.record_slot_sample(1, 2);
+        status.log_activity(LogLevel::Info, "Activated");
+
+        let detail = serde_json::to_value(&status).unwrap();
+        assert_eq!(detail["metrics"]["slot_history"].as_array().unwrap().len(), 1);
+        assert_eq!(detail["activity_log"][0]["message"], "Activated");
+
+        let list = serde_json::to_value(status.for_list()).unwrap();
+        assert!(list["metrics"].get("slot_history").is_none());
+        assert!(list.get("activity_log").is_none());
+    }
+
+    #[test]
+    fn activity_log_caps_at_capacity_dropping_the_oldest() {
+        let mut status = ProcessStatus::new("pool".to_string(), "pid".to_string(), None);
+        for i in 0..ACTIVITY_LOG_CAPACITY + 5 {
+            status.log_activity(LogLevel::Info, format!("event {}", i));
+        }
+
+        assert_eq!(status.activity_log.len(), ACTIVITY_LOG_CAPACITY);
+        assert_eq!(status.activity_log.front().unwrap().message, "event 5");
+        assert_eq!(status.activity_log.back().unwrap().message, format!("event {}", ACTIVITY_LOG_CAPACITY + 4));
+    }
+
+    #[test]
+    fn reserve_diffs_cover_tokens_on_either_side() {
+        let (a, b, c, d) = (token('a'), token('b'), token('c'), token('d'));
+        let status = with_reserves(
+            Some(&[(&a, "100"), (&b, "200"), (&c, "300"), ("K", "1")]),
+            Some(&[(&a, "100.0"), (&b, "250"), (&d, "400"), ("K", "2")]),
+        );
+
+        let diffs: Vec<_> = status.reserve_diffs(&ReserveFilter::default()).into_iter()
+            .map(|diff| (diff.token, diff.hb_amount, diff.ao_amount, diff.matches))
+            .collect();
+        let some = |amount: &str| Some(amount.to_string());
+        assert_eq!(diffs, [
+            (a, some("100"), some("100.0"), true),
+            (b, some("200"), some("250"), false),
+            (c, some("300"), None, false),
+            (d, None, some("400"), false),
+        ]);
+    }
+
+    #[test]
+    fn reserve_diffs_need_both_sides() {
+        let a = token('a');
+        let filter = ReserveFilter::default();
+        assert!(with_reserves(Some(&[(&a, "100")]), None).reserve_diffs(&filter).is_empty());
+        assert!(with_reserves(None, Some(&[(&a, "100")])).reserve_diffs(&filter).is_empty());
+        assert_eq!(with_reserves(Some(&[(&a, "100")]), None).reserves_match(&filter), None);
+    }
+
+    fn sorted(query: SortQuery, processes: &[(&str, u64, f64)]) -> Vec<String> {
+        let mut processes: Vec<_> = processes.iter().map(|(id, deficit, rate)| {
+            let mut status = status_with_slots(1000 - deficit, 1000);
+            status.process_id = id.to_string();
+            status.metrics.avg_sync_rate = *rate;
+            status
+        }).collect();
+        query.sort(&mut processes);
+        processes.into_iter().map(|p| p.process_id).collect()
+    }
+
+    #[test]
+    fn sort_query_orders_by_key_with_sensible_defaults() {
+        let processes = [("b", 10, 3.0), ("a", 50, 1.0), ("c", 10, 2.0)];
+        let query = |sort, order| SortQuery { sort, order };
+
+        // Numeric keys default to descending, ties broken by process ID
+        assert_eq!(sorted(query(SortKey::Deficit, None), &processes), ["a", "b", "c"]);
+        assert_eq!(sorted(query(SortKey::Deficit, Some(SortOrder::Asc)), &processes), ["b", "c", "a"]);
+        assert_eq!(sorted(query(SortKey::Rate, None), &processes), ["b", "c", "a"]);
+        assert_eq!(sorted(query(SortKey::Rate, Some(SortOrder::Asc)), &processes), ["a", "c", "b"]);
+        // Process IDs default to ascending
+        assert_eq!(sorted(query(SortKey::ProcessId, None), &processes), ["a", "b", "c"]);
+        assert_eq!(sorted(query(SortKey::ProcessId, Some(SortOrder::Desc)), &processes), ["c", "b", "a"]);
+    }
+}
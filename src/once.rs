@@ -0,0 +1,189 @@
+use crate::config::ServiceConfig;
+use crate::hyperbeam::HyperBeamClient;
+use crate::models::{ProcessState, ProcessStatusData};
+use crate::state;
+use anyhow::Result;
+use serde::Serialize;
+
+/// One tracked process with the slots read during a `--once` run.
+#[derive(Debug, Serialize)]
+pub struct SlotReading {
+    pub process_id: String,
+    pub name: String,
+    pub state: ProcessState,
+    pub computed_slot: Option<u64>,
+    pub current_slot: Option<u64>,
+    pub deficit: Option<u64>,
+    pub synced: bool,
+    pub error: Option<String>,
+}
+
+async fn read_slots(client: &HyperBeamClient, process_id: String, process: ProcessStatusData, sync_tolerance: u64) -> SlotReading {
+    let result = client.check_slots(process.base_url.as_deref(), &process_id).await;
+    let (computed_slot, current_slot, deficit, synced, error) = match result {
+        Ok(result) => (
+            Some(result.computed_slot),
+            Some(result.current_slot),
+            Some(result.deficit()),
+            result.is_synced(sync_tolerance),
+            None,
+        ),
+        Err(e) => (None, None, None, false, Some(e.to_string())),
+    };
+
+    SlotReading {
+        // Older state files have no name
+        name: if process.name.is_empty() { process_id.clone() } else { process.name },
+        process_id,
+        state: process.state,
+        computed_slot,
+        current_slot,
+        deficit,
+        synced,
+        error,
+    }
+}
+
+fn print_table(readings: &[SlotReading]) {
+    fn slot(value: Option<u64>) -> String {
+        value.map(|v| v.to_string()).unwrap_or_else(|| "-".to_string())
+    }
+
+    println!("{:<45} {:<24} {:<8} {:>12} {:>12} {:>10}", "PROCESS ID", "NAME", "STATE", "COMPUTED", "CURRENT", "DEFICIT");
+    for reading in readings {
+        println!(
+            "{:<45} {:<24} {:<8} {:>12} {:>12} {:>10}",
+            reading.process_id,
+            reading.name,
+            format!("{:?}", reading.state),
+            slot(reading.computed_slot),
+            slot(reading.current_slot),
+            slot(reading.deficit),
+        );
+        if let Some(error) = &reading.error {
+            println!("    error: {}", error);
+        }
+    }
+
+    let synced = readings.iter().filter(|r| r.synced).count();
+    let failed = readings.iter().filter(|r| r.error.is_some()).count();
+    println!("{} processes, {} synced, {} behind, {} failed", readings.len(), synced, readings.len() - synced - failed, failed);
+}
+
+/// Read the current slots of every process in the saved state, sorted by name. The
+/// state is only peeked at, never migrated or moved aside.
+async fn collect_readings(service_config: &ServiceConfig) -> Result<Vec<SlotReading>> {
+    let state = state::peek_state(&service_config.state)?;
+    let mut processes: Vec<_> = state.map(|s| s.processes.into_iter().collect()).unwrap_or_default();
+    processes.sort_by(|(a_id, a), (b_id, b)| a.name.cmp(&b.name).then_with(|| a_id.cmp(b_id)));
+
+    let client = HyperBeamClient::new(
+        &service_config.hyperbeam,
+        service_config.ao.cu_url.clone(),
+        service_config.reserves.clone(),
+    );
+    let sync_tolerance = service_config.monitoring.sync_tolerance;
+    Ok(futures::future::join_all(
+        processes.into_iter().map(|(process_id, process)| read_slots(&client, process_id, process, sync_tolerance))
+    ).await)
+}
+
+/// Read the current slots of every process in the saved state once and print them,
+/// as a table or as JSON. Nothing is written. Returns the process exit code:
+/// 1 if the state could not be read or any slot check failed.
+pub async fn run(service_config: &ServiceConfig, json: bool) -> i32 {
+    let readings = match collect_readings(service_config).await {
+        Ok(readings) => readings,
+        Err(e) => {
+            eprintln!("Failed to read state: {}", e);
+            return 1;
+        }
+    };
+
+    if json {
+        match serde_json::to_string_pretty(&readings) {
+            Ok(output) => println!("{}", output),
+            Err(e) => {
+                eprintln!("Failed to serialize readings: {}", e);
+                return 1;
+            }
+        }
+    } else {
+        print_table(&readings);
+    }
+
+    if readings.iter().any(|r| r.error.is_some()) { 1 } else { 0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::QueueStrategy;
+    use crate::models::ProcessConfig;
+    use crate::queue::QueueManager;
+    use crate::state::{JsonStateStore, StateStore};
+    use crate::test_support::{config_for, serve, slot_node, MockSlots};
+
+    async fn write_state(config: &ServiceConfig, process_ids: &[&str]) {
+        let queue = QueueManager::new(10, 0, 0, Vec::new(), Vec::new(), QueueStrategy::Fifo);
+        for id in process_ids {
+            queue.add_to_queue(ProcessConfig {
+                name: format!("pool-{}", id),
+                process_id: id.to_string(),
+                base_url: None,
+            }).await.unwrap();
+        }
+        let state = state::build_state(&queue, &config.state).await;
+        JsonStateStore::from_config(&config.state).write(&state).unwrap();
+    }
+
+    fn config_with_state(base_url: &str, dir: &tempfile::TempDir) -> ServiceConfig {
+        let mut config = config_for(base_url);
+        config.state.path = dir.path().join("state.json").to_string_lossy().into_owned();
+        config
+    }
+
+    #[tokio::test]
+    async fn reads_slots_for_every_saved_process_without_writing() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = config_with_state(&serve(slot_node(MockSlots::new(90, 100))).await, &dir);
+        write_state(&config, &["b", "a"]).await;
+        let before = std::fs::read(&config.state.path).unwrap();
+
+        let readings = collect_readings(&config).await.unwrap();
+        let ids: Vec<_> = readings.iter().map(|r| r.process_id.as_str()).collect();
+        assert_eq!(ids, ["a", "b"]);
+        for reading in &readings {
+            assert_eq!((reading.computed_slot, reading.current_slot, reading.deficit), (Some(90), Some(100), Some(10)));
+            assert!(!reading.synced);
+            assert!(reading.error.is_none());
+        }
+
+        assert_eq!(std::fs::read(&config.state.path).unwrap(), before);
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+        assert_eq!(run(&config, true).await, 0);
+    }
+
+    #[tokio::test]
+    async fn failed_slot_checks_are_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = config_with_state(&serve(axum::Router::new()).await, &dir);
+        write_state(&config, &["a"]).await;
+
+        let readings = collect_readings(&config).await.unwrap();
+        assert!(readings[0].error.is_some());
+        assert_eq!(run(&config, false).await, 1);
+    }
+
+    #[tokio::test]
+    async fn a_corrupt_state_file_is_left_in_place() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = config_with_state("http://127.0.0.1:9", &dir);
+        std::fs::write(&config.state.path, "{ not json").unwrap();
+
+        assert!(collect_readings(&config).await.is_err());
+        assert_eq!(run(&config, false).await, 1);
+        assert_eq!(std::fs::read_to_string(&config.state.path).unwrap(), "{ not json");
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+}
@@ -1,41 +1,263 @@
-use crate::models::{ProcessConfig, ProcessState, ProcessStatus};
-use chrono::Utc;
-use std::collections::{HashMap, VecDeque};
+use crate::config::QueueStrategy;
+use crate::models::{ErrorCode, LogLevel, ProcessConfig, ProcessState, ProcessStatus};
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
 use tokio::sync::RwLock;
-use tracing::info;
+use tracing::{info, warn};
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UpsertOutcome {
+    Added,
+    Updated,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueueError {
+    NotFound(String),
+    AlreadyExists { process_id: String, state: Option<ProcessState> },
+    DuplicateName(String),
+    QueueFull(usize),
+    NotAllowed { process_id: String, reason: &'static str },
+    /// The process exists but is not in the state the operation needs
+    WrongState { process_id: String, expected: &'static str },
+    InitAttemptsExhausted { process_id: String, max: u64 },
+}
+
+impl QueueError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            QueueError::NotFound(_) => ErrorCode::NotFound,
+            QueueError::AlreadyExists { .. } | QueueError::DuplicateName(_) => ErrorCode::Duplicate,
+            QueueError::QueueFull(_) => ErrorCode::QueueFull,
+            QueueError::NotAllowed { .. } => ErrorCode::NotAllowed,
+            QueueError::WrongState { .. } | QueueError::InitAttemptsExhausted { .. } => ErrorCode::InvalidState,
+        }
+    }
+}
+
+impl std::fmt::Display for QueueError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QueueError::NotFound(process_id) => write!(f, "Process {} not found", process_id),
+            QueueError::AlreadyExists { process_id, state: None } => write!(f, "Process {} already exists", process_id),
+            QueueError::AlreadyExists { process_id, state: Some(state) } => {
+                write!(f, "Process {} already exists in state {:?}", process_id, state)
+            }
+            QueueError::DuplicateName(name) => write!(f, "A process named {} already exists", name),
+            QueueError::QueueFull(max) => write!(f, "Queue full (max {} processes)", max),
+            QueueError::NotAllowed { process_id, reason } => write!(f, "Process {} {}", process_id, reason),
+            QueueError::WrongState { process_id, expected } => write!(f, "Process {} is not {}", process_id, expected),
+            QueueError::InitAttemptsExhausted { process_id, max } => {
+                write!(f, "Process {} exceeded {} init attempts; remove and re-add it to retry", process_id, max)
+            }
+        }
+    }
+}
+
+impl std::error::Error for QueueError {}
+
+/// Methods that hold more than one of the maps at once take their locks in the order
+/// `active`, `synced`, `queued`, `all_processes`, skipping any they do not need; code
+/// outside this module that locks the maps directly must do the same.
 pub struct QueueManager {
     pub active: Arc<RwLock<HashMap<String, ProcessStatus>>>,
     pub queued: Arc<RwLock<VecDeque<ProcessConfig>>>,
     pub synced: Arc<RwLock<HashMap<String, ProcessStatus>>>,
     pub all_processes: Arc<RwLock<HashMap<String, ProcessStatus>>>,
-    max_active_processes: usize,
+    max_active_processes: AtomicUsize,
+    max_queue_size: AtomicUsize,
+    max_init_attempts: AtomicU64,
+    process_allowlist: HashSet<String>,
+    process_denylist: HashSet<String>,
+    queue_strategy: std::sync::RwLock<QueueStrategy>,
+    started_at: DateTime<Utc>,
+    previous_runtime_seconds: AtomicU64,
+    /// Bumped on every change to process state; compared with `saved_revision` to skip
+    /// rewriting state that has not changed
+    revision: AtomicU64,
+    saved_revision: AtomicU64,
 }
 
 impl QueueManager {
-    pub fn new(max_active_processes: usize) -> Self {
+    pub fn new(
+        max_active_processes: usize,
+        max_queue_size: usize,
+        max_init_attempts: u64,
+        process_allowlist: Vec<String>,
+        process_denylist: Vec<String>,
+        queue_strategy: QueueStrategy,
+    ) -> Self {
         Self {
             active: Arc::new(RwLock::new(HashMap::new())),
             queued: Arc::new(RwLock::new(VecDeque::new())),
             synced: Arc::new(RwLock::new(HashMap::new())),
             all_processes: Arc::new(RwLock::new(HashMap::new())),
-            max_active_processes,
+            max_active_processes: AtomicUsize::new(max_active_processes),
+            max_queue_size: AtomicUsize::new(max_queue_size),
+            max_init_attempts: AtomicU64::new(max_init_attempts),
+            process_allowlist: process_allowlist.into_iter().collect(),
+            process_denylist: process_denylist.into_iter().collect(),
+            queue_strategy: std::sync::RwLock::new(queue_strategy),
+            started_at: Utc::now(),
+            previous_runtime_seconds: AtomicU64::new(0),
+            revision: AtomicU64::new(0),
+            saved_revision: AtomicU64::new(0),
+        }
+    }
+
+    pub fn max_active_processes(&self) -> usize {
+        self.max_active_processes.load(Ordering::Relaxed)
+    }
+
+    /// 0 means initialization is retried indefinitely.
+    pub fn set_max_init_attempts(&self, max_init_attempts: u64) {
+        self.max_init_attempts.store(max_init_attempts, Ordering::Relaxed);
+    }
+
+    fn init_attempts_exhausted(&self, status: &ProcessStatus) -> bool {
+        let max = self.max_init_attempts.load(Ordering::Relaxed);
+        max > 0 && status.init_attempts >= max
+    }
+
+    fn exhausted_error(&self, process_id: &str) -> QueueError {
+        QueueError::InitAttemptsExhausted {
+            process_id: process_id.to_string(),
+            max: self.max_init_attempts.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Count an initialization attempt, returning the number made since the last success.
+    pub async fn record_init_attempt(&self, process_id: &str) -> Result<u64, QueueError> {
+        let mut attempts = 0;
+        self.update_process_status(process_id, |status| {
+            status.init_attempts += 1;
+            attempts = status.init_attempts;
+        }).await?;
+        Ok(attempts)
+    }
+
+    /// Mark a failed initialization as an error, with a terminal message once the
+    /// process has used up its attempts.
+    pub async fn mark_init_failed(&self, process_id: &str, error: String) -> Result<(), QueueError> {
+        let exhausted = self.all_processes.read().await
+            .get(process_id)
+            .is_some_and(|status| self.init_attempts_exhausted(status));
+        
+        if exhausted {
+            warn!("Giving up on {} after {} failed init attempts", process_id, self.max_init_attempts.load(Ordering::Relaxed));
+            self.mark_error(process_id, format!("Max init attempts exceeded: {}", error)).await
+        } else {
+            self.mark_error(process_id, error).await
+        }
+    }
+
+    /// 0 means the queue is unbounded.
+    pub fn set_max_queue_size(&self, max_queue_size: usize) {
+        self.max_queue_size.store(max_queue_size, Ordering::Relaxed);
+    }
+
+    pub async fn is_queue_full(&self) -> bool {
+        let max = self.max_queue_size.load(Ordering::Relaxed);
+        max > 0 && self.queued.read().await.len() >= max
+    }
+
+    pub fn queue_strategy(&self) -> QueueStrategy {
+        *self.queue_strategy.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn set_queue_strategy(&self, strategy: QueueStrategy) {
+        *self.queue_strategy.write().unwrap_or_else(|e| e.into_inner()) = strategy;
+    }
+
+    /// Index in `queue` of the process to activate next. Paused processes are skipped;
+    /// with `SmallestDeficitFirst`, processes whose deficit is unknown come last and
+    /// ties keep queue order.
+    fn select_next_to_activate(&self, queue: &VecDeque<ProcessConfig>, all: &HashMap<String, ProcessStatus>) -> Option<usize> {
+        let mut candidates = queue.iter().enumerate()
+            .filter(|(_, config)| !all.get(&config.process_id).is_some_and(|status| status.paused));
+        
+        match self.queue_strategy() {
+            QueueStrategy::Fifo => candidates.next().map(|(idx, _)| idx),
+            QueueStrategy::SmallestDeficitFirst => candidates
+                .min_by_key(|(_, config)| {
+                    // Queued processes usually have no computed slot yet, so the current slot is the deficit
+                    let deficit = all.get(&config.process_id).and_then(|status| match (status.computed_slot, status.current_slot) {
+                        (Some(computed), Some(current)) => Some(current.saturating_sub(computed)),
+                        (None, current) => current,
+                        (Some(_), None) => None,
+                    });
+                    (deficit.is_none(), deficit)
+                })
+                .map(|(idx, _)| idx),
         }
     }
 
-    pub async fn add_to_queue(&self, config: ProcessConfig) -> Result<(), String> {
+    /// Applies to the next activation; processes already active above the new limit are kept.
+    pub fn set_max_active_processes(&self, max_active: usize) {
+        self.max_active_processes.store(max_active, Ordering::Relaxed);
+    }
+
+    /// Runtime accumulated across restarts, including the current run.
+    pub fn total_runtime_seconds(&self) -> u64 {
+        let current = (Utc::now() - self.started_at).num_seconds().max(0) as u64;
+        self.previous_runtime_seconds.load(Ordering::Relaxed) + current
+    }
+
+    pub fn set_previous_runtime_seconds(&self, seconds: u64) {
+        self.previous_runtime_seconds.store(seconds, Ordering::Relaxed);
+    }
+
+    /// Record that process state changed and needs saving. Called by every mutating
+    /// method here; code that writes the maps directly must call it too.
+    pub fn mark_changed(&self) {
+        self.revision.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn revision(&self) -> u64 {
+        self.revision.load(Ordering::Relaxed)
+    }
+
+    /// Whether anything changed since state was last saved.
+    pub fn has_unsaved_changes(&self) -> bool {
+        self.revision() != self.saved_revision.load(Ordering::Relaxed)
+    }
+
+    pub fn mark_saved(&self, revision: u64) {
+        self.saved_revision.store(revision, Ordering::Relaxed);
+    }
+
+    fn check_allowed(&self, process_id: &str) -> Result<(), QueueError> {
+        let reason = if self.process_denylist.contains(process_id) {
+            "is on the denylist"
+        } else if !self.process_allowlist.is_empty() && !self.process_allowlist.contains(process_id) {
+            "is not on the allowlist"
+        } else {
+            return Ok(());
+        };
+        
+        warn!("Rejected process {}: {}", process_id, reason);
+        Err(QueueError::NotAllowed { process_id: process_id.to_string(), reason })
+    }
+
+    pub async fn add_to_queue(&self, config: ProcessConfig) -> Result<(), QueueError> {
         let process_id = config.process_id.clone();
+        self.check_allowed(&process_id)?;
         
         // Check if already exists
         let all = self.all_processes.read().await;
         if all.contains_key(&process_id) {
-            return Err(format!("Process {} already exists", process_id));
+            return Err(QueueError::AlreadyExists { process_id, state: None });
         }
         drop(all);
         
+        if self.is_queue_full().await {
+            return Err(QueueError::QueueFull(self.max_queue_size.load(Ordering::Relaxed)));
+        }
+        
         // Create new status
-        let mut status = ProcessStatus::new(config.name.clone(), process_id.clone());
+        let mut status = ProcessStatus::new(config.name.clone(), process_id.clone(), config.base_url.clone());
         status.state = ProcessState::Queued;
         
         // Add to queue
@@ -47,53 +269,80 @@ impl QueueManager {
         // Add to all processes
         let mut all = self.all_processes.write().await;
         all.insert(process_id, status);
+        self.mark_changed();
         
         Ok(())
     }
 
+    /// Add a new process, or update the name and base URL of one that is still
+    /// queued. Processes in any other state are rejected.
+    pub async fn upsert_to_queue(&self, config: ProcessConfig) -> Result<UpsertOutcome, QueueError> {
+        let existing_state = self.all_processes.read().await
+            .get(&config.process_id)
+            .map(|status| status.state.clone());
+        
+        match existing_state {
+            None => self.add_to_queue(config).await.map(|_| UpsertOutcome::Added),
+            Some(ProcessState::Queued) => {
+                self.update_process_config(&config.process_id, config.name, config.base_url).await;
+                Ok(UpsertOutcome::Updated)
+            }
+            Some(state) => Err(QueueError::AlreadyExists { process_id: config.process_id, state: Some(state) }),
+        }
+    }
+
     pub async fn activate_next(&self) -> Option<ProcessConfig> {
-        let active_count = self.active.read().await.len();
-        if active_count >= self.max_active_processes {
+        let mut active = self.active.write().await;
+        if active.len() >= self.max_active_processes() {
             return None;
         }
         
         let mut queue = self.queued.write().await;
-        if let Some(config) = queue.pop_front() {
-            let process_id = config.process_id.clone();
-            drop(queue);
+        let mut all = self.all_processes.write().await;
+        loop {
+            let next = self.select_next_to_activate(&queue, &all);
+            let config = next.and_then(|idx| queue.remove(idx))?;
             
-            // Update status
-            let mut all = self.all_processes.write().await;
-            if let Some(status) = all.get_mut(&process_id) {
-                status.state = ProcessState::Active;
-                status.activated_at = Some(Utc::now());
-                status.queue_position = None;
-                
-                // Add to active
-                let mut active = self.active.write().await;
-                active.insert(process_id, status.clone());
-            }
+            let Some(status) = all.get_mut(&config.process_id) else {
+                // Removed concurrently; drop the stale queue entry and try the next one
+                warn!("Queued process {} has no status entry, skipping", config.process_id);
+                continue;
+            };
+            
+            status.state = ProcessState::Active;
+            status.activated_at = Some(Utc::now());
+            status.queue_position = None;
+            status.log_activity(LogLevel::Info, "Activated");
+            
+            // Add to active
+            active.insert(config.process_id.clone(), status.clone());
             
             // Update queue positions
-            let queue = self.queued.read().await;
             for (idx, queued_config) in queue.iter().enumerate() {
                 if let Some(status) = all.get_mut(&queued_config.process_id) {
                     status.queue_position = Some(idx);
                 }
             }
+            self.mark_changed();
             
-            Some(config)
-        } else {
-            None
+            return Some(config);
         }
     }
 
-    pub async fn mark_synced(&self, process_id: &str) -> Result<(), String> {
+    /// Move an active process to synced. Returns false, leaving the original sync
+    /// timestamps alone, when the process is already synced.
+    pub async fn mark_synced(&self, process_id: &str) -> Result<bool, QueueError> {
         // Remove from active
         let mut active = self.active.write().await;
         if let Some(mut status) = active.remove(process_id) {
+            status.close_active_period();
             status.state = ProcessState::Synced;
             status.synced_at = Some(Utc::now());
+            status.metrics.sync_end_time = status.synced_at;
+            status.log_activity(LogLevel::Info, match status.current_slot {
+                Some(slot) => format!("Synced at slot {}", slot),
+                None => "Synced".to_string(),
+            });
             
             // Add to synced
             let mut synced = self.synced.write().await;
@@ -102,38 +351,53 @@ impl QueueManager {
             // Update in all processes
             let mut all = self.all_processes.write().await;
             all.insert(process_id.to_string(), status);
+            self.mark_changed();
             
-            Ok(())
+            Ok(true)
+        } else if self.synced.read().await.contains_key(process_id) {
+            Ok(false)
         } else {
-            Err(format!("Process {} not in active list", process_id))
+            Err(QueueError::WrongState { process_id: process_id.to_string(), expected: "active" })
         }
     }
 
-    pub async fn mark_error(&self, process_id: &str, error: String) -> Result<(), String> {
+    pub async fn mark_error(&self, process_id: &str, error: String) -> Result<(), QueueError> {
         // Remove from active
         let mut active = self.active.write().await;
         if let Some(mut status) = active.remove(process_id) {
+            status.close_active_period();
             status.state = ProcessState::Error;
+            status.log_activity(LogLevel::Error, error.clone());
             status.error = Some(error);
+            status.error_count += 1;
+            status.last_error_at = Some(Utc::now());
             
             // Update in all processes
             let mut all = self.all_processes.write().await;
             all.insert(process_id.to_string(), status);
+            self.mark_changed();
             
             Ok(())
         } else {
-            Err(format!("Process {} not in active list", process_id))
+            Err(QueueError::WrongState { process_id: process_id.to_string(), expected: "active" })
         }
     }
 
-    pub async fn restart_process(&self, process_id: &str) -> Result<(), String> {
+    pub async fn restart_process(&self, process_id: &str) -> Result<(), QueueError> {
+        let mut queue = self.queued.write().await;
         let mut all = self.all_processes.write().await;
         
         if let Some(status) = all.get_mut(process_id) {
+            if self.init_attempts_exhausted(status) {
+                return Err(self.exhausted_error(process_id));
+            }
+            
             // Reset status
+            status.close_active_period();
             status.state = ProcessState::Queued;
             status.error = None;
             status.cron_initialized = false;
+            status.added_at = Some(Utc::now());
             status.activated_at = None;
             status.synced_at = None;
             status.metrics = Default::default();
@@ -142,18 +406,169 @@ impl QueueManager {
             let config = ProcessConfig {
                 name: status.name.clone(),
                 process_id: process_id.to_string(),
-                base_url: None,
+                base_url: status.base_url.clone(),
             };
             
             // Add back to queue
-            let mut queue = self.queued.write().await;
             queue.push_back(config);
             status.queue_position = Some(queue.len() - 1);
+            self.mark_changed();
             
             Ok(())
         } else {
-            Err(format!("Process {} not found", process_id))
+            Err(QueueError::NotFound(process_id.to_string()))
+        }
+    }
+
+    pub async fn remove_process(&self, process_id: &str) -> Result<(), QueueError> {
+        let mut active = self.active.write().await;
+        let mut synced = self.synced.write().await;
+        let mut queue = self.queued.write().await;
+        let mut all = self.all_processes.write().await;
+        if all.remove(process_id).is_none() {
+            return Err(QueueError::NotFound(process_id.to_string()));
+        }
+        
+        active.remove(process_id);
+        synced.remove(process_id);
+        
+        // Rebuild the queue without the process and update positions
+        queue.retain(|config| config.process_id != process_id);
+        for (idx, queued_config) in queue.iter().enumerate() {
+            if let Some(status) = all.get_mut(&queued_config.process_id) {
+                status.queue_position = Some(idx);
+            }
+        }
+        self.mark_changed();
+        
+        Ok(())
+    }
+
+    /// Drop every queued process, leaving active and synced ones alone. Returns the
+    /// number removed.
+    pub async fn clear_queue(&self) -> usize {
+        let mut queue = self.queued.write().await;
+        let mut all = self.all_processes.write().await;
+        
+        let removed = queue.len();
+        for config in queue.drain(..) {
+            all.remove(&config.process_id);
+        }
+        
+        if removed > 0 {
+            self.mark_changed();
+            info!("Cleared {} processes from the queue", removed);
         }
+        removed
+    }
+
+    /// Move a queued process to `position`, clamped to the end of the queue.
+    pub async fn set_queue_position(&self, process_id: &str, position: usize) -> Result<usize, QueueError> {
+        let mut queue = self.queued.write().await;
+        let mut all = self.all_processes.write().await;
+        let Some(current) = queue.iter().position(|c| c.process_id == process_id) else {
+            return Err(if all.contains_key(process_id) {
+                QueueError::WrongState { process_id: process_id.to_string(), expected: "queued" }
+            } else {
+                QueueError::NotFound(process_id.to_string())
+            });
+        };
+        
+        let config = queue.remove(current).expect("index from position() is valid");
+        let position = position.min(queue.len());
+        queue.insert(position, config);
+        
+        for (idx, queued_config) in queue.iter().enumerate() {
+            if let Some(status) = all.get_mut(&queued_config.process_id) {
+                status.queue_position = Some(idx);
+            }
+        }
+        self.mark_changed();
+        
+        Ok(position)
+    }
+
+    pub async fn set_paused(&self, process_id: &str, paused: bool) -> Result<(), QueueError> {
+        self.update_process_status(process_id, |status| {
+            status.paused = paused;
+            status.log_activity(LogLevel::Info, if paused { "Paused" } else { "Unpaused" });
+        }).await
+    }
+
+    /// Record that an operator stopped the process's cron. The process is paused so
+    /// nothing restarts the cron, and an active one goes back to the queue to free its
+    /// active slot; unpausing it lets it be activated, and its cron started, again.
+    pub async fn mark_cron_stopped(&self, process_id: &str, note: String) -> Result<(), QueueError> {
+        let mut active = self.active.write().await;
+        let mut synced = self.synced.write().await;
+        let mut queue = self.queued.write().await;
+        let mut all = self.all_processes.write().await;
+        let Some(status) = all.get_mut(process_id) else {
+            return Err(QueueError::NotFound(process_id.to_string()));
+        };
+        
+        status.cron_initialized = false;
+        status.paused = true;
+        status.log_activity(LogLevel::Info, note);
+        if active.remove(process_id).is_some() {
+            status.close_active_period();
+            status.state = ProcessState::Queued;
+            status.activated_at = None;
+            queue.push_back(ProcessConfig {
+                name: status.name.clone(),
+                process_id: process_id.to_string(),
+                base_url: status.base_url.clone(),
+            });
+            status.queue_position = Some(queue.len() - 1);
+        }
+        if let Some(synced_status) = synced.get_mut(process_id) {
+            *synced_status = status.clone();
+        }
+        self.mark_changed();
+        
+        Ok(())
+    }
+
+    pub async fn reset_metrics(&self, process_id: &str) -> Result<(), QueueError> {
+        self.update_process_status(process_id, |status| {
+            status.metrics = Default::default();
+        }).await
+    }
+
+    /// Move an active or synced process back to the end of the queue.
+    pub async fn requeue(&self, config: ProcessConfig) -> Result<(), QueueError> {
+        // Hold every map for the whole move so no reader sees the process in none of them
+        let mut active = self.active.write().await;
+        let mut synced = self.synced.write().await;
+        let mut queue = self.queued.write().await;
+        let mut all = self.all_processes.write().await;
+        if all.get(&config.process_id).is_some_and(|status| self.init_attempts_exhausted(status)) {
+            return Err(self.exhausted_error(&config.process_id));
+        }
+        
+        let removed_active = active.remove(&config.process_id).is_some();
+        let removed_synced = synced.remove(&config.process_id).is_some();
+        if !removed_active && !removed_synced {
+            return Err(QueueError::WrongState { process_id: config.process_id, expected: "active or synced" });
+        }
+        
+        if let Some(status) = all.get_mut(&config.process_id) {
+            status.close_active_period();
+            status.state = ProcessState::Queued;
+            status.cron_initialized = false;
+            status.added_at = Some(Utc::now());
+            status.activated_at = None;
+            status.synced_at = None;
+            status.metrics = Default::default();
+            status.name = config.name.clone();
+            status.base_url = config.base_url.clone();
+            
+            queue.push_back(config);
+            status.queue_position = Some(queue.len() - 1);
+        }
+        self.mark_changed();
+        
+        Ok(())
     }
 
     pub async fn get_status(&self) -> (usize, usize, usize) {
@@ -163,8 +578,89 @@ impl QueueManager {
         (active, queued, synced)
     }
 
+    pub async fn count_by_state(&self) -> HashMap<ProcessState, usize> {
+        let mut counts = HashMap::new();
+        for status in self.all_processes.read().await.values() {
+            *counts.entry(status.state.clone()).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// All tracked processes with exactly this name; names are not required to be unique.
+    pub async fn get_by_name(&self, name: &str) -> Vec<ProcessStatus> {
+        self.all_processes.read().await
+            .values()
+            .filter(|status| status.name == name)
+            .cloned()
+            .map(ProcessStatus::with_derived_fields)
+            .collect()
+    }
+
+    pub async fn get_process(&self, process_id: &str) -> Option<ProcessStatus> {
+        self.all_processes.read().await
+            .get(process_id)
+            .cloned()
+            .map(ProcessStatus::with_derived_fields)
+    }
+
     pub async fn get_active_processes(&self) -> Vec<ProcessStatus> {
-        self.active.read().await.values().cloned().collect()
+        self.active.read().await.values().cloned().map(ProcessStatus::with_derived_fields).collect()
+    }
+
+    /// Combined slots per minute being hydrated across all active processes.
+    pub async fn aggregate_sync_rate(&self) -> f64 {
+        self.active.read().await.values()
+            .map(|p| p.metrics.avg_sync_rate)
+            .sum()
+    }
+
+    pub async fn count_desynced(&self, sync_tolerance: u64) -> usize {
+        self.synced.read().await.values()
+            .filter(|p| p.deficit().is_some_and(|d| d > sync_tolerance))
+            .count()
+    }
+
+    /// Active processes sorted by current deficit, largest first.
+    /// Stalled processes are left out unless `include_stalled` is set, so the list shows
+    /// pools that are catching up rather than ones that already raised an alert.
+    pub async fn get_laggards(
+        &self,
+        limit: usize,
+        include_synced: bool,
+        include_stalled: bool,
+        sync_tolerance: u64,
+        stall_threshold: u64,
+    ) -> Vec<ProcessStatus> {
+        let mut laggards: Vec<_> = self.get_active_processes().await
+            .into_iter()
+            .filter(|p| include_synced || !p.is_synced(sync_tolerance))
+            .filter(|p| include_stalled || !p.metrics.is_stalled(stall_threshold))
+            .collect();
+        laggards.sort_by_key(|p| std::cmp::Reverse(p.deficit().unwrap_or(0)));
+        laggards.into_iter().take(limit).collect()
+    }
+
+    /// Active processes whose cron has not been initialized yet, oldest activation first.
+    /// These have no cron entry, so they do not show up among the active crons.
+    pub async fn get_initializing_processes(&self) -> Vec<ProcessStatus> {
+        let mut initializing: Vec<_> = self.active.read().await
+            .values()
+            .filter(|p| !p.cron_initialized)
+            .cloned()
+            .collect();
+        initializing.sort_by_key(|p| p.activated_at);
+        initializing
+    }
+
+    /// Errored processes, oldest error first so the list is stable between refreshes.
+    pub async fn get_errored_processes(&self) -> Vec<ProcessStatus> {
+        let mut errored: Vec<_> = self.all_processes.read().await
+            .values()
+            .filter(|p| p.state == ProcessState::Error)
+            .cloned()
+            .collect();
+        errored.sort_by(|a, b| a.last_error_at.cmp(&b.last_error_at).then_with(|| a.process_id.cmp(&b.process_id)));
+        errored
     }
 
     pub async fn get_queue_preview(&self, limit: usize) -> Vec<ProcessStatus> {
@@ -175,55 +671,83 @@ impl QueueManager {
             .take(limit)
             .filter_map(|config| all.get(&config.process_id))
             .cloned()
+            .map(ProcessStatus::with_derived_fields)
             .collect()
     }
 
+    /// A page of the queue in order, along with the total queue length.
+    pub async fn get_queue_page(&self, offset: usize, limit: usize) -> (Vec<ProcessStatus>, usize) {
+        let queue = self.queued.read().await;
+        let all = self.all_processes.read().await;
+        
+        let page = queue.iter()
+            .skip(offset)
+            .take(limit)
+            .filter_map(|config| all.get(&config.process_id))
+            .cloned()
+            .map(ProcessStatus::with_derived_fields)
+            .collect();
+        (page, queue.len())
+    }
+
     pub async fn get_recent_synced(&self, limit: usize) -> Vec<ProcessStatus> {
         let mut synced: Vec<_> = self.synced.read().await.values().cloned().collect();
         synced.sort_by_key(|s| s.synced_at);
         synced.reverse();
-        synced.into_iter().take(limit).collect()
+        synced.into_iter().take(limit).map(ProcessStatus::with_derived_fields).collect()
     }
 
-    pub async fn update_process_status(&self, process_id: &str, update_fn: impl FnOnce(&mut ProcessStatus)) -> Result<(), String> {
+    /// Synced processes with `synced_at` at or after `cutoff`, most recent first.
+    pub async fn get_synced_since(&self, cutoff: DateTime<Utc>) -> Vec<ProcessStatus> {
+        let mut synced: Vec<_> = self.synced.read().await.values()
+            .filter(|s| s.synced_at.is_some_and(|at| at >= cutoff))
+            .cloned()
+            .collect();
+        synced.sort_by_key(|s| s.synced_at);
+        synced.reverse();
+        synced.into_iter().map(ProcessStatus::with_derived_fields).collect()
+    }
+
+    pub async fn update_process_status(&self, process_id: &str, update_fn: impl FnOnce(&mut ProcessStatus)) -> Result<(), QueueError> {
+        let mut active = self.active.write().await;
+        let mut synced = self.synced.write().await;
         let mut all = self.all_processes.write().await;
         if let Some(status) = all.get_mut(process_id) {
             update_fn(status);
+            self.mark_changed();
             
             // Also update in active if present
-            let mut active = self.active.write().await;
             if let Some(active_status) = active.get_mut(process_id) {
                 *active_status = status.clone();
             }
-            drop(active);
             
             // Also update in synced if present
-            let mut synced = self.synced.write().await;
             if let Some(synced_status) = synced.get_mut(process_id) {
                 *synced_status = status.clone();
             }
             
             Ok(())
         } else {
-            Err(format!("Process {} not found", process_id))
+            Err(QueueError::NotFound(process_id.to_string()))
         }
     }
 
+    #[allow(dead_code)]
     pub async fn update_process_base_url(&self, process_id: &str, base_url: Option<String>) {
-        let mut all = self.all_processes.write().await;
-        if let Some(_status) = all.get_mut(process_id) {
-            // Store base_url in process status if we add that field
-            // For now, just log it
-            info!("Updated base_url for process {}: {:?}", process_id, base_url);
-        }
+        let _ = self.update_process_status(process_id, |status| {
+            status.base_url = base_url.clone();
+        }).await;
+        info!("Updated base_url for process {}: {:?}", process_id, base_url);
     }
     
     pub async fn update_process_config(&self, process_id: &str, name: String, base_url: Option<String>) {
+        self.mark_changed();
+        
         // Update in all_processes
         let mut all = self.all_processes.write().await;
         if let Some(status) = all.get_mut(process_id) {
             status.name = name.clone();
-            // Store base_url in process status if we add that field
+            status.base_url = base_url.clone();
             info!("Updated name and base_url for process {}: name={}, base_url={:?}", process_id, name, base_url);
         }
         drop(all);
@@ -232,6 +756,7 @@ impl QueueManager {
         let mut active = self.active.write().await;
         if let Some(status) = active.get_mut(process_id) {
             status.name = name.clone();
+            status.base_url = base_url.clone();
         }
         drop(active);
         
@@ -239,6 +764,7 @@ impl QueueManager {
         let mut synced = self.synced.write().await;
         if let Some(status) = synced.get_mut(process_id) {
             status.name = name.clone();
+            status.base_url = base_url.clone();
         }
         drop(synced);
         
@@ -252,4 +778,461 @@ impl QueueManager {
             }
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manager() -> QueueManager {
+        QueueManager::new(100, 0, 0, Vec::new(), Vec::new(), QueueStrategy::Fifo)
+    }
+
+    fn config(process_id: &str) -> ProcessConfig {
+        ProcessConfig {
+            name: format!("pool-{}", process_id),
+            process_id: process_id.to_string(),
+            base_url: None,
+        }
+    }
+
+    /// Queue and activate a process, then record a slot reading for it.
+    async fn add_active(queue: &QueueManager, process_id: &str, computed: u64, current: u64) {
+        queue.add_to_queue(config(process_id)).await.unwrap();
+        assert_eq!(queue.activate_next().await.unwrap().process_id, process_id);
+        queue.update_process_status(process_id, |status| {
+            status.computed_slot = Some(computed);
+            status.current_slot = Some(current);
+        }).await.unwrap();
+    }
+
+    async fn process(queue: &QueueManager, process_id: &str) -> ProcessStatus {
+        queue.all_processes.read().await.get(process_id).cloned().unwrap()
+    }
+
+    fn ids(processes: &[ProcessStatus]) -> Vec<&str> {
+        processes.iter().map(|p| p.process_id.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn laggards_are_ordered_by_deficit_and_limited() {
+        let queue = manager();
+        add_active(&queue, "small", 90, 100).await;
+        add_active(&queue, "large", 0, 500).await;
+        add_active(&queue, "synced", 100, 100).await;
+        add_active(&queue, "medium", 50, 150).await;
+
+        let laggards = queue.get_laggards(10, false, false, 0, 10).await;
+        assert_eq!(ids(&laggards), ["large", "medium", "small"]);
+
+        let laggards = queue.get_laggards(2, false, false, 0, 10).await;
+        assert_eq!(ids(&laggards), ["large", "medium"]);
+
+        let laggards = queue.get_laggards(10, true, false, 0, 10).await;
+        assert_eq!(ids(&laggards), ["large", "medium", "small", "synced"]);
+    }
+
+    #[tokio::test]
+    async fn laggards_exclude_stalled_unless_requested() {
+        let queue = manager();
+        add_active(&queue, "moving", 0, 100).await;
+        add_active(&queue, "stalled", 0, 500).await;
+        queue.update_process_status("stalled", |status| {
+            status.metrics.checks_without_progress = 3;
+        }).await.unwrap();
+
+        assert_eq!(ids(&queue.get_laggards(10, false, false, 0, 3).await), ["moving"]);
+        assert_eq!(ids(&queue.get_laggards(10, false, true, 0, 3).await), ["stalled", "moving"]);
+        // Below the threshold the process is not stalled yet
+        assert_eq!(ids(&queue.get_laggards(10, false, false, 0, 4).await), ["stalled", "moving"]);
+        // A threshold of 0 disables stall detection
+        assert_eq!(ids(&queue.get_laggards(10, false, false, 0, 0).await), ["stalled", "moving"]);
+    }
+
+    fn with_lists(allowlist: &[&str], denylist: &[&str]) -> QueueManager {
+        let list = |ids: &[&str]| ids.iter().map(|id| id.to_string()).collect();
+        QueueManager::new(100, 0, 0, list(allowlist), list(denylist), QueueStrategy::Fifo)
+    }
+
+    #[tokio::test]
+    async fn denylisted_processes_are_rejected() {
+        let queue = with_lists(&[], &["denied"]);
+        assert!(matches!(
+            queue.add_to_queue(config("denied")).await,
+            Err(QueueError::NotAllowed { reason: "is on the denylist", .. })
+        ));
+        assert!(queue.upsert_to_queue(config("denied")).await.is_err());
+        queue.add_to_queue(config("other")).await.unwrap();
+        assert!(!queue.all_processes.read().await.contains_key("denied"));
+    }
+
+    #[tokio::test]
+    async fn only_allowlisted_processes_are_accepted_when_an_allowlist_is_set() {
+        let queue = with_lists(&["allowed", "both"], &["both"]);
+        queue.add_to_queue(config("allowed")).await.unwrap();
+        assert!(matches!(
+            queue.add_to_queue(config("other")).await,
+            Err(QueueError::NotAllowed { reason: "is not on the allowlist", .. })
+        ));
+        // The denylist wins over the allowlist
+        assert!(matches!(
+            queue.add_to_queue(config("both")).await,
+            Err(QueueError::NotAllowed { reason: "is on the denylist", .. })
+        ));
+        assert_eq!(queue.get_status().await, (0, 1, 0));
+    }
+
+    #[tokio::test]
+    async fn lifecycle_timings_follow_a_process_through_every_phase() {
+        let queue = manager();
+        let ago = |secs| Some(Utc::now() - chrono::Duration::seconds(secs));
+        let within = |value: Option<u64>, expected: u64| value.is_some_and(|v| v.abs_diff(expected) <= 1);
+
+        queue.add_to_queue(config("a")).await.unwrap();
+        queue.update_process_status("a", |status| status.added_at = ago(600)).await.unwrap();
+        let timings = process(&queue, "a").await.lifecycle_timings();
+        assert!(within(timings.queued_secs, 600));
+        assert_eq!((timings.active_secs, timings.synced_secs), (None, None));
+
+        queue.activate_next().await.unwrap();
+        queue.update_process_status("a", |status| status.activated_at = ago(500)).await.unwrap();
+        let timings = process(&queue, "a").await.lifecycle_timings();
+        assert!(within(timings.queued_secs, 100));
+        assert!(within(timings.active_secs, 500));
+        assert_eq!(timings.synced_secs, None);
+
+        queue.mark_synced("a").await.unwrap();
+        queue.update_process_status("a", |status| status.synced_at = ago(50)).await.unwrap();
+        let timings = process(&queue, "a").await.lifecycle_timings();
+        assert!(within(timings.queued_secs, 100));
+        assert!(within(timings.active_secs, 450));
+        assert!(within(timings.synced_secs, 50));
+    }
+
+    #[tokio::test]
+    async fn only_max_active_processes_are_activated() {
+        let queue = QueueManager::new(2, 0, 0, Vec::new(), Vec::new(), QueueStrategy::Fifo);
+        for id in ["a", "b", "c"] {
+            queue.add_to_queue(config(id)).await.unwrap();
+        }
+
+        assert_eq!(queue.activate_next().await.unwrap().process_id, "a");
+        assert_eq!(queue.activate_next().await.unwrap().process_id, "b");
+        assert!(queue.activate_next().await.is_none());
+        assert_eq!(queue.max_active_processes(), 2);
+        assert_eq!(queue.get_status().await, (2, 1, 0));
+
+        // A freed slot is taken by the next queued process
+        queue.mark_synced("a").await.unwrap();
+        assert_eq!(queue.activate_next().await.unwrap().process_id, "c");
+    }
+
+    async fn assert_removed(queue: &QueueManager, process_id: &str) {
+        queue.remove_process(process_id).await.unwrap();
+        assert!(!queue.all_processes.read().await.contains_key(process_id));
+        assert!(!queue.active.read().await.contains_key(process_id));
+        assert!(!queue.synced.read().await.contains_key(process_id));
+        assert!(queue.queued.read().await.iter().all(|c| c.process_id != process_id));
+    }
+
+    #[tokio::test]
+    async fn remove_process_from_each_state() {
+        let queue = manager();
+        add_active(&queue, "active", 0, 100).await;
+        add_active(&queue, "synced", 100, 100).await;
+        queue.mark_synced("synced").await.unwrap();
+        add_active(&queue, "errored", 0, 100).await;
+        queue.mark_error("errored", "boom".to_string()).await.unwrap();
+        for id in ["queued-1", "queued-2", "queued-3"] {
+            queue.add_to_queue(config(id)).await.unwrap();
+        }
+
+        assert_removed(&queue, "active").await;
+        assert_removed(&queue, "synced").await;
+        assert_removed(&queue, "errored").await;
+        assert_removed(&queue, "queued-1").await;
+
+        // Positions of the processes left in the queue are renumbered
+        assert_eq!(process(&queue, "queued-2").await.queue_position, Some(0));
+        assert_eq!(process(&queue, "queued-3").await.queue_position, Some(1));
+        assert_eq!(queue.get_status().await, (0, 2, 0));
+
+        assert_eq!(queue.remove_process("active").await, Err(QueueError::NotFound("active".to_string())));
+    }
+
+    #[tokio::test]
+    async fn concurrent_removal_and_transitions_do_not_deadlock() {
+        let queue = Arc::new(manager());
+        for i in 0..20 {
+            add_active(&queue, &format!("p{}", i), 0, 100).await;
+        }
+
+        let tasks: Vec<_> = (0..20).map(|i| {
+            let queue = queue.clone();
+            tokio::spawn(async move {
+                let id = format!("p{}", i);
+                match i % 3 {
+                    0 => { let _ = queue.remove_process(&id).await; }
+                    1 => { let _ = queue.mark_synced(&id).await; }
+                    _ => { let _ = queue.mark_error(&id, "boom".to_string()).await; }
+                }
+                let _ = queue.update_process_status(&id, |status| status.cron_initialized = true).await;
+                let _ = queue.remove_process(&id).await;
+            })
+        }).collect();
+
+        let all_done = async {
+            for task in tasks {
+                task.await.unwrap();
+            }
+        };
+        tokio::time::timeout(std::time::Duration::from_secs(5), all_done).await
+            .expect("queue operations deadlocked");
+        assert!(queue.all_processes.read().await.is_empty());
+    }
+
+    async fn queued_ids(queue: &QueueManager) -> Vec<String> {
+        queue.queued.read().await.iter().map(|c| c.process_id.clone()).collect()
+    }
+
+    #[tokio::test]
+    async fn queue_positions_can_be_set_and_are_clamped() {
+        let queue = manager();
+        for id in ["a", "b", "c", "d"] {
+            queue.add_to_queue(config(id)).await.unwrap();
+        }
+
+        assert_eq!(queue.set_queue_position("c", 0).await.unwrap(), 0);
+        assert_eq!(queued_ids(&queue).await, ["c", "a", "b", "d"]);
+
+        // Past the end moves it to the back
+        assert_eq!(queue.set_queue_position("a", 99).await.unwrap(), 3);
+        assert_eq!(queued_ids(&queue).await, ["c", "b", "d", "a"]);
+        for (position, id) in ["c", "b", "d", "a"].iter().enumerate() {
+            assert_eq!(queue.get_process(id).await.unwrap().queue_position, Some(position));
+        }
+
+        assert_eq!(queue.activate_next().await.unwrap().process_id, "c");
+        assert!(matches!(queue.set_queue_position("c", 0).await, Err(QueueError::WrongState { .. })));
+        assert!(matches!(queue.set_queue_position("missing", 0).await, Err(QueueError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn restart_keeps_the_process_base_url() {
+        let queue = manager();
+        queue.add_to_queue(ProcessConfig {
+            name: "pool".to_string(),
+            process_id: "a".to_string(),
+            base_url: Some("http://node-2".to_string()),
+        }).await.unwrap();
+        queue.activate_next().await.unwrap();
+        queue.mark_error("a", "cron failed".to_string()).await.unwrap();
+
+        queue.restart_process("a").await.unwrap();
+        assert_eq!(queue.get_process("a").await.unwrap().base_url.as_deref(), Some("http://node-2"));
+        let restarted = queue.activate_next().await.unwrap();
+        assert_eq!(restarted.process_id, "a");
+        assert_eq!(restarted.base_url.as_deref(), Some("http://node-2"));
+    }
+
+    #[tokio::test]
+    async fn marking_synced_records_the_time_to_sync() {
+        let queue = manager();
+        add_active(&queue, "a", 100, 100).await;
+        let started = Utc::now() - chrono::Duration::minutes(5);
+        queue.update_process_status("a", |status| {
+            status.metrics.sync_start_time = Some(started);
+        }).await.unwrap();
+        assert_eq!(queue.get_process("a").await.unwrap().metrics.time_to_sync(), None);
+
+        queue.mark_synced("a").await.unwrap();
+        let process = queue.get_process("a").await.unwrap();
+        assert_eq!(process.metrics.sync_end_time, process.synced_at);
+        let time_to_sync = process.metrics.time_to_sync().unwrap();
+        assert!(time_to_sync >= chrono::Duration::minutes(5));
+        assert_eq!(process.metrics.time_to_sync_seconds, Some(time_to_sync.num_seconds()));
+    }
+
+    #[tokio::test]
+    async fn upsert_adds_new_processes_and_updates_queued_ones() {
+        let queue = manager();
+        queue.add_to_queue(config("first")).await.unwrap();
+        assert_eq!(queue.upsert_to_queue(config("a")).await.unwrap(), UpsertOutcome::Added);
+        assert_eq!(queued_ids(&queue).await, ["first", "a"]);
+
+        let renamed = ProcessConfig {
+            name: "renamed".to_string(),
+            process_id: "a".to_string(),
+            base_url: Some("http://node".to_string()),
+        };
+        assert_eq!(queue.upsert_to_queue(renamed).await.unwrap(), UpsertOutcome::Updated);
+        assert_eq!(queued_ids(&queue).await, ["first", "a"]);
+        let process = queue.get_process("a").await.unwrap();
+        assert_eq!((process.name.as_str(), process.base_url.as_deref()), ("renamed", Some("http://node")));
+        assert_eq!(process.queue_position, Some(1));
+        let queued = queue.queued.read().await[1].clone();
+        assert_eq!((queued.name.as_str(), queued.base_url.as_deref()), ("renamed", Some("http://node")));
+    }
+
+    #[tokio::test]
+    async fn upsert_rejects_active_and_synced_processes() {
+        let queue = manager();
+        add_active(&queue, "active", 0, 100).await;
+        add_active(&queue, "synced", 100, 100).await;
+        queue.mark_synced("synced").await.unwrap();
+
+        for (id, state) in [("active", ProcessState::Active), ("synced", ProcessState::Synced)] {
+            assert_eq!(
+                queue.upsert_to_queue(config(id)).await,
+                Err(QueueError::AlreadyExists { process_id: id.to_string(), state: Some(state) }),
+            );
+        }
+        assert!(queue.queued.read().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn aggregate_sync_rate_sums_active_rates() {
+        let queue = manager();
+        assert_eq!(queue.aggregate_sync_rate().await, 0.0);
+
+        for (id, rate) in [("a", 1.5), ("b", 2.0), ("c", 10.25)] {
+            add_active(&queue, id, 0, 100).await;
+            queue.update_process_status(id, |status| status.metrics.avg_sync_rate = rate).await.unwrap();
+        }
+        assert_eq!(queue.aggregate_sync_rate().await, 13.75);
+
+        // Synced processes no longer count
+        queue.mark_synced("c").await.unwrap();
+        assert_eq!(queue.aggregate_sync_rate().await, 3.5);
+    }
+
+    #[tokio::test]
+    async fn adding_stops_at_the_queue_limit() {
+        let queue = QueueManager::new(1, 2, 0, Vec::new(), Vec::new(), QueueStrategy::Fifo);
+        queue.add_to_queue(config("a")).await.unwrap();
+        assert!(!queue.is_queue_full().await);
+        queue.add_to_queue(config("b")).await.unwrap();
+        assert!(queue.is_queue_full().await);
+
+        assert_eq!(queue.add_to_queue(config("c")).await, Err(QueueError::QueueFull(2)));
+        assert_eq!(queue.add_to_queue(config("d")).await, Err(QueueError::QueueFull(2)));
+        assert!(queue.get_process("c").await.is_none());
+        assert_eq!(queued_ids(&queue).await, ["a", "b"]);
+
+        // Activating frees a place
+        queue.activate_next().await.unwrap();
+        queue.add_to_queue(config("c")).await.unwrap();
+        assert_eq!(queued_ids(&queue).await, ["b", "c"]);
+
+        // 0 lifts the limit
+        queue.set_max_queue_size(0);
+        queue.add_to_queue(config("d")).await.unwrap();
+        assert!(!queue.is_queue_full().await);
+    }
+
+    #[tokio::test]
+    async fn activation_skips_queue_entries_without_a_status() {
+        let queue = manager();
+        // Left behind by a concurrent removal
+        queue.queued.write().await.push_back(config("stale"));
+        queue.add_to_queue(config("live")).await.unwrap();
+
+        assert_eq!(queue.activate_next().await.unwrap().process_id, "live");
+        assert!(!queue.active.read().await.contains_key("stale"));
+        assert!(queue.queued.read().await.is_empty());
+        assert_eq!(queue.get_process("live").await.unwrap().state, ProcessState::Active);
+
+        // Nothing but stale entries left to activate
+        queue.queued.write().await.push_back(config("stale"));
+        assert!(queue.activate_next().await.is_none());
+        assert!(queue.queued.read().await.is_empty());
+        assert_eq!(queue.get_status().await, (1, 0, 0));
+    }
+
+    #[tokio::test]
+    async fn each_error_is_counted_and_timestamped() {
+        let queue = manager();
+        add_active(&queue, "a", 0, 100).await;
+        queue.mark_error("a", "first".to_string()).await.unwrap();
+        let first = queue.get_process("a").await.unwrap();
+        assert_eq!((first.error_count, first.error.as_deref()), (1, Some("first")));
+        let first_at = first.last_error_at.unwrap();
+
+        queue.restart_process("a").await.unwrap();
+        assert_eq!(queue.get_process("a").await.unwrap().error_count, 1);
+        queue.activate_next().await.unwrap();
+        queue.mark_error("a", "second".to_string()).await.unwrap();
+
+        let second = queue.get_process("a").await.unwrap();
+        assert_eq!(second.state, ProcessState::Error);
+        assert_eq!((second.error_count, second.error.as_deref()), (2, Some("second")));
+        assert!(second.last_error_at.unwrap() >= first_at);
+    }
+
+    async fn activation_order(strategy: QueueStrategy) -> Vec<String> {
+        let queue = QueueManager::new(100, 0, 0, Vec::new(), Vec::new(), strategy);
+        for (id, computed, current) in [
+            ("a", None, Some(500)),
+            ("b", Some(90), Some(100)),
+            ("unknown", None, None),
+            ("d", None, Some(10)),
+            ("paused", None, Some(1)),
+        ] {
+            queue.add_to_queue(config(id)).await.unwrap();
+            queue.update_process_status(id, |status| {
+                status.computed_slot = computed;
+                status.current_slot = current;
+            }).await.unwrap();
+        }
+        queue.set_paused("paused", true).await.unwrap();
+
+        let mut order = Vec::new();
+        while let Some(config) = queue.activate_next().await {
+            order.push(config.process_id);
+        }
+        assert_eq!(queued_ids(&queue).await, ["paused"]);
+        order
+    }
+
+    #[tokio::test]
+    async fn each_strategy_picks_the_expected_order() {
+        assert_eq!(activation_order(QueueStrategy::Fifo).await, ["a", "b", "unknown", "d"]);
+        // Ties keep queue order and unknown deficits go last
+        assert_eq!(activation_order(QueueStrategy::SmallestDeficitFirst).await, ["b", "d", "a", "unknown"]);
+    }
+
+    #[tokio::test]
+    async fn freshly_activated_processes_are_initializing_until_cron_starts() {
+        let queue = manager();
+        add_active(&queue, "first", 0, 100).await;
+        add_active(&queue, "second", 0, 100).await;
+        queue.add_to_queue(config("queued")).await.unwrap();
+        queue.update_process_status("first", |status| {
+            status.activated_at = Some(Utc::now() - chrono::Duration::seconds(10));
+        }).await.unwrap();
+
+        let initializing = queue.get_initializing_processes().await;
+        assert_eq!(ids(&initializing), ["first", "second"]);
+
+        queue.update_process_status("first", |status| status.cron_initialized = true).await.unwrap();
+        assert_eq!(ids(&queue.get_initializing_processes().await), ["second"]);
+    }
+
+    #[tokio::test]
+    async fn marking_a_synced_process_again_changes_nothing() {
+        let queue = manager();
+        add_active(&queue, "a", 100, 100).await;
+
+        assert!(queue.mark_synced("a").await.unwrap());
+        let first = queue.get_process("a").await.unwrap();
+        assert!(first.synced_at.is_some());
+
+        assert!(!queue.mark_synced("a").await.unwrap());
+        let second = queue.get_process("a").await.unwrap();
+        assert_eq!(second.synced_at, first.synced_at);
+        assert_eq!(second.metrics.sync_end_time, first.metrics.sync_end_time);
+        assert_eq!(second.activity_log.len(), first.activity_log.len());
+        assert_eq!(queue.get_status().await, (0, 0, 1));
+
+        assert!(matches!(queue.mark_synced("missing").await, Err(QueueError::WrongState { .. })));
+    }
+}
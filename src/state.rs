@@ -1,31 +1,222 @@
+#[cfg(feature = "sqlite")]
+mod sqlite;
+
+use crate::config::{StateBackend, StateConfig};
 use crate::models::{ProcessMetricsData, ProcessStatusData, ProcessState, StateFile};
 use crate::queue::QueueManager;
 use anyhow::Result;
 use chrono::Utc;
-use std::collections::HashMap;
-use std::path::Path;
-use tokio::fs;
+use std::collections::{HashMap, HashSet};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
-const STATE_FILE_PATH: &str = "hydration-state.json";
+#[cfg(feature = "sqlite")]
+pub use sqlite::SqliteStateStore;
 
-pub async fn save_state(queue: &QueueManager) -> Result<()> {
-    let all_processes = queue.all_processes.read().await;
+/// Format version written to the state file
+pub const STATE_VERSION: &str = "2.0";
+
+/// Persistence backend for the service state.
+pub trait StateStore: Send + Sync {
+    fn write(&self, state: &StateFile) -> Result<()>;
+    fn read(&self) -> Result<Option<StateFile>>;
+}
+
+pub struct JsonStateStore {
+    path: PathBuf,
+    compact: bool,
+    gzip: bool,
+}
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// Parse a state file, decompressing it first if it is gzipped.
+fn decode_state(bytes: &[u8]) -> Result<StateFile> {
+    if bytes.starts_with(&GZIP_MAGIC) {
+        let mut json = Vec::new();
+        GzDecoder::new(bytes).read_to_end(&mut json)?;
+        Ok(serde_json::from_slice(&json)?)
+    } else {
+        Ok(serde_json::from_slice(bytes)?)
+    }
+}
+
+impl JsonStateStore {
+    pub fn from_config(config: &StateConfig) -> Self {
+        Self {
+            path: PathBuf::from(&config.path),
+            compact: config.compact,
+            gzip: config.gzip,
+        }
+    }
+    
+    /// Where this store writes; gzipped state goes next to the plain path with a `.gz` suffix.
+    fn file_path(&self) -> PathBuf {
+        if self.gzip {
+            with_suffix(&self.path, ".gz")
+        } else {
+            self.path.clone()
+        }
+    }
+    
+    /// Where the other format (gzipped or plain) would be written.
+    fn other_format_path(&self) -> PathBuf {
+        if self.gzip {
+            self.path.clone()
+        } else {
+            with_suffix(&self.path, ".gz")
+        }
+    }
+    
+    /// The file to load from, falling back to the other format so toggling `gzip` keeps
+    /// the state. Every write removes the other format, so at most one of them is current.
+    fn existing_path(&self) -> Option<PathBuf> {
+        [self.file_path(), self.other_format_path()].into_iter().find(|path| path.exists())
+    }
+}
+
+impl StateStore for JsonStateStore {
+    fn write(&self, state: &StateFile) -> Result<()> {
+        let json = if self.compact {
+            serde_json::to_vec(state)?
+        } else {
+            serde_json::to_vec_pretty(state)?
+        };
+        let bytes = if self.gzip {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(&json)?;
+            encoder.finish()?
+        } else {
+            json
+        };
+        
+        // Write to a temp file and rename it over the target so a crash mid-write
+        // never leaves a truncated state file behind. The data is synced first, or
+        // the rename can reach disk before it does.
+        let path = self.file_path();
+        let tmp_path = with_suffix(&path, ".tmp");
+        let mut file = fs::File::create(&tmp_path)?;
+        file.write_all(&bytes)?;
+        file.sync_all()?;
+        fs::rename(&tmp_path, &path)?;
+        
+        // A file left in the other format is now stale and must not be loaded later
+        match fs::remove_file(self.other_format_path()) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn read(&self) -> Result<Option<StateFile>> {
+        let Some(path) = self.existing_path() else {
+            return Ok(None);
+        };
+        let bytes = fs::read(&path)?;
+        match decode_state(&bytes) {
+            Ok(state) => Ok(Some(state)),
+            Err(e) => {
+                // Keep the corrupt file around for inspection and start fresh
+                let corrupt_path = with_suffix(&path, ".corrupt");
+                tracing::warn!("State file {} is corrupt ({}), moving it to {} and starting fresh",
+                    path.display(), e, corrupt_path.display());
+                fs::rename(&path, &corrupt_path)?;
+                Ok(None)
+            }
+        }
+    }
+}
+
+pub fn open_store(config: &StateConfig) -> Result<Arc<dyn StateStore>> {
+    match config.backend {
+        StateBackend::Json => Ok(Arc::new(JsonStateStore::from_config(config))),
+        #[cfg(feature = "sqlite")]
+        StateBackend::Sqlite => {
+            let store = SqliteStateStore::open(&config.sqlite_path)?;
+            store.migrate_from_json(&JsonStateStore::from_config(config))?;
+            Ok(Arc::new(store))
+        }
+        #[cfg(not(feature = "sqlite"))]
+        StateBackend::Sqlite => Err(anyhow::anyhow!("SQLite state backend requires building with the `sqlite` feature")),
+    }
+}
+
+/// The persisted state, read without modifying anything: a corrupt JSON file is reported
+/// rather than moved aside, and with the SQLite backend the JSON file that would be
+/// migrated is read when there is no database yet.
+pub fn peek_state(config: &StateConfig) -> Result<Option<StateFile>> {
+    let peek_json = || -> Result<Option<StateFile>> {
+        match JsonStateStore::from_config(config).existing_path() {
+            Some(path) => Ok(Some(decode_state(&fs::read(path)?)?)),
+            None => Ok(None),
+        }
+    };
+    
+    match config.backend {
+        StateBackend::Json => peek_json(),
+        #[cfg(feature = "sqlite")]
+        StateBackend::Sqlite if Path::new(&config.sqlite_path).exists() => {
+            SqliteStateStore::open(&config.sqlite_path)?.read()
+        }
+        #[cfg(feature = "sqlite")]
+        StateBackend::Sqlite => peek_json(),
+        #[cfg(not(feature = "sqlite"))]
+        StateBackend::Sqlite => Err(anyhow::anyhow!("SQLite state backend requires building with the `sqlite` feature")),
+    }
+}
+
+/// IDs of the processes in the persisted state, read without modifying anything:
+/// a missing or unreadable state is treated as empty.
+pub fn peek_process_ids(config: &StateConfig) -> Result<HashSet<String>> {
+    let state = peek_state(config).unwrap_or_else(|e| {
+        tracing::debug!("Ignoring unreadable state: {}", e);
+        None
+    });
+    Ok(state.map(|s| s.processes.into_keys().collect()).unwrap_or_default())
+}
+
+pub async fn build_state(queue: &QueueManager, config: &StateConfig) -> StateFile {
     let active_ids = queue.active.read().await;
     let synced_ids = queue.synced.read().await;
     let queued = queue.queued.read().await;
+    let all_processes = queue.all_processes.read().await;
     
     let mut processes = HashMap::new();
     for (id, status) in all_processes.iter() {
         processes.insert(
             id.clone(),
             ProcessStatusData {
+                name: status.name.clone(),
+                base_url: status.base_url.clone(),
                 state: status.state.clone(),
                 cron_initialized: status.cron_initialized,
                 computed_slot: status.computed_slot,
                 current_slot: status.current_slot,
                 last_checked: status.last_checked,
+                last_advanced_at: status.last_advanced_at,
+                error: status.error.clone(),
+                error_count: status.error_count,
+                last_error_at: status.last_error_at,
+                init_attempts: status.init_attempts,
                 synced_at: status.synced_at,
                 activated_at: status.activated_at,
+                added_at: status.added_at,
+                total_active_seconds: status.total_active_seconds,
+                hb_reserves: status.hb_reserves.clone(),
+                ao_reserves: status.ao_reserves.clone(),
+                reserves_last_checked: status.reserves_last_checked,
+                paused: status.paused,
                 metrics: ProcessMetricsData {
                     initial_slot_deficit: status.metrics.initial_slot_deficit,
                     total_slots_advanced: status.metrics.total_slots_advanced,
@@ -33,52 +224,84 @@ pub async fn save_state(queue: &QueueManager) -> Result<()> {
                     sync_end_time: status.metrics.sync_end_time,
                     avg_sync_rate: status.metrics.avg_sync_rate,
                     check_count: status.metrics.check_count,
+                    api_response_times: if config.persist_response_times {
+                        status.metrics.api_response_times.clone()
+                    } else {
+                        Vec::new()
+                    },
                 },
             },
         );
     }
     
-    let state = StateFile {
-        version: "2.0".to_string(),
+    StateFile {
+        version: STATE_VERSION.to_string(),
         last_updated: Utc::now(),
         active_process_ids: active_ids.keys().cloned().collect(),
         synced_process_ids: synced_ids.keys().cloned().collect(),
         queued_process_ids: queued.iter().map(|c| c.process_id.clone()).collect(),
+        total_runtime_seconds: queue.total_runtime_seconds(),
         processes,
-    };
-    
-    let json = serde_json::to_string_pretty(&state)?;
-    fs::write(STATE_FILE_PATH, json).await?;
-    
+    }
+}
+
+pub async fn save_state(store: &Arc<dyn StateStore>, queue: &QueueManager, config: &StateConfig) -> Result<()> {
+    // Read the revision first so changes made while writing are picked up by the next save
+    let revision = queue.revision();
+    let state = build_state(queue, config).await;
+    let store = store.clone();
+    tokio::task::spawn_blocking(move || store.write(&state)).await??;
+    queue.mark_saved(revision);
     Ok(())
 }
 
-pub async fn load_state(queue: &QueueManager) -> Result<bool> {
-    let path = Path::new(STATE_FILE_PATH);
-    if !path.exists() {
+/// Save only if process state changed since the last save, returning whether a write
+/// happened. The accumulated runtime alone does not count as a change; it is brought
+/// up to date by the next save, including the one made on shutdown.
+pub async fn save_state_if_changed(store: &Arc<dyn StateStore>, queue: &QueueManager, config: &StateConfig) -> Result<bool> {
+    if !queue.has_unsaved_changes() {
         return Ok(false);
     }
+    save_state(store, queue, config).await?;
+    Ok(true)
+}
+
+pub async fn load_state(store: &Arc<dyn StateStore>, queue: &QueueManager) -> Result<bool> {
+    let reader = store.clone();
+    let Some(state) = tokio::task::spawn_blocking(move || reader.read()).await?? else {
+        return Ok(false);
+    };
     
-    let content = fs::read_to_string(path).await?;
-    let state: StateFile = serde_json::from_str(&content)?;
+    restore_state(queue, state).await;
+    Ok(true)
+}
+
+async fn restore_state(queue: &QueueManager, state: StateFile) {
+    queue.set_previous_runtime_seconds(state.total_runtime_seconds);
     
     // Restore processes
-    let mut all_processes = queue.all_processes.write().await;
     let mut active = queue.active.write().await;
     let mut synced = queue.synced.write().await;
     let mut queued = queue.queued.write().await;
+    let mut all_processes = queue.all_processes.write().await;
     
     // First, restore all processes to all_processes map
     for (id, data) in &state.processes {
-        let mut status = crate::models::ProcessStatus {
-            name: id.clone(), // Will be updated when config is loaded
+        let status = crate::models::ProcessStatus {
+            // Older state files have no name; it is updated when config is loaded
+            name: if data.name.is_empty() { id.clone() } else { data.name.clone() },
             process_id: id.clone(),
+            base_url: data.base_url.clone(),
             state: data.state.clone(),
             cron_initialized: data.cron_initialized,
             computed_slot: data.computed_slot,
             current_slot: data.current_slot,
             last_checked: data.last_checked,
-            error: None,
+            last_advanced_at: data.last_advanced_at,
+            error: data.error.clone(),
+            error_count: data.error_count,
+            last_error_at: data.last_error_at,
+            init_attempts: data.init_attempts,
             metrics: crate::models::ProcessMetrics {
                 initial_slot_deficit: data.metrics.initial_slot_deficit,
                 slots_advanced_last_check: 0,
@@ -86,16 +309,28 @@ pub async fn load_state(queue: &QueueManager) -> Result<bool> {
                 sync_start_time: data.metrics.sync_start_time,
                 sync_end_time: data.metrics.sync_end_time,
                 avg_sync_rate: data.metrics.avg_sync_rate,
+                instant_sync_rate: None,
                 check_count: data.metrics.check_count,
-                api_response_times: Vec::new(),
+                checks_without_progress: 0,
+                api_response_times: data.metrics.api_response_times.clone(),
+                p50_response_time: None,
+                p95_response_time: None,
+                time_to_sync_seconds: None,
+                slot_history: Vec::new(),
             },
             queue_position: None,
+            added_at: data.added_at,
             activated_at: data.activated_at,
             synced_at: data.synced_at,
-            hb_reserves: None,
-            ao_reserves: None,
-            reserves_last_checked: None,
+            total_active_seconds: data.total_active_seconds,
+            hb_reserves: data.hb_reserves.clone(),
+            ao_reserves: data.ao_reserves.clone(),
+            reserves_last_checked: data.reserves_last_checked,
+            paused: data.paused,
+            activity_log: Default::default(),
             cron_created_at: None,
+            lifecycle: None,
+            reserve_diffs: None,
         };
         
         match data.state {
@@ -126,7 +361,7 @@ pub async fn load_state(queue: &QueueManager) -> Result<bool> {
                 let config = crate::models::ProcessConfig {
                     name: status.name.clone(),
                     process_id: process_id.clone(),
-                    base_url: None, // Will be updated from config if provided
+                    base_url: status.base_url.clone(),
                 };
                 queued.push_back(config);
             }
@@ -149,7 +384,7 @@ pub async fn load_state(queue: &QueueManager) -> Result<bool> {
                 let config = crate::models::ProcessConfig {
                     name: status.name.clone(),
                     process_id: process_id.clone(),
-                    base_url: None, // Will be updated from config if provided
+                    base_url: status.base_url.clone(),
                 };
                 queued.push_back(config);
             }
@@ -159,6 +394,456 @@ pub async fn load_state(queue: &QueueManager) -> Result<bool> {
             tracing::info!("Restored {} queued processes from legacy state format", queued_processes.len());
         }
     }
-    
-    Ok(true)
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::QueueStrategy;
+    use crate::models::ProcessConfig;
+
+    fn manager() -> QueueManager {
+        QueueManager::new(10, 0, 0, Vec::new(), Vec::new(), QueueStrategy::Fifo)
+    }
+
+    pub(super) fn state_config(dir: &tempfile::TempDir, compact: bool, gzip: bool) -> StateConfig {
+        StateConfig {
+            path: dir.path().join("state.json").to_string_lossy().into_owned(),
+            compact,
+            gzip,
+            ..StateConfig::default()
+        }
+    }
+
+    pub(super) async fn sample_state(process_ids: &[&str]) -> StateFile {
+        let queue = manager();
+        for id in process_ids {
+            queue.add_to_queue(ProcessConfig {
+                name: format!("pool-{}", id),
+                process_id: id.to_string(),
+                base_url: Some("http://node".to_string()),
+            }).await.unwrap();
+        }
+        build_state(&queue, &StateConfig::default()).await
+    }
+
+    pub(super) fn as_json(state: &StateFile) -> serde_json::Value {
+        serde_json::to_value(state).unwrap()
+    }
+
+    /// Save `queue` and load it into a fresh manager, as a restart would.
+    async fn restart(queue: &QueueManager) -> QueueManager {
+        let json = serde_json::to_string(&build_state(queue, &StateConfig::default()).await).unwrap();
+        let restored = manager();
+        restore_state(&restored, serde_json::from_str(&json).unwrap()).await;
+        restored
+    }
+
+    #[tokio::test]
+    async fn runtime_accumulates_across_restarts() {
+        let first = manager();
+        first.set_previous_runtime_seconds(3600);
+        first.add_to_queue(ProcessConfig { name: "pool".to_string(), process_id: "a".to_string(), base_url: None }).await.unwrap();
+        first.activate_next().await.unwrap();
+        first.update_process_status("a", |status| {
+            status.activated_at = Some(Utc::now() - chrono::Duration::seconds(120));
+        }).await.unwrap();
+
+        // Restart: the new run starts from the saved totals
+        let second = restart(&first).await;
+        assert!(second.total_runtime_seconds() >= 3600);
+        assert!(second.all_processes.read().await["a"].active_seconds() >= 120);
+        tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+
+        let third = restart(&second).await;
+        assert!(third.total_runtime_seconds() > 3600);
+        assert!(build_state(&third, &StateConfig::default()).await.total_runtime_seconds > 3600);
+    }
+
+    #[tokio::test]
+    async fn compact_and_gzip_formats_round_trip() {
+        let state = sample_state(&["a", "b"]).await;
+        for (compact, gzip) in [(false, false), (true, false), (false, true), (true, true)] {
+            let dir = tempfile::tempdir().unwrap();
+            let store = JsonStateStore::from_config(&state_config(&dir, compact, gzip));
+            store.write(&state).unwrap();
+
+            let bytes = fs::read(store.file_path()).unwrap();
+            assert_eq!(bytes.starts_with(&GZIP_MAGIC), gzip);
+            if !gzip {
+                assert_eq!(bytes.contains(&b'\n'), !compact);
+            }
+            assert_eq!(as_json(&store.read().unwrap().unwrap()), as_json(&state));
+        }
+    }
+
+    #[tokio::test]
+    async fn toggling_gzip_does_not_load_the_stale_format() {
+        let dir = tempfile::tempdir().unwrap();
+        let plain = JsonStateStore::from_config(&state_config(&dir, false, false));
+        let gzipped = JsonStateStore::from_config(&state_config(&dir, false, true));
+
+        plain.write(&sample_state(&["old"]).await).unwrap();
+        // Switching to gzip still finds the plain file
+        assert!(gzipped.read().unwrap().unwrap().processes.contains_key("old"));
+
+        gzipped.write(&sample_state(&["new"]).await).unwrap();
+        assert!(!plain.file_path().exists());
+
+        // Switching back loads the newer gzipped state rather than the old plain one
+        assert!(plain.read().unwrap().unwrap().processes.contains_key("new"));
+        plain.write(&sample_state(&["newest"]).await).unwrap();
+        assert!(!gzipped.file_path().exists());
+        assert!(plain.read().unwrap().unwrap().processes.contains_key("newest"));
+    }
+
+    /// A queue with processes in every state and most persisted fields filled in.
+    async fn populated_queue() -> QueueManager {
+        let queue = manager();
+        for id in ["active", "synced", "errored", "queued-1", "queued-2"] {
+            queue.add_to_queue(ProcessConfig {
+                name: format!("pool-{}", id),
+                process_id: id.to_string(),
+                base_url: Some(format!("http://{}.node", id)),
+            }).await.unwrap();
+        }
+        for _ in 0..3 {
+            queue.activate_next().await.unwrap();
+        }
+        for id in ["active", "synced", "errored"] {
+            queue.update_process_status(id, |status| {
+                status.cron_initialized = true;
+                status.computed_slot = Some(100);
+                status.current_slot = Some(150);
+                status.last_checked = Some(Utc::now());
+                status.metrics.initial_slot_deficit = Some(500);
+                status.metrics.total_slots_advanced = 450;
+                status.metrics.avg_sync_rate = 12.5;
+                status.metrics.check_count = 9;
+                status.hb_reserves = Some(HashMap::from([("token".to_string(), "1000".to_string())]));
+                status.ao_reserves = Some(HashMap::from([("token".to_string(), "1000.0".to_string())]));
+            }).await.unwrap();
+        }
+        queue.mark_synced("synced").await.unwrap();
+        queue.mark_error("errored", "cron failed".to_string()).await.unwrap();
+        queue.set_paused("queued-2", true).await.unwrap();
+        queue
+    }
+
+    /// The loaded state, without the fields that differ between saves.
+    async fn comparable(queue: &QueueManager) -> serde_json::Value {
+        let mut state = build_state(queue, &StateConfig::default()).await;
+        state.last_updated = chrono::DateTime::UNIX_EPOCH;
+        state.total_runtime_seconds = 0;
+        state.active_process_ids.sort();
+        state.synced_process_ids.sort();
+        as_json(&state)
+    }
+
+    #[tokio::test]
+    async fn save_and_load_restore_every_state() {
+        let original = populated_queue().await;
+        let dir = tempfile::tempdir().unwrap();
+        let config = state_config(&dir, false, false);
+        let store = open_store(&config).unwrap();
+        save_state(&store, &original, &config).await.unwrap();
+
+        let queue = manager();
+        assert!(load_state(&store, &queue).await.unwrap());
+        assert_eq!(comparable(&queue).await, comparable(&original).await);
+        assert_eq!(queue.get_status().await, (1, 2, 1));
+        let queued: Vec<_> = queue.queued.read().await.iter().map(|c| c.process_id.clone()).collect();
+        assert_eq!(queued, ["queued-1", "queued-2"]);
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn json_and_sqlite_backends_load_the_same_state() {
+        let original = populated_queue().await;
+        let dir = tempfile::tempdir().unwrap();
+
+        let mut loaded = Vec::new();
+        for backend in [StateBackend::Json, StateBackend::Sqlite] {
+            let config = StateConfig {
+                backend,
+                sqlite_path: dir.path().join("state.db").to_string_lossy().into_owned(),
+                ..state_config(&dir, false, false)
+            };
+            let store = open_store(&config).unwrap();
+            save_state(&store, &original, &config).await.unwrap();
+
+            let queue = manager();
+            assert!(load_state(&store, &queue).await.unwrap());
+            loaded.push(comparable(&queue).await);
+        }
+
+        assert_eq!(loaded[0], loaded[1]);
+        assert_eq!(loaded[0], comparable(&original).await);
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn peeking_sqlite_state_does_not_create_or_migrate_the_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = StateConfig {
+            backend: StateBackend::Sqlite,
+            sqlite_path: dir.path().join("state.db").to_string_lossy().into_owned(),
+            ..state_config(&dir, false, false)
+        };
+        JsonStateStore::from_config(&config).write(&sample_state(&["a"]).await).unwrap();
+
+        let state = peek_state(&config).unwrap().unwrap();
+        assert!(state.processes.contains_key("a"));
+        assert!(!Path::new(&config.sqlite_path).exists());
+    }
+
+    /// A synced process as written by versions that predate most optional fields.
+    const LEGACY_STATE: &str = r#"{
+        "version": "1.0",
+        "last_updated": "2024-01-01T00:00:00Z",
+        "queued_process_ids": [],
+        "active_process_ids": [],
+        "synced_process_ids": ["old"],
+        "processes": {
+            "old": {
+                "state": "synced",
+                "cron_initialized": true,
+                "computed_slot": 10,
+                "current_slot": 10,
+                "last_checked": null,
+                "synced_at": null,
+                "activated_at": null,
+                "metrics": {
+                    "initial_slot_deficit": null,
+                    "total_slots_advanced": 0,
+                    "sync_start_time": null,
+                    "sync_end_time": null,
+                    "avg_sync_rate": 0.0,
+                    "check_count": 0
+                }
+            }
+        }
+    }"#;
+
+    #[tokio::test]
+    async fn reserves_survive_a_restart() {
+        let checked = Utc::now() - chrono::Duration::minutes(3);
+        let hb = HashMap::from([("token".to_string(), "1000".to_string())]);
+        let ao = HashMap::from([("token".to_string(), "999".to_string())]);
+        let original = manager();
+        original.add_to_queue(ProcessConfig { name: "pool".to_string(), process_id: "a".to_string(), base_url: None }).await.unwrap();
+        original.update_process_status("a", |status| {
+            status.hb_reserves = Some(hb.clone());
+            status.ao_reserves = Some(ao.clone());
+            status.reserves_last_checked = Some(checked);
+        }).await.unwrap();
+
+        let queue = restart(&original).await;
+        let process = queue.all_processes.read().await["a"].clone();
+        assert_eq!(process.hb_reserves, Some(hb));
+        assert_eq!(process.ao_reserves, Some(ao));
+        assert_eq!(process.reserves_last_checked, Some(checked));
+    }
+
+    #[tokio::test]
+    async fn capped_response_times_survive_a_restart_only_when_enabled() {
+        let original = manager();
+        original.add_to_queue(ProcessConfig { name: "pool".to_string(), process_id: "a".to_string(), base_url: None }).await.unwrap();
+        original.update_process_status("a", |status| {
+            for millis in 1..=30 {
+                status.metrics.record_response_time(millis as f64, 20);
+            }
+        }).await.unwrap();
+        let expected: Vec<f64> = (11..=30).map(|millis| millis as f64).collect();
+
+        for persist in [true, false] {
+            let dir = tempfile::tempdir().unwrap();
+            let config = StateConfig {
+                persist_response_times: persist,
+                ..state_config(&dir, false, false)
+            };
+            let store = open_store(&config).unwrap();
+            save_state(&store, &original, &config).await.unwrap();
+
+            let queue = manager();
+            load_state(&store, &queue).await.unwrap();
+            let restored = queue.get_process("a").await.unwrap().metrics;
+            if persist {
+                assert_eq!(restored.api_response_times, expected);
+                assert_eq!(restored.p50_response_time, Some(20.0));
+            } else {
+                assert!(restored.api_response_times.is_empty());
+                assert_eq!(restored.p50_response_time, None);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn state_files_without_reserves_still_load() {
+        let queue = manager();
+        restore_state(&queue, serde_json::from_str(LEGACY_STATE).unwrap()).await;
+        let process = queue.all_processes.read().await["old"].clone();
+        assert_eq!(process.state, ProcessState::Synced);
+        assert_eq!((process.hb_reserves, process.ao_reserves, process.reserves_last_checked), (None, None, None));
+    }
+
+    #[tokio::test]
+    async fn names_and_errors_survive_a_restart() {
+        let original = manager();
+        original.add_to_queue(ProcessConfig { name: "USDC pool".to_string(), process_id: "a".to_string(), base_url: None }).await.unwrap();
+        original.activate_next().await.unwrap();
+        original.mark_error("a", "cron init failed: HTTP 500".to_string()).await.unwrap();
+
+        let queue = restart(&original).await;
+        let process = queue.all_processes.read().await["a"].clone();
+        assert_eq!(process.name, "USDC pool");
+        assert_eq!(process.state, ProcessState::Error);
+        assert_eq!(process.error.as_deref(), Some("cron init failed: HTTP 500"));
+        assert_eq!(process.error_count, 1);
+    }
+
+    #[tokio::test]
+    async fn state_files_without_names_fall_back_to_the_process_id() {
+        let queue = manager();
+        restore_state(&queue, serde_json::from_str(LEGACY_STATE).unwrap()).await;
+        let process = queue.all_processes.read().await["old"].clone();
+        assert_eq!(process.name, "old");
+        assert_eq!(process.error, None);
+    }
+
+    #[tokio::test]
+    async fn state_is_saved_at_the_configured_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_at = |name: &str| StateConfig {
+            path: dir.path().join(name).to_string_lossy().into_owned(),
+            ..StateConfig::default()
+        };
+        let (first, second) = (config_at("first.json"), config_at("second.json"));
+        let (first_store, second_store) = (open_store(&first).unwrap(), open_store(&second).unwrap());
+
+        let queue = manager();
+        queue.add_to_queue(ProcessConfig { name: "pool".to_string(), process_id: "a".to_string(), base_url: None }).await.unwrap();
+        save_state(&first_store, &queue, &first).await.unwrap();
+        assert!(dir.path().join("first.json").exists());
+
+        // A second instance with its own path neither sees nor clobbers the first
+        assert!(!load_state(&second_store, &manager()).await.unwrap());
+        save_state(&second_store, &manager(), &second).await.unwrap();
+        let loaded = manager();
+        assert!(load_state(&first_store, &loaded).await.unwrap());
+        assert!(loaded.get_process("a").await.is_some());
+    }
+
+    #[tokio::test]
+    async fn a_half_written_temp_file_does_not_replace_the_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = JsonStateStore::from_config(&state_config(&dir, false, false));
+        store.write(&sample_state(&["saved"]).await).unwrap();
+
+        // A write that died before the rename leaves only a truncated temp file
+        let tmp_path = with_suffix(&store.file_path(), ".tmp");
+        let full = serde_json::to_vec(&sample_state(&["lost"]).await).unwrap();
+        fs::write(&tmp_path, &full[..full.len() / 2]).unwrap();
+
+        assert!(store.read().unwrap().unwrap().processes.contains_key("saved"));
+        store.write(&sample_state(&["next"]).await).unwrap();
+        assert!(!tmp_path.exists());
+        assert!(store.read().unwrap().unwrap().processes.contains_key("next"));
+    }
+
+    #[tokio::test]
+    async fn a_corrupt_state_file_is_set_aside_and_loading_starts_fresh() {
+        let dir = tempfile::tempdir().unwrap();
+        let json_store = JsonStateStore::from_config(&state_config(&dir, false, false));
+        let path = json_store.file_path();
+        fs::write(&path, "{\"version\": \"2.0\", \"proc").unwrap();
+
+        let store: Arc<dyn StateStore> = Arc::new(json_store);
+        assert!(!load_state(&store, &manager()).await.unwrap());
+        assert!(!path.exists());
+        assert!(path.with_extension("json.corrupt").exists());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn states_built_during_requeues_place_every_process_once() {
+        let queue = Arc::new(manager());
+        let ids: Vec<String> = (0..10).map(|i| format!("p{}", i)).collect();
+        for id in &ids {
+            queue.add_to_queue(ProcessConfig { name: id.clone(), process_id: id.clone(), base_url: None }).await.unwrap();
+            queue.activate_next().await.unwrap();
+        }
+
+        let requeuer = queue.clone();
+        let requeues = tokio::spawn(async move {
+            for _ in 0..500 {
+                for id in (0..10).map(|i| format!("p{}", i)) {
+                    requeuer.requeue(ProcessConfig { name: id.clone(), process_id: id, base_url: None }).await.unwrap();
+                    requeuer.activate_next().await.unwrap();
+                }
+            }
+        });
+        while !requeues.is_finished() {
+            let state = build_state(&queue, &StateConfig::default()).await;
+            let mut placed: Vec<&String> = state.active_process_ids.iter()
+                .chain(&state.synced_process_ids)
+                .chain(&state.queued_process_ids)
+                .collect();
+            placed.sort();
+            assert_eq!(placed, ids.iter().collect::<Vec<_>>());
+        }
+        requeues.await.unwrap();
+    }
+
+    #[derive(Default)]
+    struct CountingStore {
+        writes: std::sync::atomic::AtomicUsize,
+    }
+
+    impl StateStore for CountingStore {
+        fn write(&self, _state: &StateFile) -> Result<()> {
+            self.writes.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            Ok(())
+        }
+
+        fn read(&self) -> Result<Option<StateFile>> {
+            Ok(None)
+        }
+    }
+
+    #[tokio::test]
+    async fn unchanged_state_is_not_rewritten() {
+        let counting = Arc::new(CountingStore::default());
+        let store: Arc<dyn StateStore> = counting.clone();
+        let writes = || counting.writes.load(std::sync::atomic::Ordering::Relaxed);
+        let queue = manager();
+        let config = StateConfig::default();
+        let add = |id: &str| queue.add_to_queue(ProcessConfig {
+            name: format!("pool-{}", id),
+            process_id: id.to_string(),
+            base_url: None,
+        });
+
+        assert!(!save_state_if_changed(&store, &queue, &config).await.unwrap());
+        assert_eq!(writes(), 0);
+
+        add("a").await.unwrap();
+        assert!(save_state_if_changed(&store, &queue, &config).await.unwrap());
+        assert_eq!(writes(), 1);
+        for _ in 0..3 {
+            assert!(!save_state_if_changed(&store, &queue, &config).await.unwrap());
+        }
+        assert_eq!(writes(), 1);
+
+        // A rejected change leaves nothing to save
+        add("a").await.unwrap_err();
+        assert!(!save_state_if_changed(&store, &queue, &config).await.unwrap());
+        queue.remove_process("a").await.unwrap();
+        assert!(save_state_if_changed(&store, &queue, &config).await.unwrap());
+        assert_eq!(writes(), 2);
+
+        // Unconditional saves still write, and count as saved
+        save_state(&store, &queue, &config).await.unwrap();
+        assert!(!save_state_if_changed(&store, &queue, &config).await.unwrap());
+        assert_eq!(writes(), 3);
+    }
+}
@@ -0,0 +1,188 @@
+use super::{JsonStateStore, StateStore};
+use crate::models::{ProcessStatusData, StateFile};
+use anyhow::{anyhow, Result};
+use rusqlite::{params, Connection};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Stores the state in SQLite, one row per process, so saves only touch the rows
+/// that changed instead of rewriting the whole file.
+pub struct SqliteStateStore {
+    conn: Mutex<Connection>,
+}
+
+impl SqliteStateStore {
+    pub fn open(path: &str) -> Result<Self> {
+        let conn = Connection::open(path)?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS meta (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS processes (
+                process_id TEXT PRIMARY KEY,
+                data TEXT NOT NULL
+            );",
+        )?;
+
+        Ok(Self { conn: Mutex::new(conn) })
+    }
+
+    /// Import an existing JSON state file if the database is still empty.
+    pub fn migrate_from_json(&self, json: &JsonStateStore) -> Result<()> {
+        if self.read()?.is_some() {
+            return Ok(());
+        }
+
+        if let Some(state) = json.read()? {
+            tracing::info!("Migrating {} processes from JSON state to SQLite", state.processes.len());
+            self.write(&state)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl StateStore for SqliteStateStore {
+    fn write(&self, state: &StateFile) -> Result<()> {
+        let mut conn = self.conn.lock().map_err(|_| anyhow!("SQLite connection lock poisoned"))?;
+        let tx = conn.transaction()?;
+
+        {
+            let mut upsert_meta = tx.prepare(
+                "INSERT INTO meta (key, value) VALUES (?1, ?2)
+                 ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            )?;
+            upsert_meta.execute(params!["version", state.version])?;
+            upsert_meta.execute(params!["last_updated", serde_json::to_string(&state.last_updated)?])?;
+            upsert_meta.execute(params!["queued_process_ids", serde_json::to_string(&state.queued_process_ids)?])?;
+            upsert_meta.execute(params!["active_process_ids", serde_json::to_string(&state.active_process_ids)?])?;
+            upsert_meta.execute(params!["synced_process_ids", serde_json::to_string(&state.synced_process_ids)?])?;
+            upsert_meta.execute(params!["total_runtime_seconds", state.total_runtime_seconds.to_string()])?;
+
+            // Rows whose data is unchanged are left alone
+            let mut upsert = tx.prepare(
+                "INSERT INTO processes (process_id, data) VALUES (?1, ?2)
+                 ON CONFLICT(process_id) DO UPDATE SET data = excluded.data
+                 WHERE excluded.data != processes.data",
+            )?;
+            for (id, data) in &state.processes {
+                upsert.execute(params![id, serde_json::to_string(data)?])?;
+            }
+
+            // Drop processes that are no longer tracked
+            let mut ids = tx.prepare("SELECT process_id FROM processes")?;
+            let stored: Vec<String> = ids.query_map([], |row| row.get(0))?.collect::<Result<_, _>>()?;
+            let mut delete = tx.prepare("DELETE FROM processes WHERE process_id = ?1")?;
+            for id in stored.iter().filter(|id| !state.processes.contains_key(*id)) {
+                delete.execute(params![id])?;
+            }
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn read(&self) -> Result<Option<StateFile>> {
+        let conn = self.conn.lock().map_err(|_| anyhow!("SQLite connection lock poisoned"))?;
+
+        let mut meta_stmt = conn.prepare("SELECT key, value FROM meta")?;
+        let meta: HashMap<String, String> = meta_stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<_, _>>()?;
+        if meta.is_empty() {
+            return Ok(None);
+        }
+
+        let mut process_stmt = conn.prepare("SELECT process_id, data FROM processes")?;
+        let mut processes = HashMap::new();
+        for row in process_stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))? {
+            let (id, data) = row?;
+            let data: ProcessStatusData = serde_json::from_str(&data)?;
+            processes.insert(id, data);
+        }
+
+        let get = |key: &str| meta.get(key).ok_or_else(|| anyhow!("Missing `{}` in SQLite state", key));
+
+        Ok(Some(StateFile {
+            version: get("version")?.clone(),
+            last_updated: serde_json::from_str(get("last_updated")?)?,
+            queued_process_ids: serde_json::from_str(get("queued_process_ids")?)?,
+            active_process_ids: serde_json::from_str(get("active_process_ids")?)?,
+            synced_process_ids: serde_json::from_str(get("synced_process_ids")?)?,
+            total_runtime_seconds: get("total_runtime_seconds")?.parse()?,
+            processes,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::tests::{as_json, sample_state, state_config};
+
+    fn open(dir: &tempfile::TempDir) -> SqliteStateStore {
+        SqliteStateStore::open(&dir.path().join("state.db").to_string_lossy()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn write_then_read_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = open(&dir);
+        assert!(store.read().unwrap().is_none());
+
+        let state = sample_state(&["a", "b", "c"]).await;
+        store.write(&state).unwrap();
+        assert_eq!(as_json(&store.read().unwrap().unwrap()), as_json(&state));
+
+        // Processes no longer tracked are deleted
+        let state = sample_state(&["a"]).await;
+        store.write(&state).unwrap();
+        assert_eq!(as_json(&store.read().unwrap().unwrap()), as_json(&state));
+    }
+
+    #[tokio::test]
+    async fn unchanged_rows_are_not_rewritten() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = open(&dir);
+        let mut state = sample_state(&["a", "b", "c"]).await;
+        store.write(&state).unwrap();
+
+        let changes = |store: &SqliteStateStore, state: &StateFile| {
+            let before = store.conn.lock().unwrap().total_changes();
+            store.write(state).unwrap();
+            store.conn.lock().unwrap().total_changes() - before
+        };
+        let unchanged = changes(&store, &state);
+        state.processes.get_mut("b").unwrap().computed_slot = Some(99);
+        assert_eq!(changes(&store, &state), unchanged + 1);
+        assert_eq!(store.read().unwrap().unwrap().processes["b"].computed_slot, Some(99));
+    }
+
+    #[tokio::test]
+    async fn migrates_json_state_only_into_an_empty_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let json = JsonStateStore::from_config(&state_config(&dir, false, false));
+        let state = sample_state(&["a", "b"]).await;
+        json.write(&state).unwrap();
+
+        let store = open(&dir);
+        store.migrate_from_json(&json).unwrap();
+        assert_eq!(as_json(&store.read().unwrap().unwrap()), as_json(&state));
+
+        // Once the database has state, the JSON file is no longer imported
+        json.write(&sample_state(&["other"]).await).unwrap();
+        store.migrate_from_json(&json).unwrap();
+        assert_eq!(as_json(&store.read().unwrap().unwrap()), as_json(&state));
+    }
+
+    #[tokio::test]
+    async fn migrating_without_a_json_file_leaves_the_database_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let json = JsonStateStore::from_config(&state_config(&dir, false, false));
+        let store = open(&dir);
+        store.migrate_from_json(&json).unwrap();
+        assert!(store.read().unwrap().is_none());
+    }
+}
+
@@ -0,0 +1,92 @@
+//! Helpers shared by the unit tests: a local mock HyperBEAM node and clients pointed at it.
+
+use crate::config::ServiceConfig;
+use crate::hyperbeam::HyperBeamClient;
+use axum::extract::{Request, State};
+use axum::middleware::{self, Next};
+use axum::routing::get;
+use axum::Router;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Serve `router` on an ephemeral local port and return its base URL.
+pub async fn serve(router: Router) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, router).await.unwrap();
+    });
+    format!("http://{}", addr)
+}
+
+/// Requests seen by a router wrapped with [`record_requests`], as `"<METHOD> <path and query>"`.
+#[derive(Clone, Default)]
+pub struct RequestLog(Arc<Mutex<Vec<String>>>);
+
+impl RequestLog {
+    pub fn requests(&self) -> Vec<String> {
+        self.0.lock().unwrap().clone()
+    }
+
+    pub fn contains(&self, needle: &str) -> bool {
+        self.requests().iter().any(|request| request.contains(needle))
+    }
+}
+
+pub fn record_requests(router: Router) -> (Router, RequestLog) {
+    let log = RequestLog::default();
+    let recorder = log.clone();
+    let router = router.layer(middleware::from_fn(move |request: Request, next: Next| {
+        let recorder = recorder.clone();
+        async move {
+            let path = request.uri().path_and_query().map(|p| p.to_string()).unwrap_or_default();
+            recorder.0.lock().unwrap().push(format!("{} {}", request.method(), path));
+            next.run(request).await
+        }
+    }));
+    (router, log)
+}
+
+/// Slots served by [`slot_node`]; tests move them between checks.
+#[derive(Clone, Default)]
+pub struct MockSlots {
+    computed: Arc<AtomicU64>,
+    current: Arc<AtomicU64>,
+}
+
+impl MockSlots {
+    pub fn new(computed: u64, current: u64) -> Self {
+        let slots = Self::default();
+        slots.set(computed, current);
+        slots
+    }
+
+    pub fn set(&self, computed: u64, current: u64) {
+        self.computed.store(computed, Ordering::SeqCst);
+        self.current.store(current, Ordering::SeqCst);
+    }
+}
+
+/// Routes answering the computed and current slot requests of any process.
+pub fn slot_node(slots: MockSlots) -> Router {
+    Router::new()
+        .route("/:process/compute/at-slot", get(|State(slots): State<MockSlots>| async move {
+            slots.computed.load(Ordering::SeqCst).to_string()
+        }))
+        .route("/:process/slot/current", get(|State(slots): State<MockSlots>| async move {
+            slots.current.load(Ordering::SeqCst).to_string()
+        }))
+        .with_state(slots)
+}
+
+/// Default config with HyperBEAM and the AO CU both pointed at `base_url`.
+pub fn config_for(base_url: &str) -> ServiceConfig {
+    let mut config = ServiceConfig::default();
+    config.hyperbeam.base_url = base_url.to_string();
+    config.ao.cu_url = base_url.to_string();
+    config
+}
+
+pub fn client_for(config: &ServiceConfig) -> HyperBeamClient {
+    HyperBeamClient::new(&config.hyperbeam, config.ao.cu_url.clone(), config.reserves.clone())
+}
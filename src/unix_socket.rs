@@ -0,0 +1,89 @@
+use anyhow::{anyhow, Result};
+use axum::{extract::Request, Router};
+use hyper::body::Incoming;
+use hyper_util::rt::{TokioExecutor, TokioIo};
+use hyper_util::server::conn::auto::Builder;
+use hyper_util::server::graceful::GracefulShutdown;
+use std::future::Future;
+use std::os::unix::fs::FileTypeExt;
+use tokio::net::UnixListener;
+use tower::Service;
+
+/// Remove a socket file left behind by a previous run. Anything else at `path` is
+/// left alone and reported, so a typo in the config cannot delete a regular file.
+fn remove_stale_socket(path: &str) -> Result<()> {
+    match std::fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.file_type().is_socket() => {
+            std::fs::remove_file(path)?;
+            Ok(())
+        }
+        Ok(_) => Err(anyhow!("{} exists and is not a socket", path)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Serve `app` on a Unix domain socket at `path` until `shutdown` resolves, then let
+/// in-flight connections finish and remove the socket file.
+pub async fn serve(path: &str, app: Router, shutdown: impl Future<Output = ()>) -> Result<()> {
+    remove_stale_socket(path)?;
+    let listener = UnixListener::bind(path)?;
+
+    let builder = Builder::new(TokioExecutor::new());
+    let graceful = GracefulShutdown::new();
+    tokio::pin!(shutdown);
+
+    loop {
+        let socket = tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok((socket, _)) => socket,
+                Err(e) => {
+                    tracing::warn!("Failed to accept connection on {}: {}", path, e);
+                    continue;
+                }
+            },
+            _ = &mut shutdown => break,
+        };
+
+        let router = app.clone();
+        let service = hyper::service::service_fn(move |request: Request<Incoming>| {
+            router.clone().call(request)
+        });
+        let connection = graceful.watch(
+            builder.serve_connection_with_upgrades(TokioIo::new(socket), service).into_owned()
+        );
+
+        tokio::spawn(async move {
+            if let Err(e) = connection.await {
+                tracing::debug!("Unix socket connection closed with error: {}", e);
+            }
+        });
+    }
+
+    drop(listener);
+    graceful.shutdown().await;
+    let _ = std::fs::remove_file(path);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_stale_sockets_are_removed() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = dir.path().join("missing.sock");
+        assert!(remove_stale_socket(missing.to_str().unwrap()).is_ok());
+
+        let socket = dir.path().join("stale.sock");
+        drop(std::os::unix::net::UnixListener::bind(&socket).unwrap());
+        remove_stale_socket(socket.to_str().unwrap()).unwrap();
+        assert!(!socket.exists());
+
+        let file = dir.path().join("config.yaml");
+        std::fs::write(&file, "keep me").unwrap();
+        assert!(remove_stale_socket(file.to_str().unwrap()).is_err());
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "keep me");
+    }
+}
@@ -0,0 +1,146 @@
+use crate::config::ServiceConfig;
+use crate::models::Config;
+use crate::state;
+use std::collections::HashSet;
+
+/// Result of checking a pool config file against itself and the persisted state.
+#[derive(Debug, Default)]
+pub struct ValidationReport {
+    pub problems: Vec<String>,
+    pub to_add: Vec<String>,
+    pub to_update: Vec<String>,
+}
+
+/// Check for duplicate process IDs and empty names or IDs, and work out which
+/// processes would be added or updated given the IDs already known.
+pub fn validate_pool_config(config: &Config, known_ids: &HashSet<String>) -> ValidationReport {
+    let mut report = ValidationReport::default();
+    let mut seen = HashSet::new();
+    
+    for (idx, process) in config.processes.iter().enumerate() {
+        if process.process_id.trim().is_empty() {
+            report.problems.push(format!("processes[{}] ({}) has an empty processId", idx, process.name));
+            continue;
+        }
+        if process.name.trim().is_empty() {
+            report.problems.push(format!("processes[{}] ({}) has an empty name", idx, process.process_id));
+        }
+        if !seen.insert(process.process_id.as_str()) {
+            report.problems.push(format!("processes[{}] duplicates processId {}", idx, process.process_id));
+            continue;
+        }
+        
+        if known_ids.contains(&process.process_id) {
+            report.to_update.push(process.process_id.clone());
+        } else {
+            report.to_add.push(process.process_id.clone());
+        }
+    }
+    
+    report
+}
+
+/// Validate the pool config at `path` and print a summary. Returns the process exit code.
+pub fn run(path: &str, service_config: &ServiceConfig) -> i32 {
+    let config: Config = match std::fs::read_to_string(path)
+        .map_err(anyhow::Error::from)
+        .and_then(|contents| serde_json::from_str(&contents).map_err(anyhow::Error::from))
+    {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{}: {}", path, e);
+            return 1;
+        }
+    };
+    
+    let known_ids = match state::peek_process_ids(&service_config.state) {
+        Ok(ids) => ids,
+        Err(e) => {
+            eprintln!("Failed to read existing state: {}", e);
+            return 1;
+        }
+    };
+    
+    let report = validate_pool_config(&config, &known_ids);
+    println!("{}: {} processes", path, config.processes.len());
+    println!("  would add:    {}", report.to_add.len());
+    println!("  would update: {}", report.to_update.len());
+    
+    if report.problems.is_empty() {
+        println!("OK");
+        0
+    } else {
+        for problem in &report.problems {
+            eprintln!("  - {}", problem);
+        }
+        eprintln!("{} problem(s) found", report.problems.len());
+        1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::config_for;
+
+    fn parse(json: &str) -> Config {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn a_valid_config_reports_adds_and_updates() {
+        let config = parse(r#"{"processes": [
+            {"name": "new", "processId": "a"},
+            {"name": "known", "processId": "b", "baseUrl": "http://node"}
+        ]}"#);
+        let known = HashSet::from(["b".to_string()]);
+
+        let report = validate_pool_config(&config, &known);
+        assert!(report.problems.is_empty(), "{:?}", report.problems);
+        assert_eq!(report.to_add, ["a"]);
+        assert_eq!(report.to_update, ["b"]);
+    }
+
+    #[test]
+    fn duplicate_and_empty_ids_are_problems() {
+        let config = parse(r#"{"processes": [
+            {"name": "first", "processId": "a"},
+            {"name": "second", "processId": "a"},
+            {"name": "blank", "processId": " "},
+            {"name": "", "processId": "c"}
+        ]}"#);
+
+        let report = validate_pool_config(&config, &HashSet::new());
+        assert_eq!(report.problems, [
+            "processes[1] duplicates processId a",
+            "processes[2] (blank) has an empty processId",
+            "processes[3] (c) has an empty name",
+        ]);
+        assert_eq!(report.to_add, ["a", "c"]);
+    }
+
+    #[test]
+    fn run_exits_non_zero_only_on_problems() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut service_config = config_for("http://127.0.0.1:9");
+        service_config.state.path = dir.path().join("state.json").to_string_lossy().into_owned();
+        let write = |name: &str, contents: &str| {
+            let path = dir.path().join(name);
+            std::fs::write(&path, contents).unwrap();
+            path.to_string_lossy().into_owned()
+        };
+
+        let valid = write("valid.json", r#"{"processes": [{"name": "pool", "processId": "a"}]}"#);
+        let duplicate = write("duplicate.json", r#"{"processes": [
+            {"name": "pool", "processId": "a"},
+            {"name": "again", "processId": "a"}
+        ]}"#);
+        let malformed = write("malformed.json", r#"{"processes": ["#);
+
+        assert_eq!(run(&valid, &service_config), 0);
+        assert_eq!(run(&duplicate, &service_config), 1);
+        assert_eq!(run(&malformed, &service_config), 1);
+        // Nothing is written to the state path
+        assert!(!dir.path().join("state.json").exists());
+    }
+}